@@ -16,8 +16,9 @@ use tokio::process::Command;
 use tracing::{debug, error, info};
 
 use super::{Backend, BackendState, STATE_HEALTHY, STATE_STARTING, STATE_STOPPED, STATE_UNHEALTHY};
-use super::{is_available_from_atomic, state_from_atomic, store_state};
+use super::{arguments_object, is_available_from_atomic, state_from_atomic, store_state};
 use crate::config::{BackendConfig, CliOutputFormat, CliToolConfig};
+use crate::error::GatewayError;
 use crate::registry::ToolEntry;
 
 /// Default max concurrent calls for CLI adapter backends.
@@ -188,6 +189,27 @@ pub fn parse_output(stdout: &str, format: &CliOutputFormat) -> Value {
     }
 }
 
+/// Kills a tool's process group (SIGKILL) when dropped, unless disarmed
+/// after the process exited on its own.
+struct ProcessGroupGuard(Option<u32>);
+
+impl ProcessGroupGuard {
+    fn disarm(&mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.0 {
+            unsafe {
+                libc::kill(-(pid as i32), libc::SIGKILL);
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl Backend for CliAdapterBackend {
     fn name(&self) -> &str {
@@ -228,15 +250,16 @@ impl Backend for CliAdapterBackend {
     }
 
     async fn call_tool(&self, tool_name: &str, arguments: Option<Value>) -> Result<Value> {
-        let tool_config = self.tools.get(tool_name).ok_or_else(|| {
-            anyhow::anyhow!(
-                "tool '{}' not found in cli-adapter backend '{}'",
-                tool_name,
-                self.name
-            )
-        })?;
+        let tool_config = self
+            .tools
+            .get(tool_name)
+            .ok_or_else(|| GatewayError::ToolNotFound {
+                backend: self.name.clone(),
+                tool: tool_name.to_string(),
+            })?;
 
-        let args = arguments.unwrap_or(Value::Object(Default::default()));
+        let args =
+            Value::Object(arguments_object(&self.name, tool_name, arguments)?.unwrap_or_default());
 
         // Render command template
         let rendered_cmd = render_template(&tool_config.command, &args);
@@ -262,6 +285,7 @@ impl Backend for CliAdapterBackend {
                 tool_name, self.name
             )
         })?;
+        let mut group = ProcessGroupGuard(child.id());
 
         // Write stdin if configured
         if let Some(ref stdin_template) = tool_config.stdin {
@@ -272,32 +296,26 @@ impl Backend for CliAdapterBackend {
             }
         }
 
-        // Wait for output with timeout — kill child on timeout to prevent zombies.
-        // We use wait_with_output which takes ownership, so we grab the PID first
-        // for kill-on-timeout. On timeout the future is dropped but the child
-        // process may survive; we send SIGKILL via the saved PID.
-        let child_pid = child.id();
+        // Wait for output with timeout. wait_with_output takes ownership of the
+        // child, so the process group is killed by PID: on timeout, and when the
+        // call is dropped because the client cancelled it.
         let output = match tokio::time::timeout(self.timeout, child.wait_with_output()).await {
-            Ok(result) => result.with_context(|| {
-                format!(
-                    "failed to wait for tool '{}' on backend '{}'",
-                    tool_name, self.name
-                )
-            })?,
+            Ok(result) => {
+                group.disarm();
+                result.with_context(|| {
+                    format!(
+                        "failed to wait for tool '{}' on backend '{}'",
+                        tool_name, self.name
+                    )
+                })?
+            }
             Err(_) => {
-                // Kill the child process to prevent orphans
-                if let Some(pid) = child_pid {
-                    #[cfg(unix)]
-                    unsafe {
-                        libc::kill(-(pid as i32), libc::SIGKILL);
-                    }
+                return Err(GatewayError::Timeout {
+                    backend: self.name.clone(),
+                    tool: tool_name.to_string(),
+                    timeout: self.timeout,
                 }
-                anyhow::bail!(
-                    "tool '{}' on backend '{}' timed out after {:?}",
-                    tool_name,
-                    self.name,
-                    self.timeout
-                );
+                .into());
             }
         };
 
@@ -305,14 +323,19 @@ impl Backend for CliAdapterBackend {
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
             let stdout = String::from_utf8_lossy(&output.stdout);
-            anyhow::bail!(
-                "tool '{}' on backend '{}' failed (exit {})\nstderr: {}\nstdout: {}",
-                tool_name,
-                self.name,
-                output.status.code().unwrap_or(-1),
-                stderr.trim(),
-                stdout.trim()
-            );
+            // The command ran; a non-zero exit is the tool's own failure.
+            return Err(GatewayError::ToolReported {
+                backend: self.name.clone(),
+                tool: tool_name.to_string(),
+                message: format!(
+                    "exit {}\nstderr: {}\nstdout: {}",
+                    output.status.code().unwrap_or(-1),
+                    stderr.trim(),
+                    stdout.trim()
+                ),
+                structured: None,
+            }
+            .into());
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -335,6 +358,8 @@ impl Backend for CliAdapterBackend {
                 backend_name: self.name.clone(),
                 input_schema: config.input_schema.clone(),
                 tags: vec!["cli-adapter".to_string()],
+                output_schema: None,
+                annotations: None,
             })
             .collect::<Vec<_>>();
 
@@ -538,6 +563,64 @@ mod tests {
         );
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cancelled_call_kills_cli_adapter_process_group() {
+        use std::fs;
+
+        let marker = tempfile::NamedTempFile::new().unwrap();
+        let marker_path = marker.path().to_path_buf();
+        let command = format!(
+            "echo started > {} && sleep 30 # {}",
+            marker_path.display(),
+            marker_path.display()
+        );
+
+        let mut tools = HashMap::new();
+        tools.insert(
+            "hang".to_string(),
+            CliToolConfig {
+                description: "hangs".to_string(),
+                input_schema: serde_json::json!({"type": "object"}),
+                command,
+                stdin: None,
+                output: CliOutputFormat::Text,
+            },
+        );
+
+        let backend = CliAdapterBackend {
+            name: "cli-cancel".to_string(),
+            tools,
+            env: HashMap::new(),
+            cwd: None,
+            timeout: Duration::from_secs(30),
+            shutdown_grace_period: Duration::from_secs(5),
+            health_check: None,
+            state: AtomicU8::new(STATE_HEALTHY),
+        };
+
+        // Dropping the call future is how a cancelled request reaches the backend.
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(300), backend.call_tool("hang", None)).await;
+        assert!(cancelled.is_err());
+        assert!(
+            fs::read_to_string(&marker_path)
+                .unwrap()
+                .contains("started")
+        );
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let ps = std::process::Command::new("pgrep")
+            .args(["-f", marker_path.to_string_lossy().as_ref()])
+            .output()
+            .unwrap();
+        assert!(
+            ps.stdout.is_empty(),
+            "expected process group to be killed, found: {}",
+            String::from_utf8_lossy(&ps.stdout)
+        );
+    }
+
     #[tokio::test]
     async fn test_call_tool_with_stdin() {
         let mut tools = HashMap::new();
@@ -661,8 +744,9 @@ mod tests {
         };
 
         let result = backend.call_tool("fail", None).await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("exit 1"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("exit 1"));
+        assert_eq!(crate::error::error_kind(&err), "tool_error");
     }
 
     #[tokio::test]
@@ -855,11 +939,17 @@ mod tests {
             timeout: Duration::from_secs(30),
             max_concurrent_calls: None,
             oauth: None,
+            elicitation: Default::default(),
+            sampling: Default::default(),
+            pinned_tools: Vec::new(),
             semaphore_timeout: Duration::from_secs(60),
             required_keys: Vec::new(),
             retry: Default::default(),
             prerequisite: None,
             rate_limit: None,
+            cache: None,
+            validate_arguments: true,
+            coerce_arguments: false,
             tags: Vec::new(),
             fallback_chain: Vec::new(),
             tools: None,
@@ -66,7 +66,11 @@ impl Backend for CompositeBackend {
                 "arguments": arguments,
             }));
         }
-        anyhow::bail!("composite tool '{}' not found", tool_name)
+        Err(crate::error::GatewayError::ToolNotFound {
+            backend: COMPOSITE_BACKEND_NAME.to_string(),
+            tool: tool_name.to_string(),
+        }
+        .into())
     }
 
     async fn discover_tools(&self) -> Result<Vec<ToolEntry>> {
@@ -93,6 +97,8 @@ impl Backend for CompositeBackend {
                     .clone()
                     .unwrap_or_else(|| default_schema.clone()),
                 tags: vec!["composite".to_string()],
+                output_schema: None,
+                annotations: None,
             })
             .collect())
     }
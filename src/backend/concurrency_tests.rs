@@ -8,7 +8,7 @@ mod tests {
     use std::sync::Arc;
     use std::time::Duration;
 
-    use crate::backend::{Backend, BackendManager, BackendState};
+    use crate::backend::{ArgumentChecks, Backend, BackendManager, BackendState};
     use crate::registry::ToolRegistry;
     use crate::testutil::{MockBackend, insert_mock, insert_mock_with_config};
 
@@ -178,6 +178,9 @@ mod tests {
                     retry: Default::default(),
                     prerequisite: None,
                     rate_limit: None,
+                    cache: None,
+                    validate_arguments: true,
+                    coerce_arguments: false,
                     tags: Vec::new(),
                     fallback_chain: Vec::new(),
                     tools: None,
@@ -188,6 +191,9 @@ mod tests {
                     shutdown_grace_period: Duration::from_secs(5),
                     max_memory_mb: None,
                     oauth: None,
+                    elicitation: Default::default(),
+                    sampling: Default::default(),
+                    pinned_tools: Vec::new(),
                 },
             );
         }
@@ -482,6 +488,240 @@ mod tests {
         assert!(r.is_ok(), "should succeed after replenishment");
     }
 
+    /// 10 identical concurrent calls to a cached tool reach the backend once;
+    /// later identical calls are cache hits, other arguments are misses.
+    #[tokio::test]
+    async fn test_result_cache_coalesces_identical_calls() {
+        let tracker = Arc::new(crate::tracker::CallTracker::new());
+        let manager =
+            BackendManager::new_with_config(&Default::default(), Some(Arc::clone(&tracker)));
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("cache-test", Duration::from_millis(50));
+        insert_mock_with_config(&manager, &registry, &mock, Some(0), Duration::from_secs(60)).await;
+        manager.result_caches.insert(
+            "cache-test".to_string(),
+            Arc::new(crate::backend::result_cache::ResultCache::new(
+                &crate::config::ResultCacheConfig {
+                    ttl: Duration::from_secs(60),
+                    max_entries: 16,
+                    read_only_hint: true,
+                    tools: vec!["echo_tool".to_string()],
+                    exclude: Vec::new(),
+                },
+            )),
+        );
+
+        let mut handles = Vec::new();
+        for _ in 0..10 {
+            let mgr = Arc::clone(&manager);
+            handles.push(tokio::spawn(async move {
+                mgr.call_tool(
+                    "cache-test",
+                    "echo_tool",
+                    Some(serde_json::json!({"q": 1})),
+                    None,
+                )
+                .await
+                .unwrap()
+            }));
+        }
+        for h in handles {
+            assert_eq!(h.await.unwrap()["q"], 1);
+        }
+        assert_eq!(mock.call_log().await.len(), 1);
+
+        manager
+            .call_tool(
+                "cache-test",
+                "echo_tool",
+                Some(serde_json::json!({"q": 1})),
+                None,
+            )
+            .await
+            .unwrap();
+        manager
+            .call_tool(
+                "cache-test",
+                "echo_tool",
+                Some(serde_json::json!({"q": 2})),
+                None,
+            )
+            .await
+            .unwrap();
+        // Tools that are not read-only are never cached.
+        for _ in 0..2 {
+            manager
+                .call_tool("cache-test", "slow_tool", None, None)
+                .await
+                .unwrap();
+        }
+        assert_eq!(mock.call_log().await.len(), 4);
+
+        let stats = &tracker.cache_stats()["cache-test"];
+        assert_eq!((stats.hits, stats.misses, stats.coalesced), (1, 2, 9));
+    }
+
+    /// Arguments that do not fit the input schema fail before reaching the backend.
+    #[tokio::test]
+    async fn test_invalid_arguments_rejected_before_dispatch() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("validate-test", Duration::ZERO);
+        insert_mock(&manager, &registry, &mock).await;
+        let mut entry = registry.get_by_name("validate-test.echo_tool").unwrap();
+        entry.input_schema = serde_json::json!({
+            "type": "object",
+            "properties": {"query": {"type": "string"}},
+            "required": ["query"],
+            "additionalProperties": false
+        });
+        registry.register_backend_tools_namespaced("validate-test", "validate-test", vec![entry]);
+        manager.index_tools("validate-test", ArgumentChecks::default(), &registry);
+
+        let err = manager
+            .call_tool(
+                "validate-test",
+                "echo_tool",
+                Some(serde_json::json!({"querry": "rust"})),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(crate::error::error_kind(&err), "invalid_arguments");
+        let message = err.to_string();
+        assert!(message.contains("- missing required property 'query'"));
+        assert!(message.contains("- unknown property 'querry'"));
+        assert!(message.ends_with("expected parameters: {query: string}"));
+        assert!(mock.call_log().await.is_empty());
+
+        let ok = manager
+            .call_tool(
+                "validate-test",
+                "echo_tool",
+                Some(serde_json::json!({"query": "rust"})),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ok["query"], "rust");
+
+        // Validation can be turned off per backend.
+        let off = ArgumentChecks {
+            validate: false,
+            coerce: false,
+        };
+        manager.index_tools("validate-test", off, &registry);
+        assert!(
+            manager
+                .call_tool("validate-test", "echo_tool", None, None)
+                .await
+                .is_ok()
+        );
+    }
+
+    /// With a backend's default config, calls that fit typical real-world
+    /// schemas still reach the backend; only what the schema rules out is rejected.
+    #[tokio::test]
+    async fn test_valid_arguments_pass_default_validation() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("valid-test", Duration::ZERO);
+        insert_mock(&manager, &registry, &mock).await;
+        let mut entry = registry.get_by_name("valid-test.echo_tool").unwrap();
+        entry.input_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string", "minLength": 1},
+                "limit": {"type": ["integer", "null"], "minimum": 1},
+                "since": {"type": "string", "format": "date-time"},
+                "filter": {"$ref": "#/$defs/Filter"},
+                "headers": {"type": "object", "additionalProperties": {"type": "string"}}
+            },
+            "patternProperties": {"^x-": {}},
+            "required": ["query"],
+            "additionalProperties": false,
+            "$defs": {
+                "Filter": {
+                    "type": "object",
+                    "properties": {"tags": {"type": "array", "items": {"type": "string"}}}
+                }
+            }
+        });
+        registry.register_backend_tools_namespaced("valid-test", "valid-test", vec![entry]);
+        let config: crate::config::BackendConfig =
+            serde_yaml_ng::from_str("command: echo").unwrap();
+        manager.index_tools("valid-test", (&config).into(), &registry);
+
+        let calls = [
+            serde_json::json!({"query": "rust"}),
+            serde_json::json!({"query": "rust", "limit": null, "since": "2026-01-01T00:00:00Z"}),
+            serde_json::json!({
+                "query": "rust",
+                "limit": 5.0,
+                "filter": {"tags": ["a"]},
+                "headers": {"accept": "application/json"},
+                "x-trace": "1"
+            }),
+        ];
+        for args in calls {
+            manager
+                .call_tool("valid-test", "echo_tool", Some(args.clone()), None)
+                .await
+                .unwrap_or_else(|e| panic!("{args} was rejected: {e:#}"));
+        }
+        assert_eq!(mock.call_log().await.len(), 3);
+    }
+
+    /// With coercion on, fixable mistakes are repaired and counted before
+    /// validation; the backend sees the repaired arguments.
+    #[tokio::test]
+    async fn test_arguments_coerced_before_validation() {
+        let tracker = Arc::new(crate::tracker::CallTracker::new());
+        let manager =
+            BackendManager::new_with_config(&Default::default(), Some(Arc::clone(&tracker)));
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("coerce-test", Duration::ZERO);
+        insert_mock(&manager, &registry, &mock).await;
+        let mut entry = registry.get_by_name("coerce-test.echo_tool").unwrap();
+        entry.input_schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "maxResults": {"type": "integer"},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["maxResults"],
+            "additionalProperties": false
+        });
+        registry.register_backend_tools_namespaced("coerce-test", "coerce-test", vec![entry]);
+        let args = serde_json::json!({"max_results": "3", "tags": "rust"});
+
+        // Off by default: the mistakes are reported.
+        manager.index_tools("coerce-test", ArgumentChecks::default(), &registry);
+        let err = manager
+            .call_tool("coerce-test", "echo_tool", Some(args.clone()), None)
+            .await
+            .unwrap_err();
+        assert_eq!(crate::error::error_kind(&err), "invalid_arguments");
+
+        let on = ArgumentChecks {
+            validate: true,
+            coerce: true,
+        };
+        manager.index_tools("coerce-test", on, &registry);
+        let result = manager
+            .call_tool("coerce-test", "echo_tool", Some(args), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            result,
+            serde_json::json!({"maxResults": 3, "tags": ["rust"]})
+        );
+        let counts = tracker.coercion_counts();
+        assert_eq!(counts.get("rename_property"), Some(&1));
+        assert_eq!(counts.get("string_to_number"), Some(&1));
+        assert_eq!(counts.get("wrap_in_array"), Some(&1));
+    }
+
     /// BackendManager::new_with_config uses custom drain_timeout.
     #[tokio::test]
     async fn test_drain_timeout_configurable() {
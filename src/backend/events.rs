@@ -0,0 +1,652 @@
+//! Server-initiated notifications from backends, and gateway changes pushed
+//! to connected clients.
+//!
+//! Backend rmcp clients are served with [`BackendClient`] instead of `()`, so
+//! notifications a backend sends (e.g. `notifications/tools/list_changed`)
+//! reach the gateway as [`BackendEvent`]s. [`run_event_listener`] reacts to
+//! them and broadcasts a [`ClientNotice`] that every client session forwards
+//! to its peer.
+//!
+//! Requests a backend sends (elicitation, sampling) are answered by
+//! [`BackendClient`] directly, by forwarding them to the client session whose
+//! call caused them. Progress notifications for a backend call are relayed
+//! the same way, see [`super::progress`].
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rmcp::{
+    ClientHandler, ErrorData as McpError, RoleClient, RoleServer,
+    model::{
+        ClientInfo, CreateElicitationRequestParams, CreateElicitationResult,
+        CreateMessageRequestMethod, CreateMessageRequestParams, CreateMessageResult,
+        ElicitationAction, ElicitationCapability, LoggingLevel, LoggingMessageNotificationParam,
+        ProgressNotificationParam, ResourceUpdatedNotificationParam, SamplingCapability,
+    },
+    service::{NotificationContext, Peer, RequestContext},
+};
+use serde_json::Value;
+use tokio::sync::{Notify, mpsc};
+use tracing::{debug, info, warn};
+
+use super::BackendManager;
+use super::logging::backend_logger;
+use super::sessions::ClientSessions;
+use crate::config::{ElicitationPolicy, SamplingPolicy};
+use crate::registry::ToolRegistry;
+
+/// A notification received from a backend MCP server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BackendEvent {
+    /// The backend sent `notifications/tools/list_changed`.
+    ToolsChanged { backend: String },
+    /// The backend sent `notifications/resources/list_changed`.
+    ResourcesChanged { backend: String },
+    /// The backend sent `notifications/prompts/list_changed`.
+    PromptsChanged { backend: String },
+    /// The backend sent `notifications/resources/updated` for one of its own URIs.
+    ResourceUpdated { backend: String, uri: String },
+    /// The backend sent a `notifications/message` log message.
+    Log {
+        backend: String,
+        level: LoggingLevel,
+        logger: Option<String>,
+        data: Value,
+    },
+}
+
+/// A gateway change every connected client session should be told about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientNotice {
+    /// The resource list changed (e.g. `gatemini://tools` after re-discovery).
+    ResourceListChanged,
+    /// The prompt list changed (a backend's prompts were re-discovered).
+    PromptListChanged,
+    /// A proxied backend resource changed. `uri` is the namespaced
+    /// `gatemini://r/...` URI; only sessions subscribed to it are told.
+    ResourceUpdated { uri: String },
+    /// A log message (from a backend or the gateway itself); only sessions
+    /// whose `logging/setLevel` level admits it are told.
+    Log {
+        level: LoggingLevel,
+        logger: String,
+        data: Value,
+    },
+}
+
+pub type BackendEventSender = mpsc::UnboundedSender<BackendEvent>;
+
+/// rmcp client handler for backend connections.
+///
+/// Forwards backend notifications to the manager's event channel. Instances
+/// without a sender (dedicated pool instances, tests) ignore notifications,
+/// like the `()` handler they replace, except log messages: a dedicated
+/// instance sends those to the session it is assigned to.
+///
+/// With client sessions attached, elicitation and sampling are advertised to
+/// the backend and its requests are routed per [`ElicitationPolicy`] and
+/// [`SamplingPolicy`]; without them, elicitation is declined and sampling is
+/// unsupported.
+#[derive(Clone)]
+pub struct BackendClient {
+    /// Backend name, or the pool instance name for dedicated instances.
+    backend: String,
+    events: Option<BackendEventSender>,
+    sessions: Option<Arc<ClientSessions>>,
+    elicitation: ElicitationPolicy,
+    sampling: SamplingPolicy,
+}
+
+impl BackendClient {
+    pub fn new(backend: String, events: Option<BackendEventSender>) -> Self {
+        Self {
+            backend,
+            events,
+            sessions: None,
+            elicitation: ElicitationPolicy::Decline,
+            sampling: SamplingPolicy::Deny,
+        }
+    }
+
+    /// Route backend requests to client sessions according to `elicitation`.
+    pub fn with_sessions(
+        mut self,
+        sessions: Arc<ClientSessions>,
+        elicitation: ElicitationPolicy,
+    ) -> Self {
+        self.sessions = Some(sessions);
+        self.elicitation = elicitation;
+        self
+    }
+
+    /// Route requests from this backend to clients only if `sampling` allows it.
+    pub fn with_sampling(mut self, sampling: SamplingPolicy) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// The session a backend request should go to: the one session with a
+    /// call in flight on this backend. `request` names it in logs.
+    fn calling_session(&self, request: &str) -> Option<u64> {
+        let sessions = self.sessions.as_ref()?;
+        match sessions.calling_sessions(&self.backend).as_slice() {
+            [session_id] => Some(*session_id),
+            [] => {
+                debug!(backend = %self.backend, request, "backend request outside of any session's call");
+                None
+            }
+            calling => {
+                warn!(
+                    backend = %self.backend,
+                    request,
+                    sessions = calling.len(),
+                    "backend request from shared backend with several sessions calling"
+                );
+                None
+            }
+        }
+    }
+
+    /// The session an elicitation request should go to, if the policy allows routing.
+    fn elicitation_session(&self) -> Option<u64> {
+        if self.elicitation == ElicitationPolicy::Decline {
+            return None;
+        }
+        self.calling_session("elicitation")
+    }
+
+    /// Peer of a connected client session.
+    fn session_peer(&self, session_id: u64) -> Option<Peer<RoleServer>> {
+        self.sessions.as_ref()?.peer(session_id)
+    }
+
+    fn emit(&self, event: BackendEvent) {
+        if let Some(ref events) = self.events
+            && events.send(event).is_err()
+        {
+            debug!(backend = %self.backend, "event listener gone, dropping backend notification");
+        }
+    }
+}
+
+fn declined() -> CreateElicitationResult {
+    CreateElicitationResult::new(ElicitationAction::Decline)
+}
+
+impl ClientHandler for BackendClient {
+    fn get_info(&self) -> ClientInfo {
+        let mut info = ClientInfo::default();
+        if self.sessions.is_some() {
+            // Capabilities are fixed at the backend handshake. Shared backends
+            // start before any client connects, so they reflect the policy;
+            // dedicated instances are started with sampling denied unless their
+            // session's client supports it. Per-client support is checked again
+            // when a request arrives.
+            if self.elicitation == ElicitationPolicy::Route {
+                info.capabilities.elicitation = Some(ElicitationCapability::default());
+            }
+            if self.sampling == SamplingPolicy::Allow {
+                info.capabilities.sampling = Some(SamplingCapability::default());
+            }
+        }
+        info
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParams,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, McpError> {
+        let Some(session_id) = self.elicitation_session() else {
+            return Ok(declined());
+        };
+        let peer = self.session_peer(session_id).filter(|peer| {
+            peer.peer_info()
+                .is_some_and(|info| info.capabilities.elicitation.is_some())
+        });
+        let Some(peer) = peer else {
+            debug!(backend = %self.backend, session = session_id, "client session does not support elicitation, declining");
+            return Ok(declined());
+        };
+
+        debug!(backend = %self.backend, session = session_id, "forwarding elicitation to client session");
+        peer.create_elicitation(request).await.map_err(|e| {
+            McpError::internal_error(format!("elicitation via client session failed: {e}"), None)
+        })
+    }
+
+    // Sampling is deprecated by SEP-2577 but clients still support it.
+    #[allow(deprecated)]
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, McpError> {
+        if self.sessions.is_none() || self.sampling == SamplingPolicy::Deny {
+            return Err(McpError::method_not_found::<CreateMessageRequestMethod>());
+        }
+        let session_id = self.calling_session("sampling").ok_or_else(|| {
+            McpError::invalid_request(
+                "sampling request does not belong to a single client session's call",
+                None,
+            )
+        })?;
+        let peer = self
+            .session_peer(session_id)
+            .filter(|peer| {
+                peer.peer_info()
+                    .is_some_and(|info| info.capabilities.sampling.is_some())
+            })
+            .ok_or_else(|| {
+                McpError::invalid_request("client session does not support sampling", None)
+            })?;
+
+        debug!(backend = %self.backend, session = session_id, "forwarding sampling request to client session");
+        peer.create_message(params).await.map_err(|e| {
+            McpError::internal_error(format!("sampling via client session failed: {e}"), None)
+        })
+    }
+
+    async fn on_progress(
+        &self,
+        params: ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        let route = self.sessions.as_ref().and_then(|sessions| {
+            sessions
+                .progress_routes()
+                .get(&self.backend, &params.progress_token)
+        });
+        match route {
+            Some(route) => route.relay(params).await,
+            None => {
+                debug!(backend = %self.backend, token = ?params.progress_token, "progress for unknown token, dropping")
+            }
+        }
+    }
+
+    // Logging is deprecated by SEP-2577 but clients still negotiate it.
+    #[allow(deprecated)]
+    async fn on_logging_message(
+        &self,
+        mut params: LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if self.events.is_none()
+            && let Some(ref sessions) = self.sessions
+        {
+            // Dedicated instance: its logs go to the one session it serves.
+            let Some((backend, session_id)) = sessions.instance_owner(&self.backend) else {
+                debug!(backend = %self.backend, "log message from unassigned instance, dropping");
+                return;
+            };
+            if !sessions.wants_log(session_id, params.level) {
+                return;
+            }
+            let Some(peer) = sessions.peer(session_id) else {
+                return;
+            };
+            params.logger = Some(backend_logger(&backend, params.logger.as_deref()));
+            if let Err(e) = peer.notify_logging_message(params).await {
+                debug!(backend = %self.backend, session = session_id, error = %e, "failed to forward log message");
+            }
+            return;
+        }
+        self.emit(BackendEvent::Log {
+            backend: self.backend.clone(),
+            level: params.level,
+            logger: params.logger,
+            data: params.data,
+        });
+    }
+
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        debug!(backend = %self.backend, "backend tool list changed");
+        self.emit(BackendEvent::ToolsChanged {
+            backend: self.backend.clone(),
+        });
+    }
+
+    async fn on_resource_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        debug!(backend = %self.backend, "backend resource list changed");
+        self.emit(BackendEvent::ResourcesChanged {
+            backend: self.backend.clone(),
+        });
+    }
+
+    async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        debug!(backend = %self.backend, "backend prompt list changed");
+        self.emit(BackendEvent::PromptsChanged {
+            backend: self.backend.clone(),
+        });
+    }
+
+    async fn on_resource_updated(
+        &self,
+        params: ResourceUpdatedNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        debug!(backend = %self.backend, uri = %params.uri, "backend resource updated");
+        self.emit(BackendEvent::ResourceUpdated {
+            backend: self.backend.clone(),
+            uri: params.uri,
+        });
+    }
+}
+
+/// Handle backend events until shutdown.
+///
+/// `tools/list_changed` re-runs tool discovery for the backend, which updates
+/// the registry (and embeddings), then saves the cache and tells clients the
+/// resource list changed. Bursts of events are coalesced so a backend that
+/// registers several tools in a row is only re-discovered once.
+///
+/// Backend resource notifications are passed through: `resources/list_changed`
+/// becomes a client `ResourceListChanged`, and `resources/updated` is re-sent
+/// with the namespaced URI to the sessions subscribed to it.
+/// `prompts/list_changed` re-discovers the backend's prompts, and log
+/// messages are forwarded as they arrive.
+pub async fn run_event_listener(
+    manager: Arc<BackendManager>,
+    registry: Arc<ToolRegistry>,
+    mut events: mpsc::UnboundedReceiver<BackendEvent>,
+    shutdown: Arc<Notify>,
+    cache_path: PathBuf,
+) {
+    loop {
+        let first = tokio::select! {
+            event = events.recv() => match event {
+                Some(event) => event,
+                None => return,
+            },
+            _ = shutdown.notified() => {
+                info!("backend event listener shutting down");
+                return;
+            }
+        };
+
+        let mut changed = BTreeSet::new();
+        let mut prompts_changed = BTreeSet::new();
+        let mut resources_changed = false;
+        let mut updated = BTreeSet::new();
+        let mut next = Some(first);
+        while let Some(event) = next {
+            match event {
+                BackendEvent::ToolsChanged { backend } => {
+                    changed.insert(backend);
+                }
+                BackendEvent::ResourcesChanged { .. } => resources_changed = true,
+                BackendEvent::PromptsChanged { backend } => {
+                    prompts_changed.insert(backend);
+                }
+                BackendEvent::ResourceUpdated { backend, uri } => {
+                    updated.insert(super::resources::namespace_uri(&backend, &uri));
+                }
+                // Logs are passed through in order, not coalesced.
+                BackendEvent::Log {
+                    backend,
+                    level,
+                    logger,
+                    data,
+                } => manager.forward_backend_log(&backend, level, logger.as_deref(), data),
+            }
+            next = events.try_recv().ok();
+        }
+
+        let tools_refreshed = refresh_backends(&manager, &registry, &changed).await;
+        let prompts_refreshed = refresh_prompts(&manager, &registry, &prompts_changed).await;
+        if tools_refreshed || prompts_refreshed {
+            crate::cache::save(&cache_path, &registry, None).await;
+        }
+        if prompts_refreshed {
+            manager.notify_clients(ClientNotice::PromptListChanged);
+        }
+        if tools_refreshed || resources_changed {
+            manager.notify_clients(ClientNotice::ResourceListChanged);
+        }
+        for uri in updated {
+            manager.notify_clients(ClientNotice::ResourceUpdated { uri });
+        }
+    }
+}
+
+/// Re-discover tools for each backend. Returns true if any registry entries changed.
+async fn refresh_backends(
+    manager: &BackendManager,
+    registry: &ToolRegistry,
+    backends: &BTreeSet<String>,
+) -> bool {
+    let mut refreshed = false;
+    for name in backends {
+        match manager.refresh_tools(name, registry).await {
+            Ok(tool_count) => {
+                info!(backend = %name, tools = tool_count, "re-discovered tools after list_changed");
+                refreshed = true;
+            }
+            Err(e) => {
+                warn!(backend = %name, error = %e, "tool re-discovery after list_changed failed");
+            }
+        }
+    }
+    refreshed
+}
+
+/// Re-discover prompts for each backend. Returns true if any were refreshed.
+async fn refresh_prompts(
+    manager: &BackendManager,
+    registry: &ToolRegistry,
+    backends: &BTreeSet<String>,
+) -> bool {
+    let mut refreshed = false;
+    for name in backends {
+        match manager.refresh_prompts(name, registry).await {
+            Ok(prompt_count) => {
+                info!(backend = %name, prompts = prompt_count, "re-discovered prompts after list_changed");
+                refreshed = true;
+            }
+            Err(e) => {
+                warn!(backend = %name, error = %e, "prompt re-discovery after list_changed failed");
+            }
+        }
+    }
+    refreshed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{MockBackend, insert_mock};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_tools_changed_rediscovers_and_notifies() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("mock", Duration::ZERO);
+        insert_mock(&manager, &registry, &mock).await;
+
+        let mut notices = manager.subscribe_client_notices();
+        let events = manager.take_event_receiver().unwrap();
+        let shutdown = Arc::new(Notify::new());
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join("cache.json");
+        let listener = tokio::spawn(run_event_listener(
+            Arc::clone(&manager),
+            Arc::clone(&registry),
+            events,
+            Arc::clone(&shutdown),
+            cache_path.clone(),
+        ));
+
+        // Backend adds a tool at runtime, then announces it (twice, coalesced).
+        mock.add_tool("new_tool");
+        let client = BackendClient::new("mock".to_string(), Some(manager.event_sender()));
+        for _ in 0..2 {
+            client.emit(BackendEvent::ToolsChanged {
+                backend: "mock".to_string(),
+            });
+        }
+
+        let notice = tokio::time::timeout(Duration::from_secs(5), notices.recv())
+            .await
+            .expect("no client notice")
+            .unwrap();
+        assert_eq!(notice, ClientNotice::ResourceListChanged);
+        assert!(registry.get_by_name("mock.new_tool").is_some());
+        assert!(cache_path.exists());
+
+        listener.abort();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_drops_removed_tools() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("mock", Duration::ZERO);
+        insert_mock(&manager, &registry, &mock).await;
+        assert!(registry.get_by_name("mock.slow_tool").is_some());
+
+        mock.remove_tool("slow_tool");
+        let count = manager.refresh_tools("mock", &registry).await.unwrap();
+        assert_eq!(count, 3);
+        assert!(registry.get_by_name("mock.slow_tool").is_none());
+        assert!(registry.get_by_name("mock.echo_tool").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_resource_updated_is_namespaced() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mut notices = manager.subscribe_client_notices();
+        let events = manager.take_event_receiver().unwrap();
+        let shutdown = Arc::new(Notify::new());
+        let dir = tempfile::tempdir().unwrap();
+        let listener = tokio::spawn(run_event_listener(
+            Arc::clone(&manager),
+            Arc::clone(&registry),
+            events,
+            Arc::clone(&shutdown),
+            dir.path().join("cache.json"),
+        ));
+
+        let client = BackendClient::new("docs".to_string(), Some(manager.event_sender()));
+        client.emit(BackendEvent::ResourceUpdated {
+            backend: "docs".to_string(),
+            uri: "file:///guide.md".to_string(),
+        });
+
+        let notice = tokio::time::timeout(Duration::from_secs(5), notices.recv())
+            .await
+            .expect("no client notice")
+            .unwrap();
+        assert_eq!(
+            notice,
+            ClientNotice::ResourceUpdated {
+                uri: "gatemini://r/docs/file:///guide.md".to_string()
+            }
+        );
+
+        listener.abort();
+    }
+
+    #[tokio::test]
+    async fn test_backend_log_is_tagged_with_backend() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mut notices = manager.subscribe_client_notices();
+        let events = manager.take_event_receiver().unwrap();
+        let shutdown = Arc::new(Notify::new());
+        let dir = tempfile::tempdir().unwrap();
+        let listener = tokio::spawn(run_event_listener(
+            Arc::clone(&manager),
+            Arc::clone(&registry),
+            events,
+            Arc::clone(&shutdown),
+            dir.path().join("cache.json"),
+        ));
+
+        let client = BackendClient::new("github".to_string(), Some(manager.event_sender()));
+        client.emit(BackendEvent::Log {
+            backend: "github".to_string(),
+            level: LoggingLevel::Error,
+            logger: Some("auth".to_string()),
+            data: serde_json::json!("token expired"),
+        });
+
+        let notice = tokio::time::timeout(Duration::from_secs(5), notices.recv())
+            .await
+            .expect("no client notice")
+            .unwrap();
+        assert_eq!(
+            notice,
+            ClientNotice::Log {
+                level: LoggingLevel::Error,
+                logger: "github.auth".to_string(),
+                data: serde_json::json!("token expired"),
+            }
+        );
+
+        listener.abort();
+    }
+
+    #[test]
+    fn test_elicitation_routes_to_single_calling_session() {
+        let sessions = ClientSessions::new();
+        let client = BackendClient::new("exa".to_string(), None)
+            .with_sessions(Arc::clone(&sessions), ElicitationPolicy::Route);
+        assert_eq!(client.elicitation_session(), None);
+
+        let first = sessions.begin_call("exa", 7);
+        assert_eq!(client.elicitation_session(), Some(7));
+
+        // A second session calling the shared backend makes the requester ambiguous.
+        let second = sessions.begin_call("exa", 8);
+        assert_eq!(client.elicitation_session(), None);
+        drop(first);
+        assert_eq!(client.elicitation_session(), Some(8));
+        drop(second);
+    }
+
+    #[test]
+    fn test_sampling_advertised_only_when_allowed() {
+        let sessions = ClientSessions::new();
+        let allowed = BackendClient::new("exa".to_string(), None)
+            .with_sessions(Arc::clone(&sessions), ElicitationPolicy::Route)
+            .with_sampling(SamplingPolicy::Allow);
+        assert!(allowed.get_info().capabilities.sampling.is_some());
+        assert!(allowed.get_info().capabilities.elicitation.is_some());
+
+        let denied = BackendClient::new("exa".to_string(), None)
+            .with_sessions(sessions, ElicitationPolicy::Decline)
+            .with_sampling(SamplingPolicy::Deny);
+        assert!(denied.get_info().capabilities.sampling.is_none());
+        assert!(denied.get_info().capabilities.elicitation.is_none());
+
+        // Unmanaged clients (pool tests, inventory) advertise nothing.
+        let unmanaged = BackendClient::new("exa".to_string(), None);
+        assert!(unmanaged.get_info().capabilities.sampling.is_none());
+    }
+
+    #[test]
+    fn test_elicitation_decline_policy() {
+        let sessions = ClientSessions::new();
+        let _call = sessions.begin_call("exa", 1);
+        let declining = BackendClient::new("exa".to_string(), None)
+            .with_sessions(Arc::clone(&sessions), ElicitationPolicy::Decline);
+        assert_eq!(declining.elicitation_session(), None);
+        // Without sessions attached (e.g. tests, inventory) nothing is routed.
+        assert_eq!(
+            BackendClient::new("exa".to_string(), None).elicitation_session(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_without_sender_ignores_notifications() {
+        // Dedicated pool instances have no sender; emitting must not panic.
+        BackendClient::new("pooled".to_string(), None).emit(BackendEvent::ToolsChanged {
+            backend: "pooled".to_string(),
+        });
+    }
+}
@@ -21,6 +21,8 @@ struct BackendHealth {
     restart_window_start: Option<Instant>,
     circuit_open_since: Option<Instant>,
     last_memory_restart: Option<Instant>,
+    /// Tracked calls and failed calls as of the last check.
+    last_calls: (u64, u64),
 }
 
 impl BackendHealth {
@@ -34,6 +36,7 @@ impl BackendHealth {
             restart_window_start: None,
             circuit_open_since: None,
             last_memory_restart: None,
+            last_calls: (0, 0),
         }
     }
 
@@ -46,6 +49,14 @@ impl BackendHealth {
         self.consecutive_failures += 1;
     }
 
+    /// Whether the backend had calls since the last check and all of them
+    /// failed, tool-reported (`isError`) failures included.
+    fn calls_all_failed(&mut self, calls: u64, errors: u64) -> bool {
+        let (last_calls, last_errors) = std::mem::replace(&mut self.last_calls, (calls, errors));
+        let new_calls = calls.saturating_sub(last_calls);
+        new_calls > 0 && errors.saturating_sub(last_errors) >= new_calls
+    }
+
     /// Exponential backoff for restarts using configurable initial/max values.
     fn restart_backoff(&self, config: &HealthConfig) -> Duration {
         let multiplier = 2u64.saturating_pow(self.restart_count.min(5));
@@ -152,12 +163,25 @@ pub async fn run_health_checker(
 
             for (name, result) in ping_results {
                 let health = health_map.get_mut(&name).unwrap();
+                // A backend that answers pings but fails every call is not healthy.
+                let calls_failing = manager
+                    .call_counts(&name)
+                    .is_some_and(|(calls, errors)| health.calls_all_failed(calls, errors));
                 match result {
+                    Ok(Ok(())) if calls_failing => {
+                        health.record_failure();
+                        warn!(
+                            backend = %name,
+                            failures = health.consecutive_failures,
+                            "every tool call since the last health check failed"
+                        );
+                    }
                     Ok(Ok(())) => {
                         if health.consecutive_failures > 0 {
                             info!(backend = %name, "backend recovered");
                         }
                         health.record_success();
+                        continue;
                     }
                     Ok(Err(e)) => {
                         health.record_failure();
@@ -167,16 +191,6 @@ pub async fn run_health_checker(
                             error = %e,
                             "health check failed"
                         );
-
-                        if health.consecutive_failures >= config.failure_threshold {
-                            warn!(
-                                backend = %name,
-                                "circuit breaker tripped after {} consecutive failures",
-                                health.consecutive_failures
-                            );
-                            health.circuit_open_since = Some(Instant::now());
-                            manager.set_backend_state(&name, BackendState::Unhealthy);
-                        }
                     }
                     Err(_) => {
                         health.record_failure();
@@ -186,18 +200,18 @@ pub async fn run_health_checker(
                             timeout_secs = config.timeout.as_secs(),
                             "health check timed out"
                         );
-
-                        if health.consecutive_failures >= config.failure_threshold {
-                            warn!(
-                                backend = %name,
-                                "circuit breaker tripped after {} consecutive failures",
-                                health.consecutive_failures
-                            );
-                            health.circuit_open_since = Some(Instant::now());
-                            manager.set_backend_state(&name, BackendState::Unhealthy);
-                        }
                     }
                 }
+
+                if health.consecutive_failures >= config.failure_threshold {
+                    warn!(
+                        backend = %name,
+                        "circuit breaker tripped after {} consecutive failures",
+                        health.consecutive_failures
+                    );
+                    health.circuit_open_since = Some(Instant::now());
+                    manager.set_backend_state(&name, BackendState::Unhealthy);
+                }
             }
         }
 
@@ -657,6 +671,20 @@ mod tests {
         assert!(h.circuit_open_since.is_none());
     }
 
+    #[test]
+    fn test_calls_all_failed() {
+        let mut h = BackendHealth::new("test".to_string());
+        // No calls since the last check is not a failure.
+        assert!(!h.calls_all_failed(0, 0));
+        // Three calls, all failed (e.g. `isError` results).
+        assert!(h.calls_all_failed(3, 3));
+        // One more call that succeeded.
+        assert!(!h.calls_all_failed(4, 3));
+        assert!(!h.calls_all_failed(4, 3));
+        // Two more calls, both failed.
+        assert!(h.calls_all_failed(6, 5));
+    }
+
     #[test]
     fn test_pending_backend_backoff_tracking() {
         // Simulate Phase 3 behavior: a pending backend that fails repeatedly
@@ -8,17 +8,21 @@ use rmcp::{
     },
 };
 use serde_json::Value;
+use std::sync::Arc;
 use std::sync::atomic::{AtomicU8, Ordering};
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use super::events::{BackendClient, BackendEventSender};
 use super::lenient_client::LenientClient;
+use super::sessions::ClientSessions;
 use super::{Backend, BackendState, STATE_HEALTHY, STATE_STARTING, STATE_STOPPED};
 use super::{
-    is_available_from_atomic, map_call_tool_result, map_tools_to_entries, state_from_atomic,
-    store_state,
+    arguments_object, is_available_from_atomic, map_call_tool_outcome, map_tools_to_entries,
+    send_tool_call, state_from_atomic, store_state,
 };
 use crate::config::BackendConfig;
+use crate::error::GatewayError;
 use crate::oauth::TokenStore;
 use crate::registry::ToolEntry;
 use crate::trace_context::TraceContext;
@@ -35,8 +39,12 @@ fn inject_trace_meta(params: &mut CallToolRequestParams) {
 pub struct HttpBackend {
     name: String,
     config: BackendConfig,
-    service: RwLock<Option<RunningService<rmcp::RoleClient, ()>>>,
+    service: RwLock<Option<RunningService<rmcp::RoleClient, BackendClient>>>,
     state: AtomicU8,
+    /// Where backend notifications go (`None` when not managed).
+    events: Option<BackendEventSender>,
+    /// Client sessions that backend requests (elicitation, sampling) are routed to.
+    sessions: Option<Arc<ClientSessions>>,
 }
 
 impl HttpBackend {
@@ -46,6 +54,30 @@ impl HttpBackend {
             config,
             service: RwLock::new(None),
             state: AtomicU8::new(STATE_STARTING),
+            events: None,
+            sessions: None,
+        }
+    }
+
+    /// Forward notifications from this backend (e.g. `tools/list_changed`) to the manager.
+    pub fn with_events(mut self, events: BackendEventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Route requests from this backend (elicitation, sampling) to client sessions.
+    pub fn with_sessions(mut self, sessions: Arc<ClientSessions>) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    fn client_handler(&self) -> BackendClient {
+        let client = BackendClient::new(self.name.clone(), self.events.clone());
+        match self.sessions {
+            Some(ref sessions) => client
+                .with_sessions(Arc::clone(sessions), self.config.elicitation)
+                .with_sampling(self.config.sampling),
+            None => client,
         }
     }
 }
@@ -174,12 +206,16 @@ impl Backend for HttpBackend {
         let transport = StreamableHttpClientTransport::with_client(client, transport_config);
 
         // Connect rmcp client — performs MCP initialize handshake
-        let service = ().serve(transport).await.with_context(|| {
-            format!(
-                "failed MCP handshake with HTTP backend '{}' at {}",
-                self.name, url
-            )
-        })?;
+        let service = self
+            .client_handler()
+            .serve(transport)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed MCP handshake with HTTP backend '{}' at {}",
+                    self.name, url
+                )
+            })?;
 
         if let Some(peer) = service.peer_info() {
             info!(
@@ -215,38 +251,28 @@ impl Backend for HttpBackend {
 
     async fn call_tool(&self, tool_name: &str, arguments: Option<Value>) -> Result<Value> {
         let guard = self.service.read().await;
-        let service = guard
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("HTTP backend '{}' not started", self.name))?;
+        let service = guard.as_ref().ok_or_else(|| GatewayError::NotStarted {
+            backend: self.name.clone(),
+        })?;
 
         let mut params = CallToolRequestParams::new(tool_name.to_string());
-        if let Some(args) = arguments.and_then(|v| v.as_object().cloned()) {
+        if let Some(args) = arguments_object(&self.name, tool_name, arguments)? {
             params = params.with_arguments(args);
         }
         inject_trace_meta(&mut params);
 
         debug!(backend = %self.name, tool = %tool_name, "calling tool via HTTP");
 
-        let result = tokio::time::timeout(self.config.timeout, service.call_tool(params))
-            .await
-            .map_err(|_| {
-                anyhow::anyhow!(
-                    "tool call '{}' on HTTP backend '{}' timed out after {:?}",
-                    tool_name,
-                    self.name,
-                    self.config.timeout
-                )
-            })?
-            .map_err(|e| {
-                anyhow::anyhow!(
-                    "tool call '{}' on HTTP backend '{}' failed: {}",
-                    tool_name,
-                    self.name,
-                    e
-                )
-            })?;
+        let result = send_tool_call(
+            service.peer(),
+            params,
+            self.sessions.as_deref(),
+            &self.name,
+            self.config.timeout,
+        )
+        .await?;
 
-        Ok(map_call_tool_result(result))
+        map_call_tool_outcome(result, &self.name, tool_name)
     }
 
     async fn discover_tools(&self) -> Result<Vec<ToolEntry>> {
@@ -279,4 +305,7 @@ impl Backend for HttpBackend {
     fn set_state(&self, state: BackendState) {
         store_state(&self.state, state);
     }
+    async fn peer(&self) -> Option<rmcp::service::Peer<rmcp::RoleClient>> {
+        self.service.read().await.as_ref().map(|s| s.peer().clone())
+    }
 }
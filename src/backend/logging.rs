@@ -0,0 +1,115 @@
+//! MCP logging: backend log notifications and gateway events forwarded to
+//! client sessions.
+//!
+//! Backend `notifications/message` are re-sent with the logger prefixed by
+//! the backend name (`github` or `github.auth`). Gateway events (backend state
+//! changes, fallbacks taken) are logged as `gatemini`. Dedicated instances
+//! have no event listener; they log straight to the session they serve. Each
+//! session only gets
+//! messages at or above the level it set with `logging/setLevel`, or
+//! [`DEFAULT_LOG_LEVEL`] if it never set one.
+
+use rmcp::model::LoggingLevel;
+use serde_json::Value;
+
+use super::BackendManager;
+use super::events::ClientNotice;
+
+/// Level for sessions that never sent `logging/setLevel`.
+pub const DEFAULT_LOG_LEVEL: LoggingLevel = LoggingLevel::Warning;
+
+/// Logger name of gateway events.
+pub const GATEWAY_LOGGER: &str = "gatemini";
+
+/// Syslog severity of a level, increasing from `debug` to `emergency`.
+pub fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+/// Logger name for a message from `backend`, keeping the backend's own
+/// logger name if it sent one.
+pub fn backend_logger(backend: &str, logger: Option<&str>) -> String {
+    match logger {
+        Some(logger) if !logger.is_empty() => format!("{backend}.{logger}"),
+        _ => backend.to_string(),
+    }
+}
+
+/// Log notice for a gateway event about `backend`.
+pub(super) fn gateway_event(level: LoggingLevel, backend: &str, message: String) -> ClientNotice {
+    ClientNotice::Log {
+        level,
+        logger: GATEWAY_LOGGER.to_string(),
+        data: serde_json::json!({ "backend": backend, "message": message }),
+    }
+}
+
+impl BackendManager {
+    /// Set the minimum level of log messages sent to a session.
+    pub fn set_client_log_level(&self, session_id: u64, level: LoggingLevel) {
+        self.sessions.set_log_level(session_id, level);
+    }
+
+    /// Whether a message at `level` should be sent to a session.
+    pub fn wants_log(&self, session_id: u64, level: LoggingLevel) -> bool {
+        self.sessions.wants_log(session_id, level)
+    }
+
+    /// Log a gateway event to every client session.
+    pub(super) fn log_to_clients(&self, level: LoggingLevel, backend: &str, message: String) {
+        self.notify_clients(gateway_event(level, backend, message));
+    }
+
+    /// Forward a backend's `notifications/message` to every client session.
+    pub(super) fn forward_backend_log(
+        &self,
+        backend: &str,
+        level: LoggingLevel,
+        logger: Option<&str>,
+        data: Value,
+    ) {
+        self.notify_clients(ClientNotice::Log {
+            level,
+            logger: backend_logger(backend, logger),
+            data,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_logger() {
+        assert_eq!(backend_logger("github", None), "github");
+        assert_eq!(backend_logger("github", Some("")), "github");
+        assert_eq!(backend_logger("github", Some("auth")), "github.auth");
+    }
+
+    #[test]
+    fn test_session_log_level_filter() {
+        let manager = BackendManager::new();
+        // Sessions that never set a level get warnings and above.
+        assert!(!manager.wants_log(1, LoggingLevel::Info));
+        assert!(manager.wants_log(1, LoggingLevel::Warning));
+        assert!(manager.wants_log(1, LoggingLevel::Emergency));
+
+        manager.set_client_log_level(1, LoggingLevel::Debug);
+        assert!(manager.wants_log(1, LoggingLevel::Debug));
+        assert!(!manager.wants_log(2, LoggingLevel::Debug));
+
+        manager.set_client_log_level(2, LoggingLevel::Error);
+        assert!(!manager.wants_log(2, LoggingLevel::Warning));
+        assert!(manager.wants_log(2, LoggingLevel::Critical));
+    }
+}
@@ -2,12 +2,19 @@
 
 pub mod cli_adapter;
 pub mod composite;
+pub mod events;
 pub mod health;
 pub mod http;
 pub mod lenient_client;
+pub mod logging;
 pub mod memory;
 pub mod pool;
 pub mod prerequisite;
+pub mod progress;
+pub mod prompts;
+pub mod resources;
+pub mod result_cache;
+pub mod sessions;
 pub mod stdio;
 
 #[cfg(test)]
@@ -16,24 +23,30 @@ mod concurrency_tests;
 use anyhow::Result;
 use async_trait::async_trait;
 use dashmap::DashMap;
-use rmcp::model::{CallToolResult, RawContent};
+use rmcp::model::{CallToolResult, LoggingLevel, RawContent};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::time::Duration;
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::{RwLock, Semaphore, broadcast, mpsc};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use crate::config::{BackendConfig, Config, InstanceMode, Transport};
+use crate::error::GatewayError;
 use crate::registry::{ToolEntry, ToolRegistry};
 
 /// Default max concurrent calls for stdio backends.
 const DEFAULT_STDIO_MAX_CONCURRENT: u32 = 10;
+
 /// Default max concurrent calls for HTTP backends.
 const DEFAULT_HTTP_MAX_CONCURRENT: u32 = 100;
 
+/// Buffered client notices per session before a slow session starts lagging.
+const CLIENT_NOTICE_CAPACITY: usize = 64;
+
 // Shared state constants used by both stdio and http backends.
 pub(crate) const STATE_STARTING: u8 = 0;
 pub(crate) const STATE_HEALTHY: u8 = 1;
@@ -41,15 +54,21 @@ pub(crate) const STATE_UNHEALTHY: u8 = 3;
 pub(crate) const STATE_STOPPED: u8 = 7;
 
 /// Map a CallToolResult to a JSON Value.
+///
+/// Text blocks are parsed as JSON when possible. Image, audio and resource
+/// blocks keep their MCP wire shape (`{"type": "image", "data", "mimeType"}`)
+/// so sandbox code sees typed objects and the output pipeline can return them
+/// to the client as real content blocks.
 pub(crate) fn map_call_tool_result(result: CallToolResult) -> Value {
     let contents: Vec<Value> = result
         .content
         .into_iter()
-        .map(|c| match c.raw {
+        .map(|c| match &c.raw {
             RawContent::Text(t) => {
-                serde_json::from_str(&t.text).unwrap_or_else(|_| Value::String(t.text))
+                serde_json::from_str(&t.text).unwrap_or_else(|_| Value::String(t.text.clone()))
             }
-            _ => Value::String("[non-text content]".to_string()),
+            _ => serde_json::to_value(&c)
+                .unwrap_or_else(|_| Value::String("[non-text content]".to_string())),
         })
         .collect();
 
@@ -60,30 +79,201 @@ pub(crate) fn map_call_tool_result(result: CallToolResult) -> Value {
     }
 }
 
-/// Check if an error is transient and suitable for fallback.
-/// Only network, timeout, and rate limit errors trigger fallback chains.
-pub(crate) fn is_transient_error(err: &anyhow::Error) -> bool {
-    let msg = err.to_string().to_lowercase();
-    // Rate limiting / overload
-    msg.contains("rate limit")
-        || msg.contains("too many requests")
-        // Timeouts
-        || msg.contains("timeout")
-        || msg.contains("timed out")
-        || msg.contains("deadline exceeded")
-        // Connection errors (specific patterns to avoid false positives)
-        || msg.contains("connection refused")
-        || msg.contains("connection reset")
-        || msg.contains("connection closed")
-        || msg.contains("connection aborted")
-        || msg.contains("failed to connect")
-        // Network errors
-        || msg.contains("network error")
-        || msg.contains("network is unreachable")
-        || msg.contains("broken pipe")
-        // Service availability
-        || msg.contains("service unavailable")
-        || msg.contains("temporarily unavailable")
+/// Map a backend CallToolResult to the value returned to callers.
+///
+/// - `isError: true` becomes a [`GatewayError::ToolReported`].
+/// - `structuredContent` is preferred over the text content when present
+///   (non-text blocks are kept alongside it so images etc. are not lost).
+/// - Otherwise falls back to [`map_call_tool_result`].
+pub(crate) fn map_call_tool_outcome(
+    result: CallToolResult,
+    backend_name: &str,
+    tool_name: &str,
+) -> Result<Value> {
+    if result.is_error == Some(true) {
+        let message = result
+            .content
+            .iter()
+            .filter_map(|c| match &c.raw {
+                RawContent::Text(t) => Some(t.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let message = if message.is_empty() {
+            result
+                .structured_content
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "(no error message)".to_string())
+        } else {
+            message
+        };
+        return Err(GatewayError::ToolReported {
+            backend: backend_name.to_string(),
+            tool: tool_name.to_string(),
+            message,
+            structured: result.structured_content,
+        }
+        .into());
+    }
+
+    if let Some(structured) = result.structured_content {
+        let mut blocks: Vec<Value> = result
+            .content
+            .iter()
+            .filter(|c| !matches!(c.raw, RawContent::Text(_)))
+            .filter_map(|c| serde_json::to_value(c).ok())
+            .collect();
+        if blocks.is_empty() {
+            return Ok(structured);
+        }
+        blocks.insert(0, structured);
+        return Ok(Value::Array(blocks));
+    }
+
+    Ok(map_call_tool_result(result))
+}
+
+/// Discover a backend's tools, apply config tags, and register them under
+/// the backend's namespace. Returns the number of tools discovered.
+async fn register_discovered_tools(
+    name: &str,
+    config: Option<&BackendConfig>,
+    backend: &dyn Backend,
+    registry: &ToolRegistry,
+) -> Result<usize> {
+    let mut tools = backend.discover_tools().await?;
+    if let Some(config) = config
+        && !config.tags.is_empty()
+    {
+        for tool in &mut tools {
+            tool.tags.clone_from(&config.tags);
+        }
+    }
+    let tool_count = tools.len();
+
+    let namespace = config.and_then(|c| c.namespace.as_deref()).unwrap_or(name);
+    registry.register_backend_tools_namespaced(name, namespace, tools);
+    Ok(tool_count)
+}
+
+/// Convert call arguments to the JSON object MCP expects.
+///
+/// `None` and `null` mean "no arguments"; any other non-object value is
+/// rejected here instead of being silently dropped.
+pub(crate) fn arguments_object(
+    backend_name: &str,
+    tool_name: &str,
+    arguments: Option<Value>,
+) -> Result<Option<serde_json::Map<String, Value>>> {
+    match arguments {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Object(map)) => Ok(Some(map)),
+        Some(other) => Err(GatewayError::InvalidArguments {
+            backend: backend_name.to_string(),
+            tool: tool_name.to_string(),
+            message: format!("arguments must be a JSON object, got {other}"),
+        }
+        .into()),
+    }
+}
+
+/// Send `tools/call` over a backend's rmcp connection and wait for the result.
+///
+/// Goes through rmcp's request handle rather than `Peer::call_tool` so the
+/// progress token rmcp assigns to the request is known and can be routed to
+/// the calling chain (see [`progress`]), and so the request can be cancelled:
+/// if no response arrives before `timeout`, or the call is dropped because the
+/// client cancelled it, the backend is sent `notifications/cancelled`.
+pub(crate) async fn send_tool_call(
+    peer: &rmcp::service::Peer<rmcp::RoleClient>,
+    params: rmcp::model::CallToolRequestParams,
+    sessions: Option<&sessions::ClientSessions>,
+    backend_name: &str,
+    timeout: Duration,
+) -> Result<CallToolResult> {
+    use rmcp::model::{ClientRequest, Request, ServerResult};
+    use rmcp::service::{PeerRequestOptions, ServiceError};
+
+    let tool_name = params.name.to_string();
+    let service_error = |e: ServiceError| GatewayError::from_service(backend_name, &tool_name, e);
+
+    let handle = peer
+        .send_cancellable_request(
+            ClientRequest::CallToolRequest(Request::new(params)),
+            PeerRequestOptions::no_options(),
+        )
+        .await
+        .map_err(service_error)?;
+    let _progress =
+        sessions.and_then(|s| progress::route_call(s, backend_name, handle.progress_token.clone()));
+    let mut pending = PendingRequest(Some(handle));
+
+    let response = tokio::time::timeout(timeout, pending.response())
+        .await
+        .map_err(|_| GatewayError::Timeout {
+            backend: backend_name.to_string(),
+            tool: tool_name.clone(),
+            timeout,
+        })?;
+    match response {
+        Ok(ServerResult::CallToolResult(result)) => Ok(result),
+        Ok(_) => Err(service_error(ServiceError::UnexpectedResponse).into()),
+        Err(e) => Err(service_error(e).into()),
+    }
+}
+
+/// A session opened with [`BackendManager::detached_session`]. Dropping it
+/// releases the session's pool instances.
+pub struct DetachedSession {
+    manager: Arc<BackendManager>,
+    session_id: u64,
+}
+
+impl DetachedSession {
+    pub fn id(&self) -> u64 {
+        self.session_id
+    }
+}
+
+impl Drop for DetachedSession {
+    fn drop(&mut self) {
+        let manager = Arc::clone(&self.manager);
+        let session_id = self.session_id;
+        debug!(session = session_id, "releasing detached session");
+        tokio::spawn(async move { manager.release_session(session_id).await });
+    }
+}
+
+/// A backend request awaiting its response. Dropped before the response
+/// arrives, it tells the backend to stop working on the request.
+struct PendingRequest(Option<rmcp::service::RequestHandle<rmcp::RoleClient>>);
+
+impl PendingRequest {
+    async fn response(&mut self) -> Result<rmcp::model::ServerResult, rmcp::service::ServiceError> {
+        let Some(handle) = self.0.as_mut() else {
+            return Err(rmcp::service::ServiceError::TransportClosed);
+        };
+        let response = (&mut handle.rx)
+            .await
+            .unwrap_or(Err(rmcp::service::ServiceError::TransportClosed));
+        self.0 = None;
+        response
+    }
+}
+
+impl Drop for PendingRequest {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            debug!(request = %handle.id, "cancelling backend request");
+            tokio::spawn(async move {
+                let _ = handle
+                    .cancel(Some("cancelled by gatemini client".to_string()))
+                    .await;
+            });
+        }
+    }
 }
 
 /// Map rmcp Tool list to ToolEntry vec.
@@ -95,6 +285,7 @@ pub(crate) fn map_tools_to_entries(
         .into_iter()
         .map(|t| {
             let name = t.name.to_string();
+            let annotations = map_tool_annotations(&t);
             ToolEntry {
                 original_name: name.clone(),
                 name,
@@ -103,11 +294,34 @@ pub(crate) fn map_tools_to_entries(
                 input_schema: serde_json::to_value(&t.input_schema)
                     .unwrap_or(Value::Object(Default::default())),
                 tags: Vec::new(),
+                output_schema: t
+                    .output_schema
+                    .as_ref()
+                    .and_then(|schema| serde_json::to_value(schema).ok()),
+                annotations,
             }
         })
         .collect()
 }
 
+/// Convert rmcp tool annotations (plus the top-level `title`) to the registry form.
+fn map_tool_annotations(tool: &rmcp::model::Tool) -> Option<crate::registry::ToolAnnotations> {
+    let title = tool.title.clone();
+    match &tool.annotations {
+        Some(a) => Some(crate::registry::ToolAnnotations {
+            title: title.or_else(|| a.title.clone()),
+            read_only_hint: a.read_only_hint,
+            destructive_hint: a.destructive_hint,
+            idempotent_hint: a.idempotent_hint,
+            open_world_hint: a.open_world_hint,
+        }),
+        None => title.map(|title| crate::registry::ToolAnnotations {
+            title: Some(title),
+            ..Default::default()
+        }),
+    }
+}
+
 /// Read BackendState from an AtomicU8.
 pub(crate) fn state_from_atomic(state: &AtomicU8) -> BackendState {
     match state.load(Ordering::Acquire) {
@@ -176,6 +390,12 @@ pub trait Backend: Send + Sync {
     fn pid(&self) -> Option<u32> {
         None
     }
+
+    /// rmcp peer of the live backend connection, for forwarding requests other
+    /// than tool calls (resources, prompts). `None` for non-MCP backends.
+    async fn peer(&self) -> Option<rmcp::service::Peer<rmcp::RoleClient>> {
+        None
+    }
 }
 
 /// RAII guard that tracks in-flight calls for graceful drain on shutdown.
@@ -194,6 +414,37 @@ impl Drop for CallGuard {
     }
 }
 
+/// Which schema-driven argument checks run before a backend's calls.
+#[derive(Debug, Clone, Copy)]
+struct ArgumentChecks {
+    validate: bool,
+    coerce: bool,
+}
+
+impl Default for ArgumentChecks {
+    fn default() -> Self {
+        Self {
+            validate: true,
+            coerce: false,
+        }
+    }
+}
+
+impl From<&BackendConfig> for ArgumentChecks {
+    fn from(config: &BackendConfig) -> Self {
+        Self {
+            validate: config.validate_arguments,
+            coerce: config.coerce_arguments,
+        }
+    }
+}
+
+/// A backend's tool input schemas, keyed by the backend's tool name.
+struct ToolSchemas {
+    checks: ArgumentChecks,
+    schemas: std::collections::HashMap<String, Value>,
+}
+
 /// Manages all backends: startup, shutdown, tool forwarding.
 pub struct BackendManager {
     #[cfg(not(test))]
@@ -227,6 +478,11 @@ pub struct BackendManager {
     rate_limiter_handles: DashMap<String, tokio::task::JoinHandle<()>>,
     #[cfg(test)]
     pub rate_limiter_handles: DashMap<String, tokio::task::JoinHandle<()>>,
+    /// Per-backend result caches for read-only tools (`cache` config).
+    result_caches: DashMap<String, Arc<result_cache::ResultCache>>,
+    /// Per-backend tool input schemas, for backends that validate or coerce
+    /// arguments (`validate_arguments`, `coerce_arguments` config).
+    tool_schemas: DashMap<String, ToolSchemas>,
     /// Backends registered at runtime via register_manual (not from config file).
     dynamic_backends: RwLock<HashSet<String>>,
     /// PIDs of managed prerequisite processes (stopped on daemon shutdown).
@@ -242,10 +498,22 @@ pub struct BackendManager {
     dedicated_pools: DashMap<String, Arc<pool::InstancePool>>,
     /// Per-backend memory statistics from RSS sampling.
     memory_stats: DashMap<String, memory::MemoryStats>,
+    /// Sender handed to each backend's rmcp client handler.
+    events: events::BackendEventSender,
+    /// Receiving end of `events`, taken once by the event listener.
+    event_rx: std::sync::Mutex<Option<mpsc::UnboundedReceiver<events::BackendEvent>>>,
+    /// Fan-out of gateway changes to every connected client session.
+    client_notices: broadcast::Sender<events::ClientNotice>,
+    /// Client sessions subscribed to each proxied backend resource URI.
+    resource_subscriptions: resources::ResourceSubscriptions,
+    /// Connected client sessions and their in-flight calls, for routing
+    /// backend requests (elicitation, sampling) to the session that caused them.
+    sessions: Arc<sessions::ClientSessions>,
 }
 
 impl BackendManager {
     pub fn new() -> Arc<Self> {
+        let (events, event_rx) = mpsc::unbounded_channel();
         Arc::new(Self {
             backends: DashMap::new(),
             configs: RwLock::new(std::collections::HashMap::new()),
@@ -255,12 +523,19 @@ impl BackendManager {
             retry_configs: DashMap::new(),
             rate_limiters: DashMap::new(),
             rate_limiter_handles: DashMap::new(),
+            result_caches: DashMap::new(),
+            tool_schemas: DashMap::new(),
             dynamic_backends: RwLock::new(HashSet::new()),
             prerequisite_pids: DashMap::new(),
             drain_timeout: Duration::from_secs(10),
             tracker: None,
             dedicated_pools: DashMap::new(),
             memory_stats: DashMap::new(),
+            events,
+            event_rx: std::sync::Mutex::new(Some(event_rx)),
+            client_notices: broadcast::channel(CLIENT_NOTICE_CAPACITY).0,
+            resource_subscriptions: DashMap::new(),
+            sessions: sessions::ClientSessions::new(),
         })
     }
 
@@ -269,6 +544,7 @@ impl BackendManager {
         health_config: &crate::config::HealthConfig,
         tracker: Option<Arc<crate::tracker::CallTracker>>,
     ) -> Arc<Self> {
+        let (events, event_rx) = mpsc::unbounded_channel();
         Arc::new(Self {
             backends: DashMap::new(),
             configs: RwLock::new(std::collections::HashMap::new()),
@@ -278,12 +554,19 @@ impl BackendManager {
             retry_configs: DashMap::new(),
             rate_limiters: DashMap::new(),
             rate_limiter_handles: DashMap::new(),
+            result_caches: DashMap::new(),
+            tool_schemas: DashMap::new(),
             dynamic_backends: RwLock::new(HashSet::new()),
             prerequisite_pids: DashMap::new(),
             drain_timeout: health_config.drain_timeout,
             tracker,
             dedicated_pools: DashMap::new(),
             memory_stats: DashMap::new(),
+            events,
+            event_rx: std::sync::Mutex::new(Some(event_rx)),
+            client_notices: broadcast::channel(CLIENT_NOTICE_CAPACITY).0,
+            resource_subscriptions: DashMap::new(),
+            sessions: sessions::ClientSessions::new(),
         })
     }
 
@@ -359,12 +642,16 @@ impl BackendManager {
 
         let backend: Arc<dyn Backend> = match config.transport {
             Transport::Stdio => {
-                let b = stdio::StdioBackend::new(name.to_string(), config.clone());
+                let b = stdio::StdioBackend::new(name.to_string(), config.clone())
+                    .with_events(self.events.clone())
+                    .with_sessions(Arc::clone(&self.sessions));
                 b.start().await?;
                 Arc::new(b)
             }
             Transport::StreamableHttp => {
-                let b = http::HttpBackend::new(name.to_string(), config.clone());
+                let b = http::HttpBackend::new(name.to_string(), config.clone())
+                    .with_events(self.events.clone())
+                    .with_sessions(Arc::clone(&self.sessions));
                 b.start().await?;
                 Arc::new(b)
             }
@@ -375,27 +662,26 @@ impl BackendManager {
             }
         };
 
-        // Discover tools and propagate config tags
-        let mut tools = backend.discover_tools().await?;
-        if !config.tags.is_empty() {
-            for tool in &mut tools {
-                tool.tags.clone_from(&config.tags);
-            }
+        let tool_count = register_discovered_tools(name, Some(config), &*backend, registry).await?;
+        // Prompts are optional: a backend whose prompts/list fails still serves tools.
+        if let Err(e) =
+            prompts::register_discovered_prompts(name, Some(config), &*backend, registry).await
+        {
+            warn!(backend = %name, error = %e, "prompt discovery failed");
         }
-        let tool_count = tools.len();
-
-        // Register in registry with namespace
-        let namespace = config.namespace.as_deref().unwrap_or(name);
-        registry.register_backend_tools_namespaced(name, namespace, tools);
 
         // Store backend (shared path or primary instance for health checks)
         self.backends.insert(name.to_string(), Arc::clone(&backend));
 
         // Create dedicated instance pool if configured
         if is_dedicated {
-            let pool =
-                pool::InstancePool::new(name.to_string(), config.clone(), Arc::clone(registry))
-                    .await?;
+            let pool = pool::InstancePool::new(
+                name.to_string(),
+                config.clone(),
+                Arc::clone(registry),
+                Some(Arc::clone(&self.sessions)),
+            )
+            .await?;
             self.dedicated_pools
                 .insert(name.to_string(), Arc::new(pool));
             info!(backend = %name, "dedicated instance pool active");
@@ -444,11 +730,26 @@ impl BackendManager {
             self.rate_limiter_handles.insert(name.to_string(), handle);
         }
 
+        // Set up the result cache if configured. Dedicated instances keep
+        // per-session state, so their results are never shared.
+        if let Some(ref cache_config) = config.cache {
+            if is_dedicated {
+                warn!(backend = %name, "result cache ignored for dedicated instance mode");
+            } else {
+                self.result_caches.insert(
+                    name.to_string(),
+                    Arc::new(result_cache::ResultCache::new(cache_config)),
+                );
+            }
+        }
+        self.index_tools(name, ArgumentChecks::from(config), registry);
+
         // Spawn reaper task for stdio backends — monitors child process and
         // marks backend as Stopped immediately on unexpected exit.
         // The health checker will then auto-restart it with backoff.
         if is_stdio {
             let reaper_name = name.to_string();
+            let client_notices = self.client_notices.clone();
             tokio::spawn(async move {
                 if let Some(status) = backend.wait_for_exit().await
                     && backend.state() != BackendState::Stopped
@@ -459,10 +760,23 @@ impl BackendManager {
                         "backend process exited unexpectedly"
                     );
                     backend.set_state(BackendState::Stopped);
+                    let _ = client_notices.send(logging::gateway_event(
+                        LoggingLevel::Warning,
+                        &reaper_name,
+                        format!(
+                            "backend process exited unexpectedly (exit code {:?}), marked stopped",
+                            status.code()
+                        ),
+                    ));
                 }
             });
         }
 
+        self.log_to_clients(
+            LoggingLevel::Info,
+            name,
+            format!("backend started with {tool_count} tools"),
+        );
         Ok(tool_count)
     }
 
@@ -509,6 +823,8 @@ impl BackendManager {
         if let Some((_, handle)) = self.rate_limiter_handles.remove(name) {
             handle.abort();
         }
+        self.result_caches.remove(name);
+        self.tool_schemas.remove(name);
         if let Some((_, pool)) = self.dedicated_pools.remove(name) {
             pool.stop_all().await;
         }
@@ -532,12 +848,97 @@ impl BackendManager {
     /// Checks rate limiter before acquiring concurrency semaphore.
     /// Acquires a per-backend semaphore permit before dispatching. If the semaphore
     /// is full, the call queues with a configurable timeout (default 60s).
+    ///
+    /// Failures are [`GatewayError`]s; their kind is counted in the tracker.
+    /// Arguments that do not fit the tool's input schema fail with
+    /// [`GatewayError::InvalidArguments`] before anything is sent; backends
+    /// with `coerce_arguments` first get common mistakes repaired.
+    ///
+    /// Calls to read-only tools of a backend with a result cache are answered
+    /// from the cache when possible, and identical calls in flight share one
+    /// backend request (see [`result_cache`]).
     pub async fn call_tool(
         &self,
         backend_name: &str,
         tool_name: &str,
         arguments: Option<Value>,
         session_id: Option<u64>,
+    ) -> Result<Value> {
+        let cache = self
+            .result_caches
+            .get(backend_name)
+            .map(|r| Arc::clone(r.value()))
+            .filter(|cache| cache.is_cacheable(tool_name));
+        let result = match (
+            self.prepare_arguments(backend_name, tool_name, arguments),
+            cache,
+        ) {
+            (Err(e), _) => Err(e),
+            (Ok(arguments), Some(cache)) => {
+                self.call_cached(&cache, backend_name, tool_name, arguments, session_id)
+                    .await
+            }
+            (Ok(arguments), None) => {
+                self.dispatch_tool_call(backend_name, tool_name, arguments, session_id)
+                    .await
+            }
+        };
+        if let (Err(e), Some(tracker)) = (&result, &self.tracker) {
+            tracker.record_error_kind(crate::error::error_kind(e));
+        }
+        result
+    }
+
+    async fn call_cached(
+        &self,
+        cache: &result_cache::ResultCache,
+        backend_name: &str,
+        tool_name: &str,
+        arguments: Option<Value>,
+        session_id: Option<u64>,
+    ) -> Result<Value> {
+        use crate::tracker::CacheOutcome;
+        use result_cache::Lookup;
+
+        let record = |outcome| {
+            if let Some(ref tracker) = self.tracker {
+                tracker.record_cache(backend_name, outcome);
+            }
+        };
+        match cache.lookup(tool_name, arguments.as_ref()) {
+            Lookup::Hit(value) => {
+                record(CacheOutcome::Hit);
+                Ok(value)
+            }
+            Lookup::Wait(waiter) => match waiter.result().await {
+                Some(value) => {
+                    record(CacheOutcome::Coalesced);
+                    Ok(value)
+                }
+                // The leading call failed: make our own.
+                None => {
+                    record(CacheOutcome::Miss);
+                    self.dispatch_tool_call(backend_name, tool_name, arguments, session_id)
+                        .await
+                }
+            },
+            Lookup::Lead(flight) => {
+                record(CacheOutcome::Miss);
+                let result = self
+                    .dispatch_tool_call(backend_name, tool_name, arguments, session_id)
+                    .await;
+                cache.finish(flight, &result);
+                result
+            }
+        }
+    }
+
+    async fn dispatch_tool_call(
+        &self,
+        backend_name: &str,
+        tool_name: &str,
+        arguments: Option<Value>,
+        session_id: Option<u64>,
     ) -> Result<Value> {
         let _guard = CallGuard::new(&self.in_flight_calls);
 
@@ -554,8 +955,13 @@ impl BackendManager {
                 )
             })?;
             let instance = pool.acquire(sid).await?;
+            let _in_flight = self.sessions.begin_call(instance.name(), sid);
             let start = std::time::Instant::now();
-            let result = instance.call_tool(tool_name, arguments).await;
+            let result = progress::track_call(
+                format!("{backend_name}.{tool_name}"),
+                instance.call_tool(tool_name, arguments),
+            )
+            .await;
             if let Some(ref tracker) = self.tracker {
                 tracker.record(tool_name, backend_name, start.elapsed(), result.is_ok());
             }
@@ -569,10 +975,10 @@ impl BackendManager {
             match rate_sem.try_acquire() {
                 Ok(permit) => permit.forget(),
                 Err(_) => {
-                    anyhow::bail!(
-                        "backend '{}' rate limit exceeded. Try again later.",
-                        backend_name
-                    );
+                    return Err(GatewayError::RateLimited {
+                        backend: backend_name.to_string(),
+                    }
+                    .into());
                 }
             }
         }
@@ -592,19 +998,17 @@ impl BackendManager {
             match tokio::time::timeout(timeout, sem.acquire_owned()).await {
                 Ok(Ok(permit)) => Some(permit),
                 Ok(Err(_)) => {
-                    anyhow::bail!(
-                        "backend '{}' semaphore closed (backend shutting down)",
-                        backend_name
-                    );
+                    return Err(GatewayError::ShuttingDown {
+                        backend: backend_name.to_string(),
+                    }
+                    .into());
                 }
                 Err(_) => {
-                    anyhow::bail!(
-                        "backend '{}' is at max concurrent calls. \
-                         Timed out after {:?} waiting for a permit. \
-                         Consider increasing max_concurrent_calls for this backend.",
-                        backend_name,
-                        timeout
-                    );
+                    return Err(GatewayError::SemaphoreTimeout {
+                        backend: backend_name.to_string(),
+                        waited: timeout,
+                    }
+                    .into());
                 }
             }
         } else {
@@ -631,8 +1035,14 @@ impl BackendManager {
                     let state = b.state();
                     match state {
                         BackendState::Healthy => {
+                            let _in_flight =
+                                session_id.map(|sid| self.sessions.begin_call(backend_name, sid));
                             let start = std::time::Instant::now();
-                            let result = b.call_tool(tool_name, arguments).await;
+                            let result = progress::track_call(
+                                format!("{backend_name}.{tool_name}"),
+                                b.call_tool(tool_name, arguments),
+                            )
+                            .await;
                             if let Some(ref tracker) = self.tracker {
                                 tracker.record(
                                     tool_name,
@@ -656,13 +1066,11 @@ impl BackendManager {
                         }
                         // Unhealthy or Stopped — fail immediately, no point retrying
                         _ => {
-                            anyhow::bail!(
-                                "backend '{}' is not available (state: {:?}). \
-                                 Check status: @gatemini://backend/{}",
-                                backend_name,
+                            return Err(GatewayError::BackendUnavailable {
+                                backend: backend_name.to_string(),
                                 state,
-                                backend_name
-                            );
+                            }
+                            .into());
                         }
                     }
                 }
@@ -682,37 +1090,22 @@ impl BackendManager {
         }
 
         // All retries exhausted — produce a descriptive error
-        match self.backends.get(backend_name).map(|r| r.value().state()) {
-            Some(BackendState::Starting) => {
-                anyhow::bail!(
-                    "backend '{}' is still starting (retried {} times). \
-                     Tool '{}' is cached but the backend hasn't connected yet. \
-                     Check status: @gatemini://backend/{}",
-                    backend_name,
-                    retry.max_retries,
-                    tool_name,
-                    backend_name
-                )
-            }
-            Some(state) => {
-                anyhow::bail!(
-                    "backend '{}' is not available (state: {:?}). \
-                     Check status: @gatemini://backend/{}",
-                    backend_name,
-                    state,
-                    backend_name
-                )
-            }
-            None => {
-                anyhow::bail!(
-                    "backend '{}' not found after {} retries. \
-                     It may not be configured or failed to start. \
-                     See all backends: @gatemini://backends",
-                    backend_name,
-                    retry.max_retries
-                )
-            }
-        }
+        let err = match self.backends.get(backend_name).map(|r| r.value().state()) {
+            Some(BackendState::Starting) => GatewayError::BackendStarting {
+                backend: backend_name.to_string(),
+                tool: tool_name.to_string(),
+                retries: retry.max_retries,
+            },
+            Some(state) => GatewayError::BackendUnavailable {
+                backend: backend_name.to_string(),
+                state,
+            },
+            None => GatewayError::BackendNotFound {
+                backend: backend_name.to_string(),
+                retries: retry.max_retries,
+            },
+        };
+        Err(err.into())
     }
 
     /// Call a tool with fallback chain support.
@@ -738,7 +1131,7 @@ impl BackendManager {
         let err = result.unwrap_err();
 
         // Only attempt fallback on transient errors
-        if !is_transient_error(&err) {
+        if !crate::error::is_transient(&err) {
             return Err(err);
         }
 
@@ -779,6 +1172,13 @@ impl BackendManager {
                             tool = %original_name,
                             "fallback succeeded"
                         );
+                        self.log_to_clients(
+                            LoggingLevel::Notice,
+                            backend_name,
+                            format!(
+                                "call to '{original_name}' failed ({err}), served by fallback backend '{fallback_name}'"
+                            ),
+                        );
                         return Ok(result);
                     }
                     Err(fallback_err) => {
@@ -796,9 +1196,33 @@ impl BackendManager {
         Err(err)
     }
 
-    /// Release all dedicated pool instances assigned to a session.
-    /// Called when a proxy client disconnects.
+    /// Allocate an id for a new client session.
+    pub fn new_session_id(&self) -> u64 {
+        self.sessions.new_id()
+    }
+
+    /// Open a session for work that outlives the client session `origin`
+    /// (a `call_tool_chain` task), so it holds its own dedicated pool
+    /// instances. Backend requests are still routed to `origin`'s client
+    /// while it is connected. The session is released when the guard drops.
+    pub fn detached_session(self: &Arc<Self>, origin: Option<u64>) -> DetachedSession {
+        let session_id = self.new_session_id();
+        if let Some(peer) = origin.and_then(|origin| self.sessions.peer(origin)) {
+            self.sessions.register(session_id, peer);
+        }
+        DetachedSession {
+            manager: Arc::clone(self),
+            session_id,
+        }
+    }
+
+    /// Release all dedicated pool instances and resource subscriptions held
+    /// by a session, and stop routing backend requests to it. Called when a
+    /// proxy client disconnects or a [`DetachedSession`] is dropped.
     pub async fn release_session(&self, session_id: u64) {
+        self.sessions.remove(session_id);
+        self.release_resource_subscriptions(session_id).await;
+
         let pools: Vec<(String, Arc<pool::InstancePool>)> = self
             .dedicated_pools
             .iter()
@@ -817,6 +1241,13 @@ impl BackendManager {
         self.dedicated_pools.contains_key(name)
     }
 
+    /// Calls made to a backend and how many of them failed (including
+    /// `isError` results), if calls are tracked.
+    pub fn call_counts(&self, name: &str) -> Option<(u64, u64)> {
+        let tracker = self.tracker.as_ref()?;
+        Some((tracker.call_count(name), tracker.error_count(name)))
+    }
+
     /// Register a pre-built backend directly (e.g. CompositeBackend).
     ///
     /// Unlike `add_backend` / `start_backend`, this does NOT spawn a child process
@@ -956,6 +1387,142 @@ impl BackendManager {
             .map_err(|e| anyhow::anyhow!("ping failed for '{name}': {e}"))
     }
 
+    /// Re-run tool discovery on a live backend and replace its registry entries.
+    ///
+    /// Called when a backend sends `notifications/tools/list_changed`.
+    pub async fn refresh_tools(&self, name: &str, registry: &ToolRegistry) -> Result<usize> {
+        let backend = self
+            .backends
+            .get(name)
+            .map(|r| Arc::clone(r.value()))
+            .ok_or_else(|| anyhow::anyhow!("backend '{name}' not found"))?;
+        let state = backend.state();
+        if state != BackendState::Healthy {
+            return Err(GatewayError::BackendUnavailable {
+                backend: name.to_string(),
+                state,
+            }
+            .into());
+        }
+        let config = self.configs.read().await.get(name).cloned();
+        let tool_count =
+            register_discovered_tools(name, config.as_ref(), &*backend, registry).await?;
+        let checks = config
+            .as_ref()
+            .map_or_else(ArgumentChecks::default, ArgumentChecks::from);
+        self.index_tools(name, checks, registry);
+        Ok(tool_count)
+    }
+
+    /// Update the state derived from a backend's tool list after discovery:
+    /// its input schemas (if any argument checks are on) and which tools its
+    /// cache covers.
+    fn index_tools(&self, name: &str, checks: ArgumentChecks, registry: &ToolRegistry) {
+        let tools = registry.get_by_backend(name);
+        if let Some(cache) = self.result_caches.get(name) {
+            cache.set_tools(&tools);
+        }
+        if checks.validate || checks.coerce {
+            let schemas = tools
+                .into_iter()
+                .map(|t| {
+                    // Normalized, so refs into `$defs` are checked too.
+                    let schema = t.normalized_input_schema();
+                    let name = if t.original_name.is_empty() {
+                        t.name
+                    } else {
+                        t.original_name
+                    };
+                    (name, schema)
+                })
+                .collect();
+            self.tool_schemas
+                .insert(name.to_string(), ToolSchemas { checks, schemas });
+        } else {
+            self.tool_schemas.remove(name);
+        }
+    }
+
+    /// Repair and check arguments against the tool's input schema, as the
+    /// backend's config asks, if its schemas are known. Returns the arguments
+    /// to send.
+    fn prepare_arguments(
+        &self,
+        backend_name: &str,
+        tool_name: &str,
+        mut arguments: Option<Value>,
+    ) -> Result<Option<Value>> {
+        let Some(entry) = self.tool_schemas.get(backend_name) else {
+            return Ok(arguments);
+        };
+        let Some(schema) = entry.schemas.get(tool_name) else {
+            return Ok(arguments);
+        };
+        if entry.checks.coerce
+            && let Some(ref mut args) = arguments
+        {
+            for repair in crate::schema::coerce(schema, args) {
+                debug!(
+                    backend = %backend_name,
+                    tool = %tool_name,
+                    path = %repair.path,
+                    repair = repair.kind.label(),
+                    "coerced tool argument"
+                );
+                if let Some(ref tracker) = self.tracker {
+                    tracker.record_coercion(repair.kind.label());
+                }
+            }
+        }
+        if entry.checks.validate
+            && let Err(errors) = crate::schema::validate(schema, arguments.as_ref())
+        {
+            return Err(GatewayError::InvalidArguments {
+                backend: backend_name.to_string(),
+                tool: tool_name.to_string(),
+                message: format!("arguments do not match the input schema:\n{errors}"),
+            }
+            .into());
+        }
+        Ok(arguments)
+    }
+
+    /// Register an initialized client session so backend requests caused by
+    /// its calls (elicitation, sampling) can be routed back to it. The
+    /// returned token is cancelled when the session is released.
+    pub fn register_client_session(
+        &self,
+        session_id: u64,
+        peer: rmcp::service::Peer<rmcp::RoleServer>,
+    ) -> CancellationToken {
+        self.sessions.register(session_id, peer)
+    }
+
+    /// Take the receiver for backend notifications. Returns `None` after the
+    /// first call; only one event listener runs per manager.
+    pub fn take_event_receiver(&self) -> Option<mpsc::UnboundedReceiver<events::BackendEvent>> {
+        self.event_rx
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+    }
+
+    /// Sender for backend notifications, as given to backend client handlers.
+    pub fn event_sender(&self) -> events::BackendEventSender {
+        self.events.clone()
+    }
+
+    /// Subscribe a client session to gateway change notices.
+    pub fn subscribe_client_notices(&self) -> broadcast::Receiver<events::ClientNotice> {
+        self.client_notices.subscribe()
+    }
+
+    /// Tell every connected client session about a gateway change.
+    pub fn notify_clients(&self, notice: events::ClientNotice) {
+        // Err only means no session is connected right now.
+        let _ = self.client_notices.send(notice);
+    }
+
     /// Restart a backend: stop it, re-read config, start fresh, re-discover tools.
     pub async fn restart_backend(&self, name: &str, registry: &Arc<ToolRegistry>) -> Result<usize> {
         self.cleanup_backend_components(name).await;
@@ -994,6 +1561,8 @@ impl BackendManager {
             handle.abort();
         }
         self.rate_limiters.remove(name);
+        self.result_caches.remove(name);
+        self.tool_schemas.remove(name);
 
         self.memory_stats.remove(name);
 
@@ -1030,8 +1599,23 @@ impl BackendManager {
 
     /// Set the state of a backend (used by health checker for circuit breaker).
     pub fn set_backend_state(&self, name: &str, state: BackendState) {
-        if let Some(backend) = self.backends.get(name) {
+        let Some(previous) = self.backends.get(name).map(|backend| {
+            let previous = backend.state();
             backend.set_state(state);
+            previous
+        }) else {
+            return;
+        };
+        if previous != state {
+            let level = match state {
+                BackendState::Healthy | BackendState::Starting => LoggingLevel::Info,
+                BackendState::Unhealthy | BackendState::Stopped => LoggingLevel::Warning,
+            };
+            self.log_to_clients(
+                level,
+                name,
+                format!("backend state changed from {previous:?} to {state:?}"),
+            );
         }
     }
 
@@ -1149,6 +1733,99 @@ mod map_result_tests {
         assert!(value.is_string());
         assert_eq!(value.as_str().unwrap(), "hello");
     }
+
+    #[test]
+    fn test_image_content_keeps_wire_shape() {
+        let result = CallToolResult::success(vec![Content::image("iVBORw0KGgo=", "image/png")]);
+        let value = map_call_tool_result(result);
+        assert_eq!(value["type"], "image");
+        assert_eq!(value["data"], "iVBORw0KGgo=");
+        assert_eq!(value["mimeType"], "image/png");
+        assert!(crate::tools::content::is_content_block(&value));
+    }
+
+    #[test]
+    fn test_mixed_text_and_resource_content() {
+        let result = CallToolResult::success(vec![
+            Content::text("rendered report"),
+            Content::embedded_text("file:///tmp/report.md", "# Report"),
+        ]);
+        let value = map_call_tool_result(result);
+        let arr = value.as_array().unwrap();
+        assert_eq!(arr[0], "rendered report");
+        assert_eq!(arr[1]["type"], "resource");
+        assert_eq!(arr[1]["resource"]["uri"], "file:///tmp/report.md");
+        assert_eq!(arr[1]["resource"]["text"], "# Report");
+    }
+
+    #[test]
+    fn test_outcome_is_error_becomes_tool_reported_error() {
+        let result = CallToolResult::error(vec![Content::text("repository not found")]);
+        let err = map_call_tool_outcome(result, "github", "get_repo").unwrap_err();
+        let Some(GatewayError::ToolReported {
+            backend,
+            tool,
+            message,
+            ..
+        }) = GatewayError::find(&err)
+        else {
+            panic!("isError should map to GatewayError::ToolReported, got {err}");
+        };
+        assert_eq!(backend, "github");
+        assert_eq!(tool, "get_repo");
+        assert_eq!(message, "repository not found");
+        assert!(err.to_string().contains("reported an error"));
+        assert!(
+            !crate::error::is_transient(&err),
+            "tool-reported errors must not trigger fallback"
+        );
+    }
+
+    #[test]
+    fn test_arguments_object_rejects_non_objects() {
+        assert!(arguments_object("b", "t", None).unwrap().is_none());
+        assert!(
+            arguments_object("b", "t", Some(Value::Null))
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            arguments_object("b", "t", Some(serde_json::json!({"q": 1})))
+                .unwrap()
+                .is_some()
+        );
+        let err = arguments_object("b", "t", Some(serde_json::json!([1, 2]))).unwrap_err();
+        assert_eq!(crate::error::error_kind(&err), "invalid_arguments");
+    }
+
+    #[test]
+    fn test_outcome_prefers_structured_content() {
+        let mut result = CallToolResult::success(vec![Content::text("temp is 21C")]);
+        result.structured_content = Some(serde_json::json!({"temp": 21, "unit": "C"}));
+        let value = map_call_tool_outcome(result, "wx", "get_weather").unwrap();
+        assert_eq!(value, serde_json::json!({"temp": 21, "unit": "C"}));
+    }
+
+    #[test]
+    fn test_outcome_structured_content_keeps_media_blocks() {
+        let mut result = CallToolResult::success(vec![
+            Content::text(r#"{"pages": 1}"#),
+            Content::image("iVBORw0KGgo=", "image/png"),
+        ]);
+        result.structured_content = Some(serde_json::json!({"pages": 1}));
+        let value = map_call_tool_outcome(result, "pdf", "render").unwrap();
+        let arr = value.as_array().unwrap();
+        assert_eq!(arr.len(), 2);
+        assert_eq!(arr[0], serde_json::json!({"pages": 1}));
+        assert_eq!(arr[1]["type"], "image");
+    }
+
+    #[test]
+    fn test_outcome_without_structured_content_maps_text() {
+        let result = CallToolResult::success(vec![Content::text(r#"{"ok": true}"#)]);
+        let value = map_call_tool_outcome(result, "b", "t").unwrap();
+        assert_eq!(value["ok"], true);
+    }
 }
 
 #[cfg(test)]
@@ -1236,6 +1913,9 @@ mod backend_manager_lifecycle_tests {
             retry: Default::default(),
             prerequisite: None,
             rate_limit: None,
+            cache: None,
+            validate_arguments: true,
+            coerce_arguments: false,
             tags: Vec::new(),
             fallback_chain: Vec::new(),
             tools: None,
@@ -1251,6 +1931,9 @@ mod backend_manager_lifecycle_tests {
             shutdown_grace_period: Duration::from_secs(1),
             max_memory_mb: None,
             oauth: None,
+            elicitation: Default::default(),
+            sampling: Default::default(),
+            pinned_tools: Vec::new(),
         }
     }
 
@@ -1292,12 +1975,24 @@ mod backend_manager_lifecycle_tests {
             },
         );
 
-        let pool = InstancePool::new(name.to_string(), dedicated_pool_backend_config(), registry)
-            .await
-            .expect("dedicated pool should construct without spawning instances");
+        let pool = InstancePool::new(
+            name.to_string(),
+            dedicated_pool_backend_config(),
+            registry,
+            None,
+        )
+        .await
+        .expect("dedicated pool should construct without spawning instances");
         manager
             .dedicated_pools
             .insert(name.to_string(), Arc::new(pool));
+        manager.tool_schemas.insert(
+            name.to_string(),
+            ToolSchemas {
+                checks: ArgumentChecks::default(),
+                schemas: HashMap::new(),
+            },
+        );
 
         manager.cleanup_backend_components(name).await;
 
@@ -1310,5 +2005,26 @@ mod backend_manager_lifecycle_tests {
         assert!(!manager.rate_limiter_handles.contains_key(name));
         assert!(!manager.memory_stats.contains_key(name));
         assert!(!manager.dedicated_pools.contains_key(name));
+        assert!(!manager.tool_schemas.contains_key(name));
+    }
+
+    #[tokio::test]
+    async fn detached_session_is_released_on_drop() {
+        let manager = BackendManager::new();
+        let client = manager.new_session_id();
+        let session = manager.detached_session(Some(client));
+        let id = session.id();
+        assert_ne!(id, client);
+
+        manager.set_client_log_level(id, LoggingLevel::Debug);
+        assert!(manager.wants_log(id, LoggingLevel::Debug));
+        drop(session);
+        for _ in 0..100 {
+            if !manager.wants_log(id, LoggingLevel::Debug) {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("detached session {id} was not released");
     }
 }
@@ -13,8 +13,10 @@ use anyhow::Result;
 use tokio::sync::{Mutex, Notify, Semaphore};
 use tracing::{debug, info, warn};
 
+use super::sessions::ClientSessions;
 use super::{Backend, BackendState};
-use crate::config::{BackendConfig, Transport};
+use crate::config::{BackendConfig, SamplingPolicy, Transport};
+use crate::error::GatewayError;
 use crate::registry::ToolRegistry;
 
 /// A pool of backend instances that provides per-session isolation.
@@ -27,6 +29,8 @@ pub struct InstancePool {
     config: BackendConfig,
     #[allow(dead_code)] // retained to preserve the pool constructor contract
     registry: Arc<ToolRegistry>,
+    /// Client sessions that instance requests (elicitation, sampling) are routed to.
+    sessions: Option<Arc<ClientSessions>>,
     /// Idle instances ready to be assigned.
     idle: Mutex<VecDeque<Arc<dyn Backend>>>,
     /// Session-to-instance mapping.
@@ -58,6 +62,7 @@ impl InstancePool {
         name: String,
         config: BackendConfig,
         registry: Arc<ToolRegistry>,
+        sessions: Option<Arc<ClientSessions>>,
     ) -> Result<Self> {
         let pool_config = &config.pool;
         let min_idle = pool_config.min_idle;
@@ -68,6 +73,7 @@ impl InstancePool {
             backend_name: name.clone(),
             config: config.clone(),
             registry,
+            sessions,
             idle: Mutex::new(VecDeque::new()),
             assigned: Mutex::new(HashMap::new()),
             capacity: Arc::new(Semaphore::new(max_instances as usize)),
@@ -84,7 +90,7 @@ impl InstancePool {
             match pool.capacity.try_acquire() {
                 Ok(permit) => {
                     permit.forget();
-                    match pool.spawn_instance().await {
+                    match pool.spawn_instance(false).await {
                         Ok(instance) => {
                             pool.idle.lock().await.push_back(instance);
                         }
@@ -139,24 +145,30 @@ impl InstancePool {
     ///
     /// The instance name includes a pool-unique ID for log disambiguation.
     /// Does NOT register tools — tools are shared and discovered once during
-    /// pool creation.
-    async fn spawn_instance(&self) -> Result<Arc<dyn Backend>> {
+    /// pool creation. Sampling is only advertised to the instance if
+    /// `client_sampling` says the client it is for supports it.
+    async fn spawn_instance(&self, client_sampling: bool) -> Result<Arc<dyn Backend>> {
         let id = self.next_instance_id.fetch_add(1, Ordering::Relaxed);
         let instance_name = format!("{}-pool-{}", self.backend_name, id);
 
         debug!(backend = %self.backend_name, instance = %instance_name, "spawning pool instance");
 
+        let mut config = self.config.clone();
+        if !client_sampling {
+            config.sampling = SamplingPolicy::Deny;
+        }
         let backend: Arc<dyn Backend> = match self.config.transport {
             Transport::Stdio => {
-                let b = super::stdio::StdioBackend::new(instance_name.clone(), self.config.clone());
+                let mut b = super::stdio::StdioBackend::new(instance_name.clone(), config);
+                // Instances serve one session at a time, so requests route unambiguously.
+                if let Some(ref sessions) = self.sessions {
+                    b = b.with_sessions(Arc::clone(sessions));
+                }
                 b.start().await?;
                 Arc::new(b)
             }
             Transport::CliAdapter => {
-                let b = super::cli_adapter::CliAdapterBackend::new(
-                    instance_name.clone(),
-                    self.config.clone(),
-                )?;
+                let b = super::cli_adapter::CliAdapterBackend::new(instance_name.clone(), config)?;
                 b.start().await?;
                 Arc::new(b)
             }
@@ -171,14 +183,71 @@ impl InstancePool {
         Ok(backend)
     }
 
+    /// Whether sampling may be advertised to an instance for `session_id`:
+    /// the policy allows it and the session's client supports it.
+    fn client_sampling(&self, session_id: u64) -> bool {
+        self.config.sampling == SamplingPolicy::Allow
+            && self
+                .sessions
+                .as_ref()
+                .and_then(|sessions| sessions.peer(session_id))
+                .is_some_and(|peer| {
+                    peer.peer_info()
+                        .is_some_and(|info| info.capabilities.sampling.is_some())
+                })
+    }
+
+    async fn assign(&self, session_id: u64, instance: &Arc<dyn Backend>) {
+        self.assigned
+            .lock()
+            .await
+            .insert(session_id, Arc::clone(instance));
+        // Lets the instance's log messages reach the session.
+        if let Some(ref sessions) = self.sessions {
+            sessions.assign_instance(instance.name(), &self.backend_name, session_id);
+        }
+    }
+
+    /// Spawn an instance for a session if there is capacity. `None` when the
+    /// pool is at capacity.
+    async fn spawn_assigned(
+        &self,
+        session_id: u64,
+        client_sampling: bool,
+    ) -> Option<Result<Arc<dyn Backend>>> {
+        let permit = self.capacity.try_acquire().ok()?;
+        permit.forget(); // consume the permit permanently
+        match self.spawn_instance(client_sampling).await {
+            Ok(instance) => {
+                self.assign(session_id, &instance).await;
+                debug!(
+                    backend = %self.backend_name,
+                    session = session_id,
+                    "spawned new instance for session"
+                );
+                Some(Ok(instance))
+            }
+            Err(e) => {
+                // Spawn failed — return capacity.
+                self.capacity.add_permits(1);
+                self.idle_notify.notify_waiters();
+                Some(Err(e))
+            }
+        }
+    }
+
     /// Acquire an instance for a session.
     ///
     /// 1. If session already has an assigned instance, return it.
-    /// 2. Pop from idle queue.
-    /// 3. Try to spawn a new instance (if under capacity).
-    /// 4. If at capacity, wait for an instance to become available.
+    /// 2. If the session's client supports sampling, try to spawn an instance
+    ///    that advertises it: pre-warmed instances don't, since no client was
+    ///    known when they started.
+    /// 3. Pop from idle queue.
+    /// 4. Try to spawn a new instance (if under capacity).
+    /// 5. If at capacity, wait for an instance to become available.
     pub async fn acquire(&self, session_id: u64) -> Result<Arc<dyn Backend>> {
         let deadline = tokio::time::Instant::now() + self.acquire_timeout;
+        let client_sampling = self.client_sampling(session_id);
         loop {
             // Register before checking state so a concurrent release/stop/restart
             // cannot notify between our check and wait, leaving this acquire asleep.
@@ -193,14 +262,15 @@ impl InstancePool {
                 }
             }
 
+            if client_sampling && let Some(result) = self.spawn_assigned(session_id, true).await {
+                return result;
+            }
+
             {
                 let mut idle = self.idle.lock().await;
                 if let Some(instance) = idle.pop_front() {
                     if instance.state() == BackendState::Healthy {
-                        self.assigned
-                            .lock()
-                            .await
-                            .insert(session_id, Arc::clone(&instance));
+                        self.assign(session_id, &instance).await;
                         debug!(
                             backend = %self.backend_name,
                             session = session_id,
@@ -217,28 +287,8 @@ impl InstancePool {
                 }
             }
 
-            if let Ok(permit) = self.capacity.try_acquire() {
-                permit.forget(); // consume the permit permanently
-                match self.spawn_instance().await {
-                    Ok(instance) => {
-                        self.assigned
-                            .lock()
-                            .await
-                            .insert(session_id, Arc::clone(&instance));
-                        debug!(
-                            backend = %self.backend_name,
-                            session = session_id,
-                            "spawned new instance for session"
-                        );
-                        return Ok(instance);
-                    }
-                    Err(e) => {
-                        // Spawn failed — return capacity.
-                        self.capacity.add_permits(1);
-                        self.idle_notify.notify_waiters();
-                        return Err(e);
-                    }
-                }
+            if let Some(result) = self.spawn_assigned(session_id, client_sampling).await {
+                return result;
             }
 
             debug!(
@@ -252,12 +302,12 @@ impl InstancePool {
                 .await
                 .is_err()
             {
-                anyhow::bail!(
-                    "pool exhausted for backend '{}' (max: {}, timeout: {:?})",
-                    self.backend_name,
-                    self.max_instances,
-                    self.acquire_timeout
-                );
+                return Err(GatewayError::PoolExhausted {
+                    backend: self.backend_name.clone(),
+                    max: self.max_instances,
+                    waited: self.acquire_timeout,
+                }
+                .into());
             }
         }
     }
@@ -272,6 +322,9 @@ impl InstancePool {
         let Some(instance) = instance else {
             return Ok(()); // No instance assigned for this session
         };
+        if let Some(ref sessions) = self.sessions {
+            sessions.unassign_instance(instance.name());
+        }
 
         debug!(
             backend = %self.backend_name,
@@ -304,7 +357,7 @@ impl InstancePool {
             && let Ok(permit) = self.capacity.try_acquire()
         {
             permit.forget();
-            match self.spawn_instance().await {
+            match self.spawn_instance(false).await {
                 Ok(fresh) => {
                     self.idle.lock().await.push_back(fresh);
                     debug!(
@@ -478,6 +531,9 @@ mod tests {
             retry: Default::default(),
             prerequisite: None,
             rate_limit: None,
+            cache: None,
+            validate_arguments: true,
+            coerce_arguments: false,
             tags: vec![],
             fallback_chain: vec![],
             tools: None,
@@ -488,6 +544,9 @@ mod tests {
             shutdown_grace_period: Duration::from_secs(5),
             max_memory_mb: None,
             oauth: None,
+            elicitation: Default::default(),
+            sampling: Default::default(),
+            pinned_tools: Vec::new(),
         }
     }
 
@@ -575,6 +634,7 @@ mod tests {
             acquire_timeout: Duration::from_millis(200),
             replenish_delay: Duration::ZERO, // no delay in tests
             next_instance_id: AtomicU32::new(0),
+            sessions: None,
         };
 
         let mut mocks = Vec::new();
@@ -652,7 +712,7 @@ mod tests {
     async fn production_prewarmed_instances_consume_capacity() {
         let config = cli_adapter_backend_config(1, 1, Duration::from_millis(200));
         let registry = ToolRegistry::new();
-        let pool = InstancePool::new("test".to_string(), config, registry)
+        let pool = InstancePool::new("test".to_string(), config, registry, None)
             .await
             .unwrap();
 
@@ -676,7 +736,7 @@ mod tests {
         );
         let registry = ToolRegistry::new();
         let pool = Arc::new(
-            InstancePool::new("test".to_string(), config, registry)
+            InstancePool::new("test".to_string(), config, registry, None)
                 .await
                 .unwrap(),
         );
@@ -736,7 +796,7 @@ mod tests {
         let config = cli_adapter_backend_config(1, 1, Duration::from_millis(200));
         let registry = ToolRegistry::new();
         let pool = Arc::new(
-            InstancePool::new("test".to_string(), config, registry)
+            InstancePool::new("test".to_string(), config, registry, None)
                 .await
                 .unwrap(),
         );
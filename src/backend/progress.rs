@@ -0,0 +1,304 @@
+//! Progress notifications relayed from backend tool calls to the client.
+//!
+//! When a client sends `call_tool_chain` with a `_meta.progressToken`, the
+//! handler runs inside a [`ChainProgress`] scope. Every backend call made in
+//! that scope (the fast path, or the sandbox's `__call_tool`) is routed by the
+//! backend (or pool instance) name and the progress token of its backend
+//! request, since rmcp numbers tokens per connection. The
+//! `notifications/progress` a backend sends for it are folded into one
+//! aggregate reported under the client's token: each call contributes a
+//! fraction in `[0, 1]`, `progress` is the sum of the fractions and `total`
+//! the number of calls made so far.
+//! Chains run as MCP tasks record their progress the same way, for
+//! `tasks/get` to report.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use dashmap::DashMap;
+use rmcp::RoleServer;
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::service::Peer;
+use tracing::debug;
+
+use super::sessions::ClientSessions;
+
+tokio::task_local! {
+    /// Progress of the `call_tool_chain` request being executed.
+    static CHAIN: Arc<ChainProgress>;
+    /// Share of the chain progress of the tool call being executed.
+    static CALL: ProgressRoute;
+}
+
+/// Aggregated progress of one `call_tool_chain` request.
+pub struct ChainProgress {
+    /// Client peer and progress token to notify, if the client asked for progress.
+    notify: Option<(Peer<RoleServer>, ProgressToken)>,
+    aggregate: Mutex<Aggregate>,
+}
+
+/// Latest aggregate progress of a chain, e.g. for task status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressSnapshot {
+    pub progress: f64,
+    pub total: f64,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Aggregate {
+    /// Completed fraction of each call, indexed by call.
+    calls: Vec<f64>,
+    /// Last progress reported, so reports only ever increase.
+    reported: f64,
+    /// Last progress message.
+    message: Option<String>,
+}
+
+impl Aggregate {
+    fn begin(&mut self) -> usize {
+        self.calls.push(0.0);
+        self.calls.len() - 1
+    }
+
+    /// Raise a call's fraction. Returns `(progress, total)` when the sum
+    /// increased past the last report.
+    fn advance(&mut self, call: usize, fraction: f64) -> Option<(f64, f64)> {
+        let slot = self.calls.get_mut(call)?;
+        *slot = slot.max(fraction.clamp(0.0, 1.0));
+        let progress: f64 = self.calls.iter().sum();
+        if progress <= self.reported {
+            return None;
+        }
+        self.reported = progress;
+        Some((progress, self.calls.len() as f64))
+    }
+}
+
+impl ChainProgress {
+    /// Progress that is sent to `peer` under the client's `token`.
+    pub fn new(peer: Peer<RoleServer>, token: ProgressToken) -> Arc<Self> {
+        Self::with_notify(Some((peer, token)))
+    }
+
+    /// Progress that is only recorded, for [`ChainProgress::snapshot`].
+    pub fn recorded() -> Arc<Self> {
+        Self::with_notify(None)
+    }
+
+    fn with_notify(notify: Option<(Peer<RoleServer>, ProgressToken)>) -> Arc<Self> {
+        Arc::new(Self {
+            notify,
+            aggregate: Mutex::new(Aggregate::default()),
+        })
+    }
+
+    /// Latest aggregate progress, once any call made progress.
+    pub fn snapshot(&self) -> Option<ProgressSnapshot> {
+        let aggregate = self.aggregate.lock().unwrap_or_else(|e| e.into_inner());
+        Some(ProgressSnapshot {
+            progress: aggregate.reported,
+            total: aggregate.calls.len() as f64,
+            message: aggregate.message.clone()?,
+        })
+    }
+
+    fn begin_call(&self) -> usize {
+        self.aggregate
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .begin()
+    }
+
+    async fn advance(&self, call: usize, fraction: f64, message: String) {
+        let Some((progress, total)) = ({
+            let mut aggregate = self.aggregate.lock().unwrap_or_else(|e| e.into_inner());
+            let advanced = aggregate.advance(call, fraction);
+            if advanced.is_some() {
+                aggregate.message = Some(message.clone());
+            }
+            advanced
+        }) else {
+            return;
+        };
+        let Some((peer, token)) = &self.notify else {
+            return;
+        };
+        let mut params = ProgressNotificationParam::new(token.clone(), progress);
+        params.total = Some(total);
+        params.message = Some(message);
+        if let Err(e) = peer.notify_progress(params).await {
+            debug!(error = %e, "failed to send progress notification to client");
+        }
+    }
+}
+
+/// Run `fut` with `progress` as the current chain progress (if any).
+///
+/// Needed wherever backend calls are spawned onto another task, since
+/// task-locals are not inherited.
+pub async fn scope<F: Future>(progress: Option<Arc<ChainProgress>>, fut: F) -> F::Output {
+    match progress {
+        Some(progress) => CHAIN.scope(progress, fut).await,
+        None => fut.await,
+    }
+}
+
+/// Chain progress of the current task, if the client asked for progress.
+pub fn current() -> Option<Arc<ChainProgress>> {
+    CHAIN.try_with(Arc::clone).ok()
+}
+
+/// One backend call's share of a [`ChainProgress`].
+#[derive(Clone)]
+pub(super) struct ProgressRoute {
+    chain: Arc<ChainProgress>,
+    call: usize,
+    /// `backend.tool`, prefixed to relayed messages.
+    label: String,
+}
+
+impl ProgressRoute {
+    /// Fold a backend progress notification into the chain's aggregate.
+    ///
+    /// With a known total the call's fraction is `progress / total`. Without
+    /// one it approaches 1 as `progress` grows, so it still only increases.
+    pub(super) async fn relay(&self, params: ProgressNotificationParam) {
+        let fraction = match params.total {
+            Some(total) if total > 0.0 => params.progress / total,
+            _ => params.progress / (params.progress + 1.0),
+        };
+        let message = match params.message {
+            Some(message) => format!("{}: {message}", self.label),
+            None => self.label.clone(),
+        };
+        self.chain.advance(self.call, fraction, message).await;
+    }
+}
+
+/// Progress tokens of in-flight backend requests, and where they report.
+///
+/// Keyed by backend (or pool instance) name as well as token: each backend
+/// connection issues its own tokens from 0, so tokens alone collide.
+#[derive(Default)]
+pub(super) struct ProgressRoutes {
+    routes: DashMap<(String, ProgressToken), ProgressRoute>,
+}
+
+impl ProgressRoutes {
+    pub(super) fn get(&self, backend: &str, token: &ProgressToken) -> Option<ProgressRoute> {
+        self.routes
+            .get(&(backend.to_string(), token.clone()))
+            .map(|r| r.value().clone())
+    }
+}
+
+/// Removes a route when its request ends, even if the request is dropped.
+pub(super) struct RouteGuard<'a> {
+    routes: &'a ProgressRoutes,
+    key: (String, ProgressToken),
+}
+
+impl Drop for RouteGuard<'_> {
+    fn drop(&mut self) {
+        self.routes.routes.remove(&self.key);
+    }
+}
+
+/// Run one backend tool call, relaying its progress to the current chain.
+///
+/// Outside a chain scope this is just `fut.await`. Inside one, the call is
+/// counted in the aggregate, requests `fut` sends can be routed to it with
+/// [`route_call`], and the call counts as complete once it returns.
+pub(super) async fn track_call<F: Future>(label: String, fut: F) -> F::Output {
+    let Some(chain) = current() else {
+        return fut.await;
+    };
+    let route = ProgressRoute {
+        call: chain.begin_call(),
+        chain: Arc::clone(&chain),
+        label,
+    };
+    let output = CALL.scope(route.clone(), fut).await;
+    chain
+        .advance(route.call, 1.0, format!("{} finished", route.label))
+        .await;
+    output
+}
+
+/// Route progress for the request with `token` on `backend` to the tool call
+/// being tracked, until the returned guard drops. `None` outside
+/// [`track_call`].
+pub(super) fn route_call<'a>(
+    sessions: &'a ClientSessions,
+    backend: &str,
+    token: ProgressToken,
+) -> Option<RouteGuard<'a>> {
+    let route = CALL.try_with(Clone::clone).ok()?;
+    let routes = sessions.progress_routes();
+    let key = (backend.to_string(), token);
+    routes.routes.insert(key.clone(), route);
+    Some(RouteGuard { routes, key })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    #[test]
+    fn test_aggregate_only_increases() {
+        let mut aggregate = Aggregate::default();
+        let a = aggregate.begin();
+        assert_eq!(aggregate.advance(a, 0.5), Some((0.5, 1.0)));
+        // Going backwards or repeating is not reported.
+        assert_eq!(aggregate.advance(a, 0.25), None);
+        assert_eq!(aggregate.advance(a, 0.5), None);
+
+        // A new call grows the total but not the progress.
+        let b = aggregate.begin();
+        assert_eq!(aggregate.advance(b, 0.0), None);
+        assert_eq!(aggregate.advance(b, 2.0), Some((1.5, 2.0)));
+        assert_eq!(aggregate.advance(a, 1.0), Some((2.0, 2.0)));
+        assert_eq!(aggregate.advance(7, 1.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_untracked_calls_are_not_routed() {
+        let sessions = ClientSessions::new();
+        let token = ProgressToken(NumberOrString::Number(1));
+        assert!(route_call(&sessions, "exa", token.clone()).is_none());
+        // Without a chain scope calls are not tracked either.
+        let routed = track_call("exa.search".into(), async {
+            route_call(&sessions, "exa", token.clone()).is_some()
+        })
+        .await;
+        assert!(!routed);
+        assert!(sessions.progress_routes().routes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_same_token_on_two_backends() {
+        let sessions = ClientSessions::new();
+        let token = ProgressToken(NumberOrString::Number(0));
+        let routes = sessions.progress_routes();
+        scope(Some(ChainProgress::recorded()), async {
+            track_call("a.search".into(), async {
+                let _a = route_call(&sessions, "a", token.clone()).unwrap();
+                track_call("b.search".into(), async {
+                    let b = route_call(&sessions, "b", token.clone()).unwrap();
+                    assert_eq!(routes.get("a", &token).unwrap().label, "a.search");
+                    assert_eq!(routes.get("b", &token).unwrap().label, "b.search");
+                    // One call ending leaves the other's route in place.
+                    drop(b);
+                    assert!(routes.get("b", &token).is_none());
+                    assert!(routes.get("a", &token).is_some());
+                })
+                .await;
+            })
+            .await;
+        })
+        .await;
+        assert!(routes.routes.is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+//! Proxying of backend MCP prompts and argument completion.
+//!
+//! Backend prompts are discovered alongside tools and registered under the
+//! backend's namespace (`github.review_pr`). `prompts/get` and
+//! `completion/complete` are forwarded to the owning backend with the
+//! backend's own prompt name or resource URI.
+
+use anyhow::Result;
+use rmcp::model::{
+    CompleteRequestParams, CompleteResult, GetPromptRequestParams, GetPromptResult, JsonObject,
+    Prompt, Reference,
+};
+use rmcp::service::{Peer, RoleClient};
+use tracing::debug;
+
+use super::resources::split_namespaced_uri;
+use super::{Backend, BackendManager, BackendState};
+use crate::config::BackendConfig;
+use crate::error::GatewayError;
+use crate::registry::{PromptArgumentEntry, PromptEntry, ToolRegistry};
+
+fn supports_prompts(peer: &Peer<RoleClient>) -> bool {
+    peer.peer_info()
+        .is_some_and(|info| info.capabilities.prompts.is_some())
+}
+
+fn supports_completions(peer: &Peer<RoleClient>) -> bool {
+    peer.peer_info()
+        .is_some_and(|info| info.capabilities.completions.is_some())
+}
+
+/// Convert a backend's prompt into a registry entry under `namespace`.
+fn prompt_entry(prompt: Prompt, backend_name: &str, namespace: &str) -> PromptEntry {
+    PromptEntry {
+        name: format!("{namespace}.{}", prompt.name),
+        original_name: prompt.name,
+        backend_name: backend_name.to_string(),
+        title: prompt.title,
+        description: prompt.description,
+        arguments: prompt
+            .arguments
+            .unwrap_or_default()
+            .into_iter()
+            .map(|a| PromptArgumentEntry {
+                name: a.name,
+                title: a.title,
+                description: a.description,
+                required: a.required.unwrap_or(false),
+            })
+            .collect(),
+    }
+}
+
+/// Discover a backend's prompts and register them under the backend's
+/// namespace, replacing any previous (or cached) ones. Backends without the
+/// prompts capability register none. Returns the number of prompts.
+pub(super) async fn register_discovered_prompts(
+    name: &str,
+    config: Option<&BackendConfig>,
+    backend: &dyn Backend,
+    registry: &ToolRegistry,
+) -> Result<usize> {
+    let Some(peer) = backend.peer().await else {
+        return Ok(0);
+    };
+    if !supports_prompts(&peer) {
+        registry.register_backend_prompts(name, Vec::new());
+        return Ok(0);
+    }
+
+    let timeout = config
+        .map(|c| c.timeout)
+        .unwrap_or(super::resources::DEFAULT_REQUEST_TIMEOUT);
+    let prompts = tokio::time::timeout(timeout, peer.list_all_prompts())
+        .await
+        .map_err(|_| anyhow::anyhow!("prompt discovery on backend '{name}' timed out"))?
+        .map_err(|e| anyhow::anyhow!("prompt discovery on backend '{name}' failed: {e}"))?;
+
+    let namespace = config.and_then(|c| c.namespace.as_deref()).unwrap_or(name);
+    let entries: Vec<PromptEntry> = prompts
+        .into_iter()
+        .map(|p| prompt_entry(p, name, namespace))
+        .collect();
+    let count = entries.len();
+    debug!(backend = %name, prompts = count, "discovered prompts");
+    registry.register_backend_prompts(name, entries);
+    Ok(count)
+}
+
+/// Where a completion request should go when it targets a backend prompt
+/// or a proxied backend resource template.
+///
+/// Returns the owning backend and the request rewritten with the backend's
+/// own prompt name / URI, or `None` for gatemini's own prompts and resources.
+pub fn route_completion(
+    request: &CompleteRequestParams,
+    registry: &ToolRegistry,
+) -> Option<(String, CompleteRequestParams)> {
+    let mut forwarded = request.clone();
+    let backend = match &mut forwarded.r#ref {
+        Reference::Prompt(prompt_ref) => {
+            let entry = registry.get_prompt(&prompt_ref.name)?;
+            prompt_ref.name = entry.original_name;
+            entry.backend_name
+        }
+        Reference::Resource(resource_ref) => {
+            let (backend, inner) = split_namespaced_uri(&resource_ref.uri)?;
+            let backend = backend.to_string();
+            resource_ref.uri = inner.to_string();
+            backend
+        }
+    };
+    Some((backend, forwarded))
+}
+
+impl BackendManager {
+    /// Re-run prompt discovery on a live backend.
+    ///
+    /// Called when a backend sends `notifications/prompts/list_changed`.
+    pub async fn refresh_prompts(&self, name: &str, registry: &ToolRegistry) -> Result<usize> {
+        let backend = self
+            .backends
+            .get(name)
+            .map(|r| std::sync::Arc::clone(r.value()))
+            .ok_or_else(|| anyhow::anyhow!("backend '{name}' not found"))?;
+        let state = backend.state();
+        if state != BackendState::Healthy {
+            return Err(GatewayError::BackendUnavailable {
+                backend: name.to_string(),
+                state,
+            }
+            .into());
+        }
+        let config = self.configs.read().await.get(name).cloned();
+        register_discovered_prompts(name, config.as_ref(), &*backend, registry).await
+    }
+
+    /// Render a backend prompt by forwarding `prompts/get` to its backend.
+    pub async fn get_backend_prompt(
+        &self,
+        entry: &PromptEntry,
+        arguments: Option<JsonObject>,
+    ) -> Result<GetPromptResult> {
+        let backend = &entry.backend_name;
+        let (peer, timeout) = self.backend_peer(backend).await?;
+        if !supports_prompts(&peer) {
+            anyhow::bail!("backend '{backend}' no longer provides prompts");
+        }
+
+        let mut params = GetPromptRequestParams::new(entry.original_name.clone());
+        if let Some(arguments) = arguments {
+            params = params.with_arguments(arguments);
+        }
+        let result = tokio::time::timeout(timeout, peer.get_prompt(params))
+            .await
+            .map_err(|_| GatewayError::Timeout {
+                backend: backend.clone(),
+                tool: "prompts/get".to_string(),
+                timeout,
+            })?
+            .map_err(|e| GatewayError::from_service(backend, "prompts/get", e))?;
+        Ok(result)
+    }
+
+    /// Forward a completion request (already rewritten by [`route_completion`])
+    /// to a backend. Backends without the completions capability get no
+    /// suggestions rather than an error.
+    pub async fn complete_backend(
+        &self,
+        backend: &str,
+        request: CompleteRequestParams,
+    ) -> Result<CompleteResult> {
+        let (peer, timeout) = self.backend_peer(backend).await?;
+        if !supports_completions(&peer) {
+            return Ok(CompleteResult::default());
+        }
+        let result = tokio::time::timeout(timeout, peer.complete(request))
+            .await
+            .map_err(|_| GatewayError::Timeout {
+                backend: backend.to_string(),
+                tool: "completion/complete".to_string(),
+                timeout,
+            })?
+            .map_err(|e| GatewayError::from_service(backend, "completion/complete", e))?;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::PromptArgument;
+    use serde_json::json;
+
+    fn complete_request(reference: serde_json::Value, argument: &str) -> CompleteRequestParams {
+        serde_json::from_value(json!({
+            "ref": reference,
+            "argument": {"name": argument, "value": ""},
+        }))
+        .unwrap()
+    }
+
+    fn registry_with_prompt() -> std::sync::Arc<ToolRegistry> {
+        let registry = ToolRegistry::new();
+        let prompt = Prompt::new(
+            "review_pr",
+            Some("Review a pull request"),
+            Some(vec![PromptArgument::new("number").with_required(true)]),
+        );
+        registry.register_backend_prompts("github", vec![prompt_entry(prompt, "github", "gh")]);
+        registry
+    }
+
+    #[test]
+    fn test_prompt_entry_is_namespaced() {
+        let registry = registry_with_prompt();
+        let entry = registry.get_prompt("gh.review_pr").unwrap();
+        assert_eq!(entry.original_name, "review_pr");
+        assert_eq!(entry.backend_name, "github");
+        assert_eq!(entry.arguments.len(), 1);
+        assert!(entry.arguments[0].required);
+    }
+
+    #[test]
+    fn test_route_completion_rewrites_prompt_ref() {
+        let registry = registry_with_prompt();
+        let request = complete_request(
+            json!({"type": "ref/prompt", "name": "gh.review_pr"}),
+            "number",
+        );
+        let (backend, forwarded) = route_completion(&request, &registry).unwrap();
+        assert_eq!(backend, "github");
+        assert_eq!(forwarded.r#ref, Reference::for_prompt("review_pr"));
+    }
+
+    #[test]
+    fn test_route_completion_rewrites_resource_ref() {
+        let registry = ToolRegistry::new();
+        let request = complete_request(
+            json!({"type": "ref/resource", "uri": "gatemini://r/db/postgres://{table}/schema"}),
+            "table",
+        );
+        let (backend, forwarded) = route_completion(&request, &registry).unwrap();
+        assert_eq!(backend, "db");
+        assert_eq!(
+            forwarded.r#ref,
+            Reference::for_resource("postgres://{table}/schema")
+        );
+    }
+
+    #[test]
+    fn test_route_completion_leaves_gateway_refs() {
+        let registry = registry_with_prompt();
+        for reference in [
+            json!({"type": "ref/prompt", "name": "find_tool"}),
+            json!({"type": "ref/resource", "uri": "gatemini://tool/{tool_name}"}),
+        ] {
+            let request = complete_request(reference, "x");
+            assert!(route_completion(&request, &registry).is_none());
+        }
+    }
+}
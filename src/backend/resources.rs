@@ -0,0 +1,408 @@
+//! Proxying of backend MCP resources.
+//!
+//! Backend resources are re-published under `gatemini://r/{backend}/{uri}`,
+//! where `{uri}` is the backend's own URI unchanged (e.g.
+//! `gatemini://r/docs/file:///guide.md`). Reads and subscriptions are routed
+//! back to the owning backend by splitting the namespaced URI.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use rmcp::model::{
+    ReadResourceRequestParams, ReadResourceResult, Resource, ResourceContents, ResourceTemplate,
+    SubscribeRequestParams, UnsubscribeRequestParams,
+};
+use rmcp::service::{Peer, RoleClient};
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+use super::{BackendManager, BackendState};
+use crate::error::GatewayError;
+
+/// Prefix of every proxied backend resource URI.
+pub const BACKEND_RESOURCE_PREFIX: &str = "gatemini://r/";
+
+/// Subscriptions to each namespaced backend resource URI.
+pub(super) type ResourceSubscriptions = dashmap::DashMap<String, ResourceSubscription>;
+
+/// Sessions subscribed to a backend resource, and the backend's answer to
+/// the `resources/subscribe` forwarded for them.
+#[derive(Default)]
+pub(super) struct ResourceSubscription {
+    sessions: HashSet<u64>,
+    /// Set once by whichever subscriber forwards the request; the others
+    /// wait for it.
+    forwarded: Arc<OnceCell<Result<(), String>>>,
+}
+
+/// Timeout for forwarded requests when the backend has no config (virtual backends).
+pub(super) const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Map a backend's resource URI (or URI template) into the gateway namespace.
+pub fn namespace_uri(backend: &str, uri: &str) -> String {
+    format!("{BACKEND_RESOURCE_PREFIX}{backend}/{uri}")
+}
+
+/// Split a `gatemini://r/{backend}/{uri}` URI into the backend name and its own URI.
+pub fn split_namespaced_uri(uri: &str) -> Option<(&str, &str)> {
+    let rest = uri.strip_prefix(BACKEND_RESOURCE_PREFIX)?;
+    let (backend, inner) = rest.split_once('/')?;
+    if backend.is_empty() || inner.is_empty() {
+        return None;
+    }
+    Some((backend, inner))
+}
+
+fn supports_resources(peer: &Peer<RoleClient>) -> bool {
+    peer.peer_info()
+        .is_some_and(|info| info.capabilities.resources.is_some())
+}
+
+fn supports_subscribe(peer: &Peer<RoleClient>) -> bool {
+    peer.peer_info().is_some_and(|info| {
+        info.capabilities
+            .resources
+            .as_ref()
+            .is_some_and(|r| r.subscribe == Some(true))
+    })
+}
+
+impl BackendManager {
+    /// Peers of healthy backends that advertise the resources capability.
+    async fn resource_peers(&self) -> Vec<(String, Peer<RoleClient>, Duration)> {
+        let backends: Vec<_> = self
+            .backends
+            .iter()
+            .filter(|r| r.value().state() == BackendState::Healthy)
+            .map(|r| (r.key().clone(), std::sync::Arc::clone(r.value())))
+            .collect();
+        let mut peers = Vec::new();
+        for (name, backend) in backends {
+            if let Some(peer) = backend.peer().await
+                && supports_resources(&peer)
+            {
+                let timeout = self.request_timeout(&name).await;
+                peers.push((name, peer, timeout));
+            }
+        }
+        peers
+    }
+
+    /// Live peer for one backend, for routing a request to it.
+    pub(super) async fn backend_peer(&self, name: &str) -> Result<(Peer<RoleClient>, Duration)> {
+        let backend = self
+            .backends
+            .get(name)
+            .map(|r| std::sync::Arc::clone(r.value()))
+            .ok_or_else(|| anyhow::anyhow!("backend '{name}' not found"))?;
+        let state = backend.state();
+        if state != BackendState::Healthy {
+            return Err(GatewayError::BackendUnavailable {
+                backend: name.to_string(),
+                state,
+            }
+            .into());
+        }
+        let peer = backend
+            .peer()
+            .await
+            .ok_or_else(|| anyhow::anyhow!("backend '{name}' is not an MCP server"))?;
+        Ok((peer, self.request_timeout(name).await))
+    }
+
+    async fn resource_peer(&self, name: &str) -> Result<(Peer<RoleClient>, Duration)> {
+        let (peer, timeout) = self.backend_peer(name).await?;
+        if !supports_resources(&peer) {
+            anyhow::bail!("backend '{name}' does not provide resources");
+        }
+        Ok((peer, timeout))
+    }
+
+    pub(super) async fn request_timeout(&self, name: &str) -> Duration {
+        self.configs
+            .read()
+            .await
+            .get(name)
+            .map(|c| c.timeout)
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Resources from every backend, with URIs and names namespaced by backend.
+    ///
+    /// Backends that fail or time out are skipped so one slow server can't
+    /// hide everyone else's resources.
+    pub async fn list_backend_resources(&self) -> Vec<Resource> {
+        let requests =
+            self.resource_peers()
+                .await
+                .into_iter()
+                .map(|(name, peer, timeout)| async move {
+                    match tokio::time::timeout(timeout, peer.list_all_resources()).await {
+                        Ok(Ok(resources)) => resources
+                            .into_iter()
+                            .map(|mut r| {
+                                r.raw.uri = namespace_uri(&name, &r.raw.uri);
+                                r.raw.name = format!("{name}.{}", r.raw.name);
+                                r
+                            })
+                            .collect(),
+                        Ok(Err(e)) => {
+                            warn!(backend = %name, error = %e, "resources/list failed");
+                            Vec::new()
+                        }
+                        Err(_) => {
+                            warn!(backend = %name, "resources/list timed out");
+                            Vec::new()
+                        }
+                    }
+                });
+        futures::future::join_all(requests)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Resource templates from every backend, namespaced like [`Self::list_backend_resources`].
+    pub async fn list_backend_resource_templates(&self) -> Vec<ResourceTemplate> {
+        let requests =
+            self.resource_peers()
+                .await
+                .into_iter()
+                .map(|(name, peer, timeout)| async move {
+                    match tokio::time::timeout(timeout, peer.list_all_resource_templates()).await {
+                        Ok(Ok(templates)) => templates
+                            .into_iter()
+                            .map(|mut t| {
+                                t.raw.uri_template = namespace_uri(&name, &t.raw.uri_template);
+                                t.raw.name = format!("{name}.{}", t.raw.name);
+                                t
+                            })
+                            .collect(),
+                        Ok(Err(e)) => {
+                            warn!(backend = %name, error = %e, "resources/templates/list failed");
+                            Vec::new()
+                        }
+                        Err(_) => {
+                            warn!(backend = %name, "resources/templates/list timed out");
+                            Vec::new()
+                        }
+                    }
+                });
+        futures::future::join_all(requests)
+            .await
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Read a `gatemini://r/{backend}/{uri}` resource from its backend.
+    /// Returned contents carry the namespaced URI.
+    pub async fn read_backend_resource(&self, uri: &str) -> Result<ReadResourceResult> {
+        let (backend, inner) = split_namespaced_uri(uri)
+            .ok_or_else(|| anyhow::anyhow!("invalid backend resource URI: {uri}"))?;
+        let (peer, timeout) = self.resource_peer(backend).await?;
+
+        let mut result = tokio::time::timeout(
+            timeout,
+            peer.read_resource(ReadResourceRequestParams::new(inner)),
+        )
+        .await
+        .map_err(|_| GatewayError::Timeout {
+            backend: backend.to_string(),
+            tool: "resources/read".to_string(),
+            timeout,
+        })?
+        .map_err(|e| GatewayError::from_service(backend, "resources/read", e))?;
+
+        for contents in &mut result.contents {
+            match contents {
+                ResourceContents::TextResourceContents { uri, .. }
+                | ResourceContents::BlobResourceContents { uri, .. } => {
+                    *uri = namespace_uri(backend, uri);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Subscribe a session to updates of a backend resource.
+    ///
+    /// The backend is subscribed once, for the first session. Sessions that
+    /// subscribe while that request is in flight share its result.
+    pub async fn subscribe_resource(&self, session_id: u64, uri: &str) -> Result<()> {
+        let (backend, inner) = split_namespaced_uri(uri)
+            .ok_or_else(|| anyhow::anyhow!("invalid backend resource URI: {uri}"))?;
+
+        let forwarded = {
+            let mut subscription = self
+                .resource_subscriptions
+                .entry(uri.to_string())
+                .or_default();
+            subscription.sessions.insert(session_id);
+            Arc::clone(&subscription.forwarded)
+        };
+        let result = forwarded
+            .get_or_init(|| async {
+                self.forward_subscribe(backend, inner)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+            .await;
+        if let Err(e) = result {
+            if let Some(mut subscription) = self.resource_subscriptions.get_mut(uri) {
+                subscription.sessions.remove(&session_id);
+            }
+            self.resource_subscriptions
+                .remove_if(uri, |_, subscription| subscription.sessions.is_empty());
+            anyhow::bail!("{e}");
+        }
+        Ok(())
+    }
+
+    async fn forward_subscribe(&self, backend: &str, inner: &str) -> Result<()> {
+        let (peer, timeout) = self.resource_peer(backend).await?;
+        if !supports_subscribe(&peer) {
+            // The backend never sends updates; accept the subscription anyway,
+            // as a backend that ignores it would.
+            debug!(backend = %backend, uri = %inner, "backend does not support resource subscriptions");
+            return Ok(());
+        }
+        tokio::time::timeout(timeout, peer.subscribe(SubscribeRequestParams::new(inner)))
+            .await
+            .map_err(|_| anyhow::anyhow!("resources/subscribe on backend '{backend}' timed out"))?
+            .map_err(|e| anyhow::anyhow!("resources/subscribe on backend '{backend}' failed: {e}"))
+    }
+
+    /// Remove a session's subscription. The backend is unsubscribed when
+    /// the last session leaves.
+    pub async fn unsubscribe_resource(&self, session_id: u64, uri: &str) -> Result<()> {
+        let last = {
+            let Some(mut subscription) = self.resource_subscriptions.get_mut(uri) else {
+                return Ok(());
+            };
+            subscription.sessions.remove(&session_id);
+            subscription.sessions.is_empty()
+        };
+        if !last {
+            return Ok(());
+        }
+        self.resource_subscriptions
+            .remove_if(uri, |_, subscription| subscription.sessions.is_empty());
+
+        let Some((backend, inner)) = split_namespaced_uri(uri) else {
+            return Ok(());
+        };
+        let Ok((peer, timeout)) = self.resource_peer(backend).await else {
+            return Ok(()); // backend gone: nothing left to unsubscribe
+        };
+        if !supports_subscribe(&peer) {
+            return Ok(());
+        }
+        tokio::time::timeout(
+            timeout,
+            peer.unsubscribe(UnsubscribeRequestParams::new(inner)),
+        )
+        .await
+        .map_err(|_| anyhow::anyhow!("resources/unsubscribe on backend '{backend}' timed out"))?
+        .map_err(|e| anyhow::anyhow!("resources/unsubscribe on backend '{backend}' failed: {e}"))
+    }
+
+    /// Whether a session subscribed to a (namespaced) resource URI.
+    pub fn is_subscribed(&self, session_id: u64, uri: &str) -> bool {
+        self.resource_subscriptions
+            .get(uri)
+            .is_some_and(|subscription| subscription.sessions.contains(&session_id))
+    }
+
+    /// Drop all of a session's resource subscriptions (on disconnect).
+    pub(super) async fn release_resource_subscriptions(&self, session_id: u64) {
+        let uris: Vec<String> = self
+            .resource_subscriptions
+            .iter()
+            .filter(|r| r.value().sessions.contains(&session_id))
+            .map(|r| r.key().clone())
+            .collect();
+        for uri in uris {
+            if let Err(e) = self.unsubscribe_resource(session_id, &uri).await {
+                warn!(uri = %uri, error = %e, "failed to release resource subscription");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace_roundtrip() {
+        let uri = namespace_uri("docs", "file:///guide/intro.md");
+        assert_eq!(uri, "gatemini://r/docs/file:///guide/intro.md");
+        assert_eq!(
+            split_namespaced_uri(&uri),
+            Some(("docs", "file:///guide/intro.md"))
+        );
+    }
+
+    #[test]
+    fn test_split_rejects_other_uris() {
+        assert_eq!(split_namespaced_uri("gatemini://tools"), None);
+        assert_eq!(split_namespaced_uri("gatemini://r/docs"), None);
+        assert_eq!(split_namespaced_uri("gatemini://r//file:///x"), None);
+        assert_eq!(split_namespaced_uri("file:///x"), None);
+    }
+
+    #[test]
+    fn test_template_namespacing_keeps_variables() {
+        assert_eq!(
+            namespace_uri("db", "postgres://{table}/schema"),
+            "gatemini://r/db/postgres://{table}/schema"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_are_per_session() {
+        let manager = BackendManager::new();
+        let uri = namespace_uri("docs", "file:///a.md");
+        // Backend isn't running: subscribing fails and leaves no state behind.
+        assert!(manager.subscribe_resource(1, &uri).await.is_err());
+        assert!(!manager.is_subscribed(1, &uri));
+
+        // A second subscriber piggybacks on the first without contacting the backend.
+        manager.resource_subscriptions.insert(
+            uri.clone(),
+            ResourceSubscription {
+                sessions: HashSet::from([1]),
+                forwarded: Arc::new(OnceCell::from(Ok(()))),
+            },
+        );
+        manager.subscribe_resource(2, &uri).await.unwrap();
+        assert!(manager.is_subscribed(1, &uri));
+        assert!(manager.is_subscribed(2, &uri));
+
+        manager.release_resource_subscriptions(1).await;
+        assert!(!manager.is_subscribed(1, &uri));
+        assert!(manager.is_subscribed(2, &uri));
+        manager.unsubscribe_resource(2, &uri).await.unwrap();
+        assert!(!manager.resource_subscriptions.contains_key(&uri));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_subscribers_share_a_failed_forward() {
+        let manager = BackendManager::new();
+        let uri = namespace_uri("docs", "file:///a.md");
+        let (a, b) = tokio::join!(
+            manager.subscribe_resource(1, &uri),
+            manager.subscribe_resource(2, &uri)
+        );
+        // Neither session is left subscribed to a resource the backend never subscribed.
+        assert!(a.is_err());
+        assert!(b.is_err());
+        assert!(!manager.is_subscribed(1, &uri));
+        assert!(!manager.is_subscribed(2, &uri));
+        assert!(!manager.resource_subscriptions.contains_key(&uri));
+    }
+}
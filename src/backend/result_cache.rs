@@ -0,0 +1,392 @@
+//! Result cache and request coalescing for read-only backend tools.
+//!
+//! A backend with a `cache` section keeps the results of its read-only tools
+//! (annotated `readOnlyHint`, or listed in `cache.tools`) keyed by tool name
+//! and a SHA-256 hash of the canonical JSON arguments, so key order and
+//! whitespace do not matter. Results are reused until the TTL passes; the
+//! least recently used result is evicted when the cache is full.
+//!
+//! Identical calls that arrive while one is in flight wait for it instead of
+//! sending their own request (singleflight). If the leading call fails or is
+//! cancelled, the waiting calls go to the backend themselves, so each caller
+//! sees its own error.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio::sync::watch;
+
+use crate::config::ResultCacheConfig;
+use crate::registry::ToolEntry;
+
+/// Tool name and hash of the canonical arguments.
+type Key = (String, [u8; 32]);
+
+struct Entry {
+    value: Value,
+    expires: Instant,
+    /// Tick of the last hit, for LRU eviction.
+    used: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<Key, Entry>,
+    tick: u64,
+}
+
+/// Where a cacheable call gets its result from.
+pub enum Lookup {
+    /// A fresh cached result.
+    Hit(Value),
+    /// An identical call is in flight; wait for its result.
+    Wait(Waiter),
+    /// No result yet: make the call and pass it to [`ResultCache::finish`].
+    Lead(Flight),
+}
+
+/// Result cache of one backend.
+pub struct ResultCache {
+    ttl: Duration,
+    max_entries: usize,
+    read_only_hint: bool,
+    tools: HashSet<String>,
+    exclude: HashSet<String>,
+    /// Tools the backend annotated read-only, updated on discovery.
+    annotated: RwLock<HashSet<String>>,
+    entries: Mutex<Entries>,
+    in_flight: Arc<Mutex<HashMap<Key, watch::Receiver<Option<Value>>>>>,
+}
+
+impl ResultCache {
+    pub fn new(config: &ResultCacheConfig) -> Self {
+        Self {
+            ttl: config.ttl,
+            max_entries: config.max_entries.max(1),
+            read_only_hint: config.read_only_hint,
+            tools: config.tools.iter().cloned().collect(),
+            exclude: config.exclude.iter().cloned().collect(),
+            annotated: RwLock::new(HashSet::new()),
+            entries: Mutex::new(Entries::default()),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Record which of the backend's tools are annotated read-only.
+    pub fn set_tools(&self, tools: &[ToolEntry]) {
+        let annotated = tools
+            .iter()
+            .filter(|t| t.is_read_only())
+            .map(|t| t.original_name.clone())
+            .collect();
+        *self.annotated.write().unwrap_or_else(|e| e.into_inner()) = annotated;
+    }
+
+    /// Whether results of `tool` (as the backend names it) are cached.
+    pub fn is_cacheable(&self, tool: &str) -> bool {
+        if self.exclude.contains(tool) {
+            return false;
+        }
+        self.tools.contains(tool)
+            || (self.read_only_hint
+                && self
+                    .annotated
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .contains(tool))
+    }
+
+    /// Find the result of a call, or join or lead the identical call in flight.
+    pub fn lookup(&self, tool: &str, arguments: Option<&Value>) -> Lookup {
+        let key = (tool.to_string(), arguments_hash(arguments));
+        if let Some(value) = self.get(&key) {
+            return Lookup::Hit(value);
+        }
+
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(rx) = in_flight.get(&key) {
+            return Lookup::Wait(Waiter(rx.clone()));
+        }
+        // The previous leader may have finished since the first look.
+        if let Some(value) = self.get(&key) {
+            return Lookup::Hit(value);
+        }
+        let (tx, rx) = watch::channel(None);
+        in_flight.insert(key.clone(), rx);
+        Lookup::Lead(Flight {
+            key: Some(key),
+            tx,
+            in_flight: Arc::clone(&self.in_flight),
+        })
+    }
+
+    /// Store the leading call's result and hand it to the waiting calls.
+    /// Errors are neither stored nor shared.
+    pub fn finish(&self, mut flight: Flight, result: &anyhow::Result<Value>) {
+        let Some(key) = flight.key.take() else {
+            return;
+        };
+        if let Ok(value) = result {
+            self.insert(key.clone(), value.clone());
+            flight.tx.send_replace(Some(value.clone()));
+        }
+        flight.remove(&key);
+    }
+
+    fn get(&self, key: &Key) -> Option<Value> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.tick += 1;
+        let tick = entries.tick;
+        match entries.map.get_mut(key) {
+            Some(entry) if entry.expires > Instant::now() => {
+                entry.used = tick;
+                Some(entry.value.clone())
+            }
+            Some(_) => {
+                entries.map.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: Key, value: Value) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.map.len() >= self.max_entries && !entries.map.contains_key(&key) {
+            let now = Instant::now();
+            entries.map.retain(|_, entry| entry.expires > now);
+            if entries.map.len() >= self.max_entries
+                && let Some(oldest) = entries
+                    .map
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.used)
+                    .map(|(key, _)| key.clone())
+            {
+                entries.map.remove(&oldest);
+            }
+        }
+        entries.tick += 1;
+        let entry = Entry {
+            value,
+            expires: Instant::now() + self.ttl,
+            used: entries.tick,
+        };
+        entries.map.insert(key, entry);
+    }
+
+    /// Number of results held, including expired ones not yet dropped.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .map
+            .len()
+    }
+}
+
+/// The leading call of a group of identical calls. Dropping it without
+/// [`ResultCache::finish`] (e.g. when the call is cancelled) releases the
+/// waiting calls to make their own.
+pub struct Flight {
+    key: Option<Key>,
+    tx: watch::Sender<Option<Value>>,
+    in_flight: Arc<Mutex<HashMap<Key, watch::Receiver<Option<Value>>>>>,
+}
+
+impl Flight {
+    fn remove(&self, key: &Key) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+    }
+}
+
+impl Drop for Flight {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.remove(&key);
+        }
+    }
+}
+
+/// A call waiting for an identical call in flight.
+pub struct Waiter(watch::Receiver<Option<Value>>);
+
+impl Waiter {
+    /// The leading call's result, or `None` if it failed or was cancelled.
+    pub async fn result(mut self) -> Option<Value> {
+        self.0
+            .wait_for(Option::is_some)
+            .await
+            .ok()
+            .and_then(|value| value.clone())
+    }
+}
+
+/// SHA-256 of the arguments serialized with sorted object keys.
+/// `None`, `null` and `{}` all mean "no arguments".
+fn arguments_hash(arguments: Option<&Value>) -> [u8; 32] {
+    let mut canonical = String::new();
+    match arguments {
+        None | Some(Value::Null) => {}
+        Some(Value::Object(map)) if map.is_empty() => {}
+        Some(value) => write_canonical(value, &mut canonical),
+    }
+    Sha256::digest(canonical.as_bytes()).into()
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn cache(ttl: Duration, max_entries: usize) -> ResultCache {
+        ResultCache::new(&ResultCacheConfig {
+            ttl,
+            max_entries,
+            read_only_hint: true,
+            tools: vec!["search".to_string()],
+            exclude: vec!["now".to_string()],
+        })
+    }
+
+    fn lead(cache: &ResultCache, tool: &str, args: Value) -> Flight {
+        match cache.lookup(tool, Some(&args)) {
+            Lookup::Lead(flight) => flight,
+            _ => panic!("expected a miss"),
+        }
+    }
+
+    #[test]
+    fn test_canonical_arguments_hash() {
+        let a = json!({"q": "rust", "opts": {"limit": 5, "lang": "en"}});
+        let b: Value =
+            serde_json::from_str(r#"{ "opts": {"lang":"en","limit":5}, "q":"rust" }"#).unwrap();
+        assert_eq!(arguments_hash(Some(&a)), arguments_hash(Some(&b)));
+        assert_ne!(
+            arguments_hash(Some(&a)),
+            arguments_hash(Some(&json!({"q": "rust"})))
+        );
+        assert_eq!(arguments_hash(None), arguments_hash(Some(&json!({}))));
+    }
+
+    #[test]
+    fn test_cacheable_tools() {
+        let cache = cache(Duration::from_secs(60), 10);
+        let mut annotated: ToolEntry = serde_json::from_value(json!({
+            "name": "b.lookup", "original_name": "lookup", "description": "",
+            "backend_name": "b", "input_schema": {},
+            "annotations": {"readOnlyHint": true}
+        }))
+        .unwrap();
+        let mut now = annotated.clone();
+        now.original_name = "now".to_string();
+        let mut write = annotated.clone();
+        write.original_name = "write".to_string();
+        write.annotations = None;
+        cache.set_tools(&[annotated.clone(), now, write]);
+
+        assert!(cache.is_cacheable("search"));
+        assert!(cache.is_cacheable("lookup"));
+        assert!(!cache.is_cacheable("now"));
+        assert!(!cache.is_cacheable("write"));
+
+        annotated.annotations = None;
+        cache.set_tools(&[annotated]);
+        assert!(!cache.is_cacheable("lookup"));
+    }
+
+    #[test]
+    fn test_hit_expiry_and_eviction() {
+        let cache = cache(Duration::from_secs(60), 2);
+        let flight = lead(&cache, "search", json!({"q": 1}));
+        cache.finish(flight, &Ok(json!("one")));
+        assert!(matches!(
+            cache.lookup("search", Some(&json!({"q": 1}))),
+            Lookup::Hit(v) if v == "one"
+        ));
+
+        // Errors are not cached.
+        let flight = lead(&cache, "search", json!({"q": 2}));
+        cache.finish(flight, &Err(anyhow::anyhow!("boom")));
+        let flight = lead(&cache, "search", json!({"q": 2}));
+        cache.finish(flight, &Ok(json!("two")));
+
+        // q=1 was used more recently than q=2, so q=2 is evicted.
+        let _ = cache.lookup("search", Some(&json!({"q": 1})));
+        let flight = lead(&cache, "search", json!({"q": 3}));
+        cache.finish(flight, &Ok(json!("three")));
+        assert_eq!(cache.len(), 2);
+        assert!(matches!(
+            cache.lookup("search", Some(&json!({"q": 2}))),
+            Lookup::Lead(_)
+        ));
+
+        let short = self::cache(Duration::ZERO, 2);
+        let flight = lead(&short, "search", json!({}));
+        short.finish(flight, &Ok(json!("stale")));
+        assert!(matches!(
+            short.lookup("search", Some(&json!({}))),
+            Lookup::Lead(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_waiters_share_leader_result() {
+        let cache = cache(Duration::from_secs(60), 10);
+        let flight = lead(&cache, "search", json!({"q": "x"}));
+        let Lookup::Wait(waiter) = cache.lookup("search", Some(&json!({"q": "x"}))) else {
+            panic!("expected to wait");
+        };
+        let waiting = tokio::spawn(waiter.result());
+        cache.finish(flight, &Ok(json!(42)));
+        assert_eq!(waiting.await.unwrap(), Some(json!(42)));
+
+        // A dropped leader releases its waiters without a result.
+        let flight = lead(&cache, "search", json!({"q": "y"}));
+        let Lookup::Wait(waiter) = cache.lookup("search", Some(&json!({"q": "y"}))) else {
+            panic!("expected to wait");
+        };
+        drop(flight);
+        assert_eq!(waiter.result().await, None);
+        assert!(matches!(
+            cache.lookup("search", Some(&json!({"q": "y"}))),
+            Lookup::Lead(_)
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+//! Connected client sessions, for routing backend-initiated requests
+//! (elicitation, sampling) and progress notifications back to the session
+//! whose tool call caused them.
+//!
+//! Each session registers its rmcp peer once initialized. While a session's
+//! call is in flight on a backend, an [`InFlightCall`] guard records it under
+//! the backend name (or the pool instance name for dedicated backends, whose
+//! instances only ever serve one session). Which session each dedicated
+//! instance is assigned to, and sessions' `logging/setLevel` levels, are kept
+//! here too.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use dashmap::DashMap;
+use rmcp::RoleServer;
+use rmcp::model::LoggingLevel;
+use rmcp::service::Peer;
+use tokio_util::sync::CancellationToken;
+
+use super::logging::{DEFAULT_LOG_LEVEL, severity};
+use super::progress::ProgressRoutes;
+
+/// Client sessions and the calls they have in flight on each backend.
+#[derive(Default)]
+pub struct ClientSessions {
+    /// session_id -> peer of the client connection
+    peers: DashMap<u64, Peer<RoleServer>>,
+    /// session_id -> cancelled when the session is removed
+    closed: DashMap<u64, CancellationToken>,
+    /// backend (or pool instance) name -> session_id -> in-flight call count
+    in_flight: DashMap<String, HashMap<u64, usize>>,
+    /// Progress tokens of in-flight backend calls
+    progress: ProgressRoutes,
+    /// session_id -> minimum level set with `logging/setLevel`
+    log_levels: DashMap<u64, LoggingLevel>,
+    /// dedicated instance name -> (backend name, session_id it is assigned to)
+    instances: DashMap<String, (String, u64)>,
+    /// Last session id handed out by [`ClientSessions::new_id`]
+    last_id: AtomicU64,
+}
+
+impl ClientSessions {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Allocate a session id (from 1; direct mode uses 0).
+    pub fn new_id(&self) -> u64 {
+        self.last_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Register the peer of an initialized client session. The returned
+    /// token is cancelled once the session is removed.
+    pub fn register(&self, session_id: u64, peer: Peer<RoleServer>) -> CancellationToken {
+        self.peers.insert(session_id, peer);
+        let closed = CancellationToken::new();
+        if let Some(previous) = self.closed.insert(session_id, closed.clone()) {
+            previous.cancel();
+        }
+        closed
+    }
+
+    /// Forget a disconnected session.
+    pub fn remove(&self, session_id: u64) {
+        self.peers.remove(&session_id);
+        self.log_levels.remove(&session_id);
+        if let Some((_, closed)) = self.closed.remove(&session_id) {
+            closed.cancel();
+        }
+    }
+
+    /// Peer of a connected session.
+    pub fn peer(&self, session_id: u64) -> Option<Peer<RoleServer>> {
+        self.peers.get(&session_id).map(|r| r.value().clone())
+    }
+
+    /// Set the minimum level of log messages sent to a session.
+    pub fn set_log_level(&self, session_id: u64, level: LoggingLevel) {
+        self.log_levels.insert(session_id, level);
+    }
+
+    /// Minimum log level of a session ([`DEFAULT_LOG_LEVEL`] until it sets one).
+    pub fn log_level(&self, session_id: u64) -> LoggingLevel {
+        self.log_levels
+            .get(&session_id)
+            .map_or(DEFAULT_LOG_LEVEL, |r| *r.value())
+    }
+
+    /// Whether a message at `level` should be sent to a session.
+    pub fn wants_log(&self, session_id: u64, level: LoggingLevel) -> bool {
+        severity(level) >= severity(self.log_level(session_id))
+    }
+
+    /// Record that dedicated `instance` of `backend` serves `session_id`.
+    pub fn assign_instance(&self, instance: &str, backend: &str, session_id: u64) {
+        self.instances
+            .insert(instance.to_string(), (backend.to_string(), session_id));
+    }
+
+    /// Forget a dedicated instance's assignment once it is released.
+    pub fn unassign_instance(&self, instance: &str) {
+        self.instances.remove(instance);
+    }
+
+    /// Backend name and session of a dedicated instance, while assigned.
+    pub fn instance_owner(&self, instance: &str) -> Option<(String, u64)> {
+        self.instances.get(instance).map(|r| r.value().clone())
+    }
+
+    /// Record a call from `session_id` in flight on `backend` until the guard drops.
+    pub fn begin_call(self: &Arc<Self>, backend: &str, session_id: u64) -> InFlightCall {
+        *self
+            .in_flight
+            .entry(backend.to_string())
+            .or_default()
+            .entry(session_id)
+            .or_default() += 1;
+        InFlightCall {
+            sessions: Arc::clone(self),
+            backend: backend.to_string(),
+            session_id,
+        }
+    }
+
+    /// Sessions with at least one call in flight on `backend`, sorted.
+    pub fn calling_sessions(&self, backend: &str) -> Vec<u64> {
+        let mut sessions: Vec<u64> = self
+            .in_flight
+            .get(backend)
+            .map(|r| r.value().keys().copied().collect())
+            .unwrap_or_default();
+        sessions.sort_unstable();
+        sessions
+    }
+
+    /// Where progress notifications from backends are relayed to.
+    pub(super) fn progress_routes(&self) -> &ProgressRoutes {
+        &self.progress
+    }
+
+    fn end_call(&self, backend: &str, session_id: u64) {
+        if let Some(mut calls) = self.in_flight.get_mut(backend)
+            && let Some(count) = calls.get_mut(&session_id)
+        {
+            *count -= 1;
+            if *count == 0 {
+                calls.remove(&session_id);
+            }
+        }
+        self.in_flight
+            .remove_if(backend, |_, calls| calls.is_empty());
+    }
+}
+
+/// RAII guard for one in-flight call; see [`ClientSessions::begin_call`].
+pub struct InFlightCall {
+    sessions: Arc<ClientSessions>,
+    backend: String,
+    session_id: u64,
+}
+
+impl Drop for InFlightCall {
+    fn drop(&mut self) {
+        self.sessions.end_call(&self.backend, self.session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calling_sessions_tracks_guards() {
+        let sessions = ClientSessions::new();
+        assert!(sessions.calling_sessions("exa").is_empty());
+
+        let a1 = sessions.begin_call("exa", 1);
+        let a2 = sessions.begin_call("exa", 1);
+        assert_eq!(sessions.calling_sessions("exa"), vec![1]);
+
+        let b = sessions.begin_call("exa", 2);
+        assert_eq!(sessions.calling_sessions("exa"), vec![1, 2]);
+        assert!(sessions.calling_sessions("tavily").is_empty());
+
+        drop(a1);
+        assert_eq!(sessions.calling_sessions("exa"), vec![1, 2]);
+        drop(a2);
+        assert_eq!(sessions.calling_sessions("exa"), vec![2]);
+        drop(b);
+        assert!(sessions.calling_sessions("exa").is_empty());
+        assert!(sessions.in_flight.is_empty());
+    }
+
+    #[test]
+    fn test_instance_assignment() {
+        let sessions = ClientSessions::new();
+        assert!(sessions.instance_owner("db-pool-0").is_none());
+        sessions.assign_instance("db-pool-0", "db", 7);
+        assert_eq!(
+            sessions.instance_owner("db-pool-0"),
+            Some(("db".to_string(), 7))
+        );
+        sessions.unassign_instance("db-pool-0");
+        assert!(sessions.instance_owner("db-pool-0").is_none());
+    }
+}
@@ -11,12 +11,15 @@ use tracing::{debug, error, info, warn};
 
 const STDERR_BUFFER_SIZE: usize = 200;
 
+use super::events::{BackendClient, BackendEventSender};
+use super::sessions::ClientSessions;
 use super::{Backend, BackendState, STATE_HEALTHY, STATE_STARTING, STATE_STOPPED};
 use super::{
-    is_available_from_atomic, map_call_tool_result, map_tools_to_entries, state_from_atomic,
-    store_state,
+    arguments_object, is_available_from_atomic, map_call_tool_outcome, map_tools_to_entries,
+    send_tool_call, state_from_atomic, store_state,
 };
 use crate::config::BackendConfig;
+use crate::error::GatewayError;
 use crate::registry::ToolEntry;
 
 /// A stdio child-process MCP backend using rmcp.
@@ -28,11 +31,15 @@ use crate::registry::ToolEntry;
 pub struct StdioBackend {
     name: String,
     config: BackendConfig,
-    service: RwLock<Option<RunningService<rmcp::RoleClient, ()>>>,
+    service: RwLock<Option<RunningService<rmcp::RoleClient, BackendClient>>>,
     state: AtomicU8,
     child: RwLock<Option<tokio::process::Child>>,
     stderr_buffer: Arc<std::sync::Mutex<std::collections::VecDeque<String>>>,
     pid: std::sync::atomic::AtomicU32,
+    /// Where backend notifications go (`None` for dedicated pool instances).
+    events: Option<BackendEventSender>,
+    /// Client sessions that backend requests (elicitation, sampling) are routed to.
+    sessions: Option<Arc<ClientSessions>>,
 }
 
 impl StdioBackend {
@@ -47,6 +54,30 @@ impl StdioBackend {
                 std::collections::VecDeque::with_capacity(STDERR_BUFFER_SIZE),
             )),
             pid: std::sync::atomic::AtomicU32::new(0),
+            events: None,
+            sessions: None,
+        }
+    }
+
+    /// Forward notifications from this backend (e.g. `tools/list_changed`) to the manager.
+    pub fn with_events(mut self, events: BackendEventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Route requests from this backend (elicitation, sampling) to client sessions.
+    pub fn with_sessions(mut self, sessions: Arc<ClientSessions>) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    fn client_handler(&self) -> BackendClient {
+        let client = BackendClient::new(self.name.clone(), self.events.clone());
+        match self.sessions {
+            Some(ref sessions) => client
+                .with_sessions(Arc::clone(sessions), self.config.elicitation)
+                .with_sampling(self.config.sampling),
+            None => client,
         }
     }
 
@@ -194,7 +225,8 @@ impl Backend for StdioBackend {
         }
 
         // rmcp accepts (AsyncRead, AsyncWrite) tuples as IntoTransport
-        let service = ()
+        let service = self
+            .client_handler()
             .serve((stdout, stdin))
             .await
             .with_context(|| format!("failed MCP handshake with backend '{}'", self.name))?;
@@ -243,37 +275,27 @@ impl Backend for StdioBackend {
 
     async fn call_tool(&self, tool_name: &str, arguments: Option<Value>) -> Result<Value> {
         let guard = self.service.read().await;
-        let service = guard
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("backend '{}' not started", self.name))?;
+        let service = guard.as_ref().ok_or_else(|| GatewayError::NotStarted {
+            backend: self.name.clone(),
+        })?;
 
         let mut params = CallToolRequestParams::new(tool_name.to_string());
-        if let Some(args) = arguments.and_then(|v| v.as_object().cloned()) {
+        if let Some(args) = arguments_object(&self.name, tool_name, arguments)? {
             params = params.with_arguments(args);
         }
 
         debug!(backend = %self.name, tool = %tool_name, "calling tool");
 
-        let result = tokio::time::timeout(self.config.timeout, service.call_tool(params))
-            .await
-            .map_err(|_| {
-                anyhow::anyhow!(
-                    "tool call '{}' on backend '{}' timed out after {:?}",
-                    tool_name,
-                    self.name,
-                    self.config.timeout
-                )
-            })?
-            .map_err(|e| {
-                anyhow::anyhow!(
-                    "tool call '{}' on backend '{}' failed: {}",
-                    tool_name,
-                    self.name,
-                    e
-                )
-            })?;
-
-        Ok(map_call_tool_result(result))
+        let result = send_tool_call(
+            service.peer(),
+            params,
+            self.sessions.as_deref(),
+            &self.name,
+            self.config.timeout,
+        )
+        .await?;
+
+        map_call_tool_outcome(result, &self.name, tool_name)
     }
 
     async fn discover_tools(&self) -> Result<Vec<ToolEntry>> {
@@ -339,6 +361,9 @@ impl Backend for StdioBackend {
         let p = self.pid.load(Ordering::Acquire);
         if p > 0 { Some(p) } else { None }
     }
+    async fn peer(&self) -> Option<rmcp::service::Peer<rmcp::RoleClient>> {
+        self.service.read().await.as_ref().map(|s| s.peer().clone())
+    }
 }
 
 #[cfg(test)]
@@ -364,6 +389,9 @@ mod tests {
             retry: Default::default(),
             prerequisite: None,
             rate_limit: None,
+            cache: None,
+            validate_arguments: true,
+            coerce_arguments: false,
             tags: vec![],
             fallback_chain: vec![],
             tools: None,
@@ -374,6 +402,9 @@ mod tests {
             shutdown_grace_period: Duration::from_millis(100),
             max_memory_mb: None,
             oauth: None,
+            elicitation: Default::default(),
+            sampling: Default::default(),
+            pinned_tools: Vec::new(),
         }
     }
 
@@ -1,4 +1,4 @@
-use crate::registry::{ToolEntry, ToolRegistry};
+use crate::registry::{PromptEntry, ToolEntry, ToolRegistry};
 use crate::tracker::CallTracker;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -6,8 +6,11 @@ use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
 
 /// Current cache version. Bump when adding new persisted fields.
-const CACHE_VERSION: u32 = 4;
+const CACHE_VERSION: u32 = 6;
 
+/// Version 5 adds `output_schema` and `annotations` to each `ToolEntry`
+/// (both optional, so older caches load unchanged). Version 6 adds backend
+/// prompts.
 #[derive(Serialize, Deserialize)]
 struct ToolCache {
     version: u32,
@@ -18,6 +21,9 @@ struct ToolCache {
     /// Per-tool usage counts. Only present in version 4+ caches.
     #[serde(default)]
     usage_stats: Option<HashMap<String, u64>>,
+    /// Backend prompts keyed by backend name. Only present in version 6+ caches.
+    #[serde(default)]
+    prompts: Option<HashMap<String, Vec<PromptEntry>>>,
 }
 
 /// Default cache path: platform cache directory
@@ -84,6 +90,15 @@ pub async fn load(
         }
     }
 
+    // Restore backend prompts (version 6+ caches)
+    if let Some(prompts) = cache.prompts {
+        for (backend_name, prompts) in prompts {
+            if config_backend_names.contains(&backend_name) {
+                registry.register_backend_prompts(&backend_name, prompts);
+            }
+        }
+    }
+
     // Restore cached embeddings (semantic feature only)
     #[cfg(feature = "semantic")]
     if let Some(embeddings) = cache.embeddings
@@ -122,6 +137,7 @@ pub async fn save(path: &Path, registry: &ToolRegistry, tracker: Option<&CallTra
         backends: snapshot,
         embeddings,
         usage_stats,
+        prompts: Some(registry.prompt_snapshot()),
     };
 
     let json = match serde_json::to_string_pretty(&cache) {
@@ -159,6 +175,8 @@ mod tests {
             backend_name: backend.to_string(),
             input_schema: json!({"type": "object"}),
             tags: Vec::new(),
+            output_schema: None,
+            annotations: None,
         }
     }
 
@@ -203,6 +221,33 @@ mod tests {
         assert!(registry2.get_by_name("web_search").is_none());
     }
 
+    #[tokio::test]
+    async fn test_prompts_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(".test.cache.json");
+
+        let registry = ToolRegistry::new();
+        registry.register_backend_tools("gh", vec![make_entry("get_repo", "gh")]);
+        registry.register_backend_prompts(
+            "gh",
+            vec![PromptEntry {
+                name: "gh.review_pr".to_string(),
+                original_name: "review_pr".to_string(),
+                backend_name: "gh".to_string(),
+                title: Some("Review PR".to_string()),
+                description: None,
+                arguments: Vec::new(),
+            }],
+        );
+        save(&cache_path, &registry, None).await;
+
+        let registry2 = ToolRegistry::new();
+        load(&cache_path, &registry2, &["gh".to_string()], None).await;
+        let prompt = registry2.get_prompt("gh.review_pr").unwrap();
+        assert_eq!(prompt.original_name, "review_pr");
+        assert_eq!(prompt.title.as_deref(), Some("Review PR"));
+    }
+
     #[tokio::test]
     async fn test_load_filters_by_config() {
         let dir = tempfile::tempdir().unwrap();
@@ -327,6 +372,34 @@ mod tests {
         assert_eq!(tracker2.usage_count("exa.web_search"), 2);
     }
 
+    #[tokio::test]
+    async fn test_cache_v5_annotations_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_path = dir.path().join(".test.cache.json");
+
+        let mut entry = make_entry("get_repo", "github");
+        entry.annotations = Some(crate::registry::ToolAnnotations {
+            title: Some("Get Repository".to_string()),
+            read_only_hint: Some(true),
+            ..Default::default()
+        });
+        entry.output_schema = Some(json!({"type": "object"}));
+        let registry = ToolRegistry::new();
+        registry.register_backend_tools("github", vec![entry]);
+
+        save(&cache_path, &registry, None).await;
+
+        let registry2 = ToolRegistry::new();
+        load(&cache_path, &registry2, &["github".to_string()], None).await;
+        let loaded = registry2.get_by_name("github.get_repo").unwrap();
+        assert!(loaded.is_read_only());
+        assert_eq!(
+            loaded.annotations.unwrap().title.as_deref(),
+            Some("Get Repository")
+        );
+        assert_eq!(loaded.output_schema, Some(json!({"type": "object"})));
+    }
+
     #[tokio::test]
     async fn test_cache_v3_migration_to_v4() {
         // v3 cache without usage_stats should load fine, defaulting to no usage
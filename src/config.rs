@@ -7,6 +7,8 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Once};
 use std::time::Duration;
 
+use crate::registry::ToolEntry;
+
 static DOTENV_ONCE: Once = Once::new();
 static SECRETREF_RE: LazyLock<regex::Regex> =
     LazyLock::new(|| regex::Regex::new(r"secretref:[^:\s]+:[\w/.\-]+").unwrap());
@@ -140,6 +142,17 @@ pub struct Config {
     /// These are registered under a virtual `__composite` backend.
     #[serde(default)]
     pub composite_tools: HashMap<String, CompositeToolConfig>,
+
+    /// Tools exposed directly in `tools/list` with their real schemas, so
+    /// clients can call them without `call_tool_chain`. Names are resolved
+    /// like `call_tool_chain` names (`backend.tool`, bare names, aliases).
+    #[serde(default)]
+    pub pinned_tools: Vec<String>,
+
+    /// Named groups of tools a session can expose natively with
+    /// `enable_toolset`.
+    #[serde(default)]
+    pub toolsets: HashMap<String, ToolsetConfig>,
 }
 
 /// Configuration for a composite tool — a multi-step TypeScript snippet
@@ -157,6 +170,65 @@ pub struct CompositeToolConfig {
     pub input_schema: Option<serde_json::Value>,
 }
 
+/// A named group of tools. A tool belongs to the toolset if it matches any
+/// of the selectors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolsetConfig {
+    /// What the toolset is for, shown to the agent.
+    #[serde(default)]
+    pub description: Option<String>,
+    /// All tools of these backends.
+    #[serde(default)]
+    pub backends: Vec<String>,
+    /// Tools with any of these tags (config tags or annotation hints such as
+    /// `read-only`).
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Namespaced tool name globs (`*` and `?`), e.g. `github.*_issue`.
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+impl ToolsetConfig {
+    fn is_empty(&self) -> bool {
+        self.backends.is_empty() && self.tags.is_empty() && self.tools.is_empty()
+    }
+
+    /// Whether `entry` belongs to the toolset.
+    pub fn matches(&self, entry: &ToolEntry) -> bool {
+        self.backends.contains(&entry.backend_name)
+            || entry.has_any_tag(&self.tags)
+            || self.tools.iter().any(|glob| glob_match(glob, &entry.name))
+    }
+}
+
+/// Match `name` against a glob where `*` matches any run of characters and
+/// `?` exactly one.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` and the name position it currently covers up to.
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // Let the last `*` swallow one more character and retry.
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 /// Secrets resolution configuration.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SecretsConfig {
@@ -249,13 +321,28 @@ pub struct BackendConfig {
     #[serde(default)]
     pub rate_limit: Option<RateLimitConfig>,
 
+    /// Result cache for read-only tools. None = no caching.
+    #[serde(default)]
+    pub cache: Option<ResultCacheConfig>,
+
+    /// Check call arguments against each tool's input schema before
+    /// dispatch. Default: true.
+    #[serde(default = "default_true_config")]
+    pub validate_arguments: bool,
+
+    /// Repair common argument mistakes (`"5"` for an integer, a bare value
+    /// for an array, `snake_case` for a `camelCase` property, ...) using the
+    /// tool's input schema before validation. Default: false.
+    #[serde(default)]
+    pub coerce_arguments: bool,
+
     /// Tags applied to all tools from this backend. Used for search filtering.
     #[serde(default)]
     pub tags: Vec<String>,
 
     /// Fallback backend names to try if this backend fails with a transient error.
-    /// Only triggered for: network errors, timeouts, rate limit exceeded.
-    /// NOT triggered for: invalid parameters, tool not found, authentication errors.
+    /// Only triggered for: transport failures, timeouts, rate limit / concurrency limits.
+    /// NOT triggered for: invalid arguments, tool not found, tool-reported (`isError`) errors.
     #[serde(default)]
     pub fallback_chain: Vec<String>,
 
@@ -294,6 +381,21 @@ pub struct BackendConfig {
     /// OAuth 2.0 configuration for this backend.
     #[serde(default)]
     pub oauth: Option<crate::oauth::OAuthConfig>,
+
+    /// What to do with elicitation requests from this backend: route (default)
+    /// or decline.
+    #[serde(default)]
+    pub elicitation: ElicitationPolicy,
+
+    /// Whether this backend may ask the client's LLM for completions via
+    /// sampling: allow or deny (default).
+    #[serde(default)]
+    pub sampling: SamplingPolicy,
+
+    /// Tools of this backend (as the backend names them) exposed directly
+    /// in `tools/list`, alongside the meta-tools.
+    #[serde(default)]
+    pub pinned_tools: Vec<String>,
 }
 
 /// Per-backend retry configuration for transient failures (Starting state).
@@ -334,6 +436,29 @@ pub struct RateLimitConfig {
     pub window: Duration,
 }
 
+/// Result cache for a backend's read-only tools.
+///
+/// Results are keyed by tool and arguments and reused until `ttl` passes.
+/// Identical calls in flight at the same time share one backend request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResultCacheConfig {
+    /// How long a result is reused. Default: 5m.
+    #[serde(default = "default_cache_ttl", with = "humantime_duration")]
+    pub ttl: Duration,
+    /// Most results kept; the least recently used is evicted. Default: 256.
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+    /// Cache tools the backend annotates with `readOnlyHint`. Default: true.
+    #[serde(default = "default_true_config")]
+    pub read_only_hint: bool,
+    /// Tools (as the backend names them) cached as read-only regardless of annotations.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Tools never cached, e.g. read-only tools whose results change quickly.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
 /// Configuration for a prerequisite process that must be running before a backend starts.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PrerequisiteConfig {
@@ -382,6 +507,40 @@ pub enum InstanceMode {
     Dedicated,
 }
 
+/// Handling of elicitation requests (`elicitation/create`) sent by a backend.
+///
+/// `route` forwards the request to the client session whose call is in flight
+/// on the backend. Dedicated instances always have exactly one such session;
+/// on a shared backend, a request is declined when no session or more than one
+/// session has a call in flight, since the requester can't be identified.
+/// `decline` declines every request without asking a client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitationPolicy {
+    #[default]
+    Route,
+    Decline,
+}
+
+/// Whether a backend may send sampling (`sampling/createMessage`) requests.
+///
+/// `allow` advertises sampling to the backend and forwards each request to the
+/// client session whose call is in flight on it, routed like elicitation.
+/// Requests that can't be attributed to one session, or whose client lacks
+/// sampling support, fail. `deny` does not advertise sampling at all.
+///
+/// Dedicated instances started for a session only advertise sampling if that
+/// session's client supports it. Shared backends start before any client
+/// connects, so `allow` advertises it unconditionally; that is why `deny` is
+/// the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SamplingPolicy {
+    Allow,
+    #[default]
+    Deny,
+}
+
 /// Pool configuration for dedicated instance mode.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PoolConfig {
@@ -542,9 +701,43 @@ pub struct OutputConfig {
     #[serde(default = "default_true_config")]
     pub smart_truncation: bool,
 
-    /// Threshold in bytes above which JSON auto-chunking activates. Default: 10240 (10KB).
-    #[serde(default = "default_chunk_threshold")]
-    pub chunk_threshold: usize,
+    /// Threshold above which JSON auto-chunking activates, in `budget_unit`.
+    /// Default: 10240 bytes (10KB), converted like the other built-in byte
+    /// limits when `budget_unit` is `tokens` (2560 tokens). Read it with
+    /// [`OutputConfig::chunk_threshold`].
+    #[serde(default)]
+    pub chunk_threshold: Option<usize>,
+
+    /// Most sections, items or keys the intent filter keeps, best first,
+    /// within the output budget. Default: 10.
+    #[serde(default = "default_intent_top_k")]
+    pub intent_top_k: usize,
+
+    /// Mix embedding similarity into the intent filter's ranking when an
+    /// embedding index is loaded. Default: true.
+    #[serde(default = "default_true_config")]
+    pub intent_semantic: bool,
+
+    /// Unit of `max_output_size` and `chunk_threshold`: `bytes` or `tokens`.
+    /// With `tokens`, budgets, the intent-filter threshold and the size
+    /// metadata are counted in estimated tokens. Default: bytes.
+    #[serde(default)]
+    pub budget_unit: crate::tokens::BudgetUnit,
+
+    /// Token estimator for token budgets and the session stats: `heuristic`
+    /// (character classes) or `bytes` (4 bytes per token). Default: heuristic.
+    #[serde(default)]
+    pub token_estimator: crate::tokens::EstimatorKind,
+
+    /// Total bytes of full results kept when chunking or truncation drops
+    /// part of an output, for paging with `read_result`. Oldest results are
+    /// evicted first. 0 disables. Default: 67108864 (64MB).
+    #[serde(default = "default_result_store_bytes")]
+    pub result_store_bytes: usize,
+
+    /// How long a kept full result can be read. Default: 30m.
+    #[serde(default = "default_result_ttl", with = "humantime_duration")]
+    pub result_ttl: Duration,
 }
 
 impl Default for OutputConfig {
@@ -552,9 +745,35 @@ impl Default for OutputConfig {
         Self {
             auto_chunk_json: true,
             smart_truncation: true,
-            chunk_threshold: default_chunk_threshold(),
+            chunk_threshold: None,
+            intent_top_k: default_intent_top_k(),
+            intent_semantic: true,
+            budget_unit: Default::default(),
+            token_estimator: Default::default(),
+            result_store_bytes: default_result_store_bytes(),
+            result_ttl: default_result_ttl(),
+        }
+    }
+}
+
+impl OutputConfig {
+    pub fn estimator(&self) -> &'static dyn crate::tokens::TokenEstimator {
+        self.token_estimator.estimator()
+    }
+
+    /// Measures output in `budget_unit`.
+    pub fn meter(&self) -> crate::tokens::Meter {
+        match self.budget_unit {
+            crate::tokens::BudgetUnit::Bytes => crate::tokens::Meter::Bytes,
+            crate::tokens::BudgetUnit::Tokens => crate::tokens::Meter::Tokens(self.estimator()),
         }
     }
+
+    /// JSON auto-chunking threshold in `budget_unit`.
+    pub fn chunk_threshold(&self) -> usize {
+        self.chunk_threshold
+            .unwrap_or_else(|| self.meter().from_bytes(default_chunk_threshold()))
+    }
 }
 
 /// Daemon lifecycle configuration.
@@ -658,9 +877,18 @@ fn default_semaphore_timeout() -> Duration {
 fn default_max_concurrent_sandboxes() -> u32 {
     8
 }
+fn default_intent_top_k() -> usize {
+    10
+}
 fn default_chunk_threshold() -> usize {
     10_240 // 10KB
 }
+fn default_result_store_bytes() -> usize {
+    64 * 1024 * 1024
+}
+fn default_result_ttl() -> Duration {
+    Duration::from_secs(1800)
+}
 fn default_max_retries() -> u32 {
     5
 }
@@ -676,6 +904,12 @@ fn default_backoff_multiplier() -> f64 {
 fn default_rate_window() -> Duration {
     Duration::from_secs(60)
 }
+fn default_cache_ttl() -> Duration {
+    Duration::from_secs(300)
+}
+fn default_cache_max_entries() -> usize {
+    256
+}
 fn default_restart_initial_backoff() -> Duration {
     Duration::from_secs(1)
 }
@@ -1036,6 +1270,14 @@ impl Config {
                 );
             }
         }
+
+        for (name, toolset) in &self.toolsets {
+            if toolset.is_empty() {
+                anyhow::bail!(
+                    "toolset '{name}': requires at least one of 'backends', 'tags' or 'tools'"
+                );
+            }
+        }
         Ok(())
     }
 }
@@ -1051,6 +1293,24 @@ pub struct ConfigDiff {
 }
 
 impl Config {
+    /// All pinned tool names: the global list plus each backend's pinned
+    /// tools under its namespace (`backend.tool`), sorted and deduplicated.
+    pub fn pinned_tool_names(&self) -> Vec<String> {
+        let mut names = self.pinned_tools.clone();
+        for (name, backend) in &self.backends {
+            let namespace = backend.namespace.as_deref().unwrap_or(name);
+            names.extend(
+                backend
+                    .pinned_tools
+                    .iter()
+                    .map(|tool| format!("{namespace}.{tool}")),
+            );
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
     /// Compute the diff between this config and a new config.
     pub fn diff_backends(&self, new: &Config) -> ConfigDiff {
         let mut added = Vec::new();
@@ -1159,6 +1419,9 @@ pub async fn watch_config(
                     || !diff.removed.is_empty()
                     || !diff.changed.is_empty();
                 let has_alias_changes = new_config.aliases != old_config.aliases;
+                let has_pinned_changes =
+                    new_config.pinned_tool_names() != old_config.pinned_tool_names();
+                let has_toolset_changes = new_config.toolsets != old_config.toolsets;
                 let has_composite_changes = new_config.composite_tools != old_config.composite_tools;
 
                 if has_composite_changes {
@@ -1168,8 +1431,21 @@ pub async fn watch_config(
                     );
                 }
 
+                // The registry tells clients if the exposed tools actually change.
+                if has_pinned_changes {
+                    let pinned = new_config.pinned_tool_names();
+                    info!(count = pinned.len(), "updating pinned tools");
+                    registry.set_pinned_tools(pinned);
+                }
+                if has_toolset_changes {
+                    info!(count = new_config.toolsets.len(), "updating toolsets");
+                    registry.set_toolsets(new_config.toolsets.clone());
+                }
+
                 if !has_backend_changes && !has_alias_changes {
-                    info!("config reloaded, no changes detected");
+                    if !has_pinned_changes && !has_toolset_changes {
+                        info!("config reloaded, no changes detected");
+                    }
                     current_config.store(Arc::new(new_config));
                     continue;
                 }
@@ -1284,6 +1560,85 @@ backends:
         );
     }
 
+    #[test]
+    fn test_pinned_tool_names() {
+        let yaml = r#"
+pinned_tools: ["exa.web_search_exa", "gh.get_issue"]
+backends:
+  github:
+    command: echo
+    namespace: gh
+    pinned_tools: ["get_issue", "create_pr"]
+  tavily:
+    command: echo
+    pinned_tools: ["search"]
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+        assert_eq!(
+            config.pinned_tool_names(),
+            vec![
+                "exa.web_search_exa",
+                "gh.create_pr",
+                "gh.get_issue",
+                "tavily.search"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_toolsets() {
+        let yaml = r#"
+toolsets:
+  issues:
+    description: Issue tracking
+    backends: [linear]
+    tools: ["github.*_issue"]
+  readonly:
+    tags: [read-only]
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        let issues = &config.toolsets["issues"];
+        assert_eq!(issues.description.as_deref(), Some("Issue tracking"));
+        assert_eq!(issues.backends, vec!["linear"]);
+        assert_eq!(issues.tools, vec!["github.*_issue"]);
+        assert_eq!(config.toolsets["readonly"].tags, vec!["read-only"]);
+
+        let yaml = r#"
+toolsets:
+  empty:
+    description: Matches nothing
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_client_request_policies() {
+        let yaml = r#"
+backends:
+  default:
+    command: echo
+  quiet:
+    command: echo
+    elicitation: decline
+  research:
+    command: echo
+    sampling: allow
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+        assert_eq!(
+            config.backends["default"].elicitation,
+            ElicitationPolicy::Route
+        );
+        assert_eq!(config.backends["default"].sampling, SamplingPolicy::Deny);
+        assert_eq!(
+            config.backends["quiet"].elicitation,
+            ElicitationPolicy::Decline
+        );
+        assert_eq!(config.backends["research"].sampling, SamplingPolicy::Allow);
+    }
+
     #[test]
     fn test_validate_stdio_missing_command() {
         let yaml = r#"
@@ -1623,6 +1978,29 @@ backends:
         let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
         let backend = config.backends.get("test").unwrap();
         assert!(backend.rate_limit.is_none());
+        assert!(backend.cache.is_none());
+        assert!(backend.validate_arguments);
+        assert!(!backend.coerce_arguments);
+    }
+
+    #[test]
+    fn test_result_cache_config_parsing() {
+        let yaml = r#"
+backends:
+  exa:
+    transport: stdio
+    command: echo
+    cache:
+      ttl: 10m
+      tools: [web_search]
+"#;
+        let config: Config = serde_yaml_ng::from_str(yaml).unwrap();
+        let cache = config.backends["exa"].cache.as_ref().unwrap();
+        assert_eq!(cache.ttl, Duration::from_secs(600));
+        assert_eq!(cache.max_entries, 256);
+        assert!(cache.read_only_hint);
+        assert_eq!(cache.tools, ["web_search"]);
+        assert!(cache.exclude.is_empty());
     }
 
     #[test]
@@ -1658,6 +2036,18 @@ backends: {}
         assert_eq!(config.health.drain_timeout, Duration::from_secs(15));
     }
 
+    #[test]
+    fn test_chunk_threshold_default_follows_budget_unit() {
+        let mut output = OutputConfig::default();
+        assert_eq!(output.chunk_threshold(), 10_240);
+        output.budget_unit = crate::tokens::BudgetUnit::Tokens;
+        assert_eq!(output.chunk_threshold(), 2_560);
+
+        let yaml = "budget_unit: tokens\nchunk_threshold: 4000\n";
+        let output: OutputConfig = serde_yaml_ng::from_str(yaml).unwrap();
+        assert_eq!(output.chunk_threshold(), 4_000);
+    }
+
     // --- Env fallback + validate_no_unresolved tests ---
 
     #[test]
@@ -1769,4 +2159,16 @@ backends:
         assert_eq!(refs, vec!["secretref:bws:project/dotenv/key/LEAKED"]);
         assert!(!refs[0].contains("plain-token"));
     }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("github.*", "github.get_issue"));
+        assert!(glob_match("*_issue", "github.get_issue"));
+        assert!(glob_match("github.*_issue", "github.create_issue"));
+        assert!(glob_match("exa.web_search_ex?", "exa.web_search_exa"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("github.*", "gitlab.get_issue"));
+        assert!(!glob_match("*_issue", "github.get_issues"));
+        assert!(!glob_match("exa.?", "exa.ab"));
+    }
 }
@@ -9,7 +9,7 @@ mod tests {
     use std::sync::Arc;
     use std::time::Duration;
 
-    use crate::backend::{Backend, BackendManager, BackendState, store_state, STATE_UNHEALTHY};
+    use crate::backend::{Backend, BackendManager, BackendState};
     use crate::registry::{ToolEntry, ToolRegistry};
 
     /// Helper: create a ToolEntry with the given name, description, and backend.
@@ -21,16 +21,13 @@ mod tests {
             backend_name: backend.to_string(),
             input_schema: serde_json::json!({"type": "object"}),
             tags: Vec::new(),
+            output_schema: None,
+            annotations: None,
         }
     }
 
     /// Helper: create a ToolEntry with explicit tags.
-    fn make_entry_tagged(
-        name: &str,
-        desc: &str,
-        backend: &str,
-        tags: Vec<String>,
-    ) -> ToolEntry {
+    fn make_entry_tagged(name: &str, desc: &str, backend: &str, tags: Vec<String>) -> ToolEntry {
         ToolEntry {
             name: name.to_string(),
             original_name: name.to_string(),
@@ -38,6 +35,8 @@ mod tests {
             backend_name: backend.to_string(),
             input_schema: serde_json::json!({"type": "object"}),
             tags,
+            output_schema: None,
+            annotations: None,
         }
     }
 
@@ -50,11 +49,17 @@ mod tests {
     #[test]
     fn test_empty_tool_name_registration() {
         let reg = ToolRegistry::new();
-        reg.register_backend_tools("backend", vec![make_entry("", "Empty name tool", "backend")]);
+        reg.register_backend_tools(
+            "backend",
+            vec![make_entry("", "Empty name tool", "backend")],
+        );
 
         // Namespaced key should still be accessible
         let entry = reg.get_by_name("backend.");
-        assert!(entry.is_some(), "empty-name tool should be accessible via namespaced key");
+        assert!(
+            entry.is_some(),
+            "empty-name tool should be accessible via namespaced key"
+        );
         let entry = entry.unwrap();
         assert_eq!(entry.description, "Empty name tool");
         assert_eq!(entry.backend_name, "backend");
@@ -93,7 +98,10 @@ mod tests {
         );
 
         let results = reg.search("   \t\n  ", 10, None, None);
-        assert!(results.is_empty(), "whitespace-only query should return no results");
+        assert!(
+            results.is_empty(),
+            "whitespace-only query should return no results"
+        );
     }
 
     /// Look up a tool name that doesn't exist.
@@ -114,7 +122,10 @@ mod tests {
         reg.register_backend_tools("test", vec![make_entry("real_tool", "A real tool", "test")]);
 
         let results = reg.search("xyzzy_plugh", 10, None, None);
-        assert!(results.is_empty(), "nonsense query should return no results");
+        assert!(
+            results.is_empty(),
+            "nonsense query should return no results"
+        );
     }
 
     // ============================================================
@@ -138,11 +149,17 @@ mod tests {
         // Tool should be accessible
         let entry = reg.get_by_name("big_tool").unwrap();
         assert_eq!(entry.backend_name, "big_backend");
-        assert!(entry.description.len() > 9000, "description should be ~10KB");
+        assert!(
+            entry.description.len() > 9000,
+            "description should be ~10KB"
+        );
 
         // Search by name still works
         let results = reg.search("big_tool", 5, None, None);
-        assert!(!results.is_empty(), "should find tool by name even with large description");
+        assert!(
+            !results.is_empty(),
+            "should find tool by name even with large description"
+        );
     }
 
     /// Register many tools (100+) with large descriptions and verify search
@@ -171,10 +188,7 @@ mod tests {
     #[test]
     fn test_zero_length_description() {
         let reg = ToolRegistry::new();
-        reg.register_backend_tools(
-            "minimal",
-            vec![make_entry("minimal_tool", "", "minimal")],
-        );
+        reg.register_backend_tools("minimal", vec![make_entry("minimal_tool", "", "minimal")]);
 
         let entry = reg.get_by_name("minimal_tool").unwrap();
         assert!(entry.description.is_empty());
@@ -195,9 +209,17 @@ mod tests {
         reg.register_backend_tools(
             "unicode",
             vec![
-                make_entry("café_search", "Search for café-related content ☕", "unicode"),
+                make_entry(
+                    "café_search",
+                    "Search for café-related content ☕",
+                    "unicode",
+                ),
                 make_entry("日本語ツール", "日本語のツールです 🎌", "unicode"),
-                make_entry("π_calculator", "Calculate π to arbitrary precision", "unicode"),
+                make_entry(
+                    "π_calculator",
+                    "Calculate π to arbitrary precision",
+                    "unicode",
+                ),
                 make_entry("✨_magic", "✨ Magic tool with emoji name ✨", "unicode"),
             ],
         );
@@ -230,10 +252,13 @@ mod tests {
         let results = reg.search("search", 5, None, None);
         assert!(!results.is_empty());
 
-        // Non-ASCII query: "café" should tokenize to ["café"] 
+        // Non-ASCII query: "café" should tokenize to ["café"]
         // and match "café_search" which tokenizes to ["café", "search"]
         let results = reg.search("café", 5, None, None);
-        assert!(!results.is_empty(), "Unicode query 'café' should find results");
+        assert!(
+            !results.is_empty(),
+            "Unicode query 'café' should find results"
+        );
     }
 
     /// Test with emoji-only description.
@@ -316,7 +341,10 @@ mod tests {
                     let query = format!("tool_{}", i % 20);
                     let results = reg.search(&query, 10, None, None);
                     // Result must exist
-                    assert!(!results.is_empty(), "search for '{query}' should find results");
+                    assert!(
+                        !results.is_empty(),
+                        "search for '{query}' should find results"
+                    );
                 }
             }));
         }
@@ -375,15 +403,35 @@ mod tests {
         reg.register_backend_tools(
             "bk_a",
             vec![
-                make_entry_tagged("tool_a1", "Group A tool 1", "bk_a", vec!["group_a".into(), "read".into()]),
-                make_entry_tagged("tool_a2", "Group A tool 2", "bk_a", vec!["group_a".into(), "write".into()]),
+                make_entry_tagged(
+                    "tool_a1",
+                    "Group A tool 1",
+                    "bk_a",
+                    vec!["group_a".into(), "read".into()],
+                ),
+                make_entry_tagged(
+                    "tool_a2",
+                    "Group A tool 2",
+                    "bk_a",
+                    vec!["group_a".into(), "write".into()],
+                ),
             ],
         );
         reg.register_backend_tools(
             "bk_b",
             vec![
-                make_entry_tagged("tool_b1", "Group B tool 1", "bk_b", vec!["group_b".into(), "read".into()]),
-                make_entry_tagged("tool_b2", "Group B tool 2", "bk_b", vec!["group_b".into(), "write".into()]),
+                make_entry_tagged(
+                    "tool_b1",
+                    "Group B tool 1",
+                    "bk_b",
+                    vec!["group_b".into(), "read".into()],
+                ),
+                make_entry_tagged(
+                    "tool_b2",
+                    "Group B tool 2",
+                    "bk_b",
+                    vec!["group_b".into(), "write".into()],
+                ),
             ],
         );
 
@@ -403,7 +451,10 @@ mod tests {
             handles.push(tokio::spawn(async move {
                 for _ in 0..5 {
                     let results = reg.search("tool", 10, Some(&filter), None);
-                    assert!(!results.is_empty(), "tag-filtered search should find results");
+                    assert!(
+                        !results.is_empty(),
+                        "tag-filtered search should find results"
+                    );
                 }
             }));
         }
@@ -443,7 +494,8 @@ mod tests {
             let mgr = Arc::clone(&manager);
             handles.push(tokio::spawn(async move {
                 let args = serde_json::json!({"id": i});
-                mgr.call_tool("timeout-test", "slow_tool", Some(args), None).await
+                mgr.call_tool("timeout-test", "slow_tool", Some(args), None)
+                    .await
             }));
         }
 
@@ -480,7 +532,12 @@ mod tests {
         mock.set_state(BackendState::Unhealthy);
 
         let result = manager
-            .call_tool("unhealthy-test", "echo_tool", Some(serde_json::json!({"x": 1})), None)
+            .call_tool(
+                "unhealthy-test",
+                "echo_tool",
+                Some(serde_json::json!({"x": 1})),
+                None,
+            )
             .await;
 
         assert!(result.is_err(), "unhealthy backend should reject calls");
@@ -493,7 +550,12 @@ mod tests {
         // Restore health and verify calls work again
         mock.set_state(BackendState::Healthy);
         let result = manager
-            .call_tool("unhealthy-test", "echo_tool", Some(serde_json::json!({"x": 1})), None)
+            .call_tool(
+                "unhealthy-test",
+                "echo_tool",
+                Some(serde_json::json!({"x": 1})),
+                None,
+            )
             .await;
         assert!(result.is_ok(), "healthy backend should accept calls");
     }
@@ -570,7 +632,6 @@ mod tests {
         )
         .await;
 
-        let start = std::time::Instant::now();
         let result = manager
             .call_tool("slow-test", "slow_tool", None, None)
             .await;
@@ -595,20 +656,36 @@ mod tests {
 
         // First call: consumes the only permit via forget()
         let result1 = manager
-            .call_tool("rate-test", "echo_tool", Some(serde_json::json!({"n": 1})), None)
+            .call_tool(
+                "rate-test",
+                "echo_tool",
+                Some(serde_json::json!({"n": 1})),
+                None,
+            )
             .await;
-        assert!(result1.is_ok(), "first call should succeed (permit available)");
+        assert!(
+            result1.is_ok(),
+            "first call should succeed (permit available)"
+        );
 
         // Second call: no permits left
         let result2 = manager
-            .call_tool("rate-test", "echo_tool", Some(serde_json::json!({"n": 2})), None)
+            .call_tool(
+                "rate-test",
+                "echo_tool",
+                Some(serde_json::json!({"n": 2})),
+                None,
+            )
             .await;
 
-        assert!(result2.is_err(), "second call should fail (rate limit exhausted)");
+        assert!(
+            result2.is_err(),
+            "second call should fail (rate limit exhausted)"
+        );
         let err_msg = result2.unwrap_err().to_string();
         assert!(
             err_msg.contains("rate limit"),
             "error should mention rate limit, got: {err_msg}"
         );
     }
-}
\ No newline at end of file
+}
@@ -148,6 +148,22 @@ impl EmbeddingIndex {
     }
 }
 
+impl crate::tools::intent::Similarity for EmbeddingIndex {
+    /// Embeds `texts` on the fly; they are not added to the index.
+    fn similarities(&self, query: &str, texts: &[&str]) -> Vec<f32> {
+        let query_vec = self.embed_text(query);
+        let texts: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+        self.model
+            .encode(&texts)
+            .into_iter()
+            .map(|mut vec| {
+                l2_normalize(&mut vec);
+                dot_product(&query_vec, &vec)
+            })
+            .collect()
+    }
+}
+
 /// L2-normalize a vector in-place.
 fn l2_normalize(vec: &mut [f32]) {
     let norm: f32 = vec.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -0,0 +1,338 @@
+//! Typed errors for tool dispatch through the gateway.
+//!
+//! Backends, `BackendManager::call_tool` and the sandbox return these inside
+//! `anyhow::Error`. Callers that need to make a decision (fallback, on-demand
+//! restart, tracker stats, hints shown to the LLM) look the variant up with
+//! [`GatewayError::find`] instead of matching on message text, so tool output
+//! that happens to contain words like "timeout" can't trigger a fallback.
+
+use std::time::Duration;
+
+use rmcp::service::ServiceError;
+use serde_json::Value;
+
+use crate::backend::BackendState;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GatewayError {
+    /// The backend's token-bucket rate limiter had no tokens left.
+    #[error("backend '{backend}' rate limit exceeded. Try again later.")]
+    RateLimited { backend: String },
+
+    /// No per-backend concurrency permit became free in time.
+    #[error(
+        "backend '{backend}' is at max concurrent calls. \
+         Timed out after {waited:?} waiting for a permit. \
+         Consider increasing max_concurrent_calls for this backend."
+    )]
+    SemaphoreTimeout { backend: String, waited: Duration },
+
+    /// Every dedicated instance in the backend's pool stayed taken.
+    #[error("pool exhausted for backend '{backend}' (max: {max}, timeout: {waited:?})")]
+    PoolExhausted {
+        backend: String,
+        max: u32,
+        waited: Duration,
+    },
+
+    /// The backend's call semaphore was closed because it is being removed.
+    #[error("backend '{backend}' semaphore closed (backend shutting down)")]
+    ShuttingDown { backend: String },
+
+    /// The backend exists but is not in a state that accepts calls.
+    #[error(
+        "backend '{backend}' is not available (state: {state:?}). \
+         Check status: @gatemini://backend/{backend}"
+    )]
+    BackendUnavailable {
+        backend: String,
+        state: BackendState,
+    },
+
+    /// The backend stayed in `Starting` for every retry.
+    #[error(
+        "backend '{backend}' is still starting (retried {retries} times). \
+         Tool '{tool}' is cached but the backend hasn't connected yet. \
+         Check status: @gatemini://backend/{backend}"
+    )]
+    BackendStarting {
+        backend: String,
+        tool: String,
+        retries: u32,
+    },
+
+    /// The backend never appeared in the manager.
+    #[error(
+        "backend '{backend}' not found after {retries} retries. \
+         It may not be configured or failed to start. \
+         See all backends: @gatemini://backends"
+    )]
+    BackendNotFound { backend: String, retries: u32 },
+
+    /// The backend has no live MCP connection (never started or already stopped).
+    #[error("backend '{backend}' not started")]
+    NotStarted { backend: String },
+
+    /// The backend has no tool with this name.
+    #[error("tool '{tool}' not found in backend '{backend}'")]
+    ToolNotFound { backend: String, tool: String },
+
+    /// The call did not complete within the backend's configured timeout.
+    #[error("tool call '{tool}' on backend '{backend}' timed out after {timeout:?}")]
+    Timeout {
+        backend: String,
+        tool: String,
+        timeout: Duration,
+    },
+
+    /// The request never got an answer because the connection failed
+    /// (closed pipe, HTTP failure, send error, process spawn failure).
+    #[error("tool call '{tool}' on backend '{backend}' failed: {message}")]
+    Transport {
+        backend: String,
+        tool: String,
+        message: String,
+    },
+
+    /// The backend answered with a JSON-RPC error (e.g. invalid params).
+    /// The backend is up; the request itself was refused.
+    #[error("backend '{backend}' rejected '{tool}': {message} (code {code})")]
+    Rejected {
+        backend: String,
+        tool: String,
+        code: i32,
+        message: String,
+    },
+
+    /// The call reached the tool and the tool reported a failure
+    /// (`isError: true`, or a non-zero exit for cli-adapter tools).
+    #[error("tool '{tool}' on backend '{backend}' reported an error: {message}")]
+    ToolReported {
+        backend: String,
+        tool: String,
+        message: String,
+        /// `structuredContent` of the error result, if the backend sent one.
+        structured: Option<Value>,
+    },
+
+    /// The arguments do not fit the tool's input schema.
+    #[error("invalid arguments for tool '{tool}' on backend '{backend}': {message}")]
+    InvalidArguments {
+        backend: String,
+        tool: String,
+        message: String,
+    },
+
+    /// All V8 sandbox slots stayed busy for the whole call timeout.
+    #[error(
+        "sandbox concurrency limit reached. All V8 isolates are busy. \
+         Try again shortly or increase max_concurrent_sandboxes in config."
+    )]
+    SandboxBusy,
+
+    /// The sandboxed script failed (syntax error, thrown exception, timeout).
+    #[error("{message}")]
+    Sandbox { message: String },
+
+    /// The client cancelled the request or disconnected while it ran.
+    #[error("request cancelled by the client")]
+    Cancelled,
+}
+
+impl GatewayError {
+    /// Classify a failed rmcp request to `backend`. Only connection failures
+    /// become [`Transport`](Self::Transport); a JSON-RPC error reply is
+    /// [`Rejected`](Self::Rejected).
+    pub fn from_service(backend: &str, tool: &str, err: ServiceError) -> Self {
+        match err {
+            ServiceError::McpError(e) => Self::Rejected {
+                backend: backend.to_string(),
+                tool: tool.to_string(),
+                code: e.code.0,
+                message: e.message.into_owned(),
+            },
+            ServiceError::Timeout { timeout } => Self::Timeout {
+                backend: backend.to_string(),
+                tool: tool.to_string(),
+                timeout,
+            },
+            ServiceError::Cancelled { .. } => Self::Cancelled,
+            other => Self::Transport {
+                backend: backend.to_string(),
+                tool: tool.to_string(),
+                message: other.to_string(),
+            },
+        }
+    }
+
+    /// Find the `GatewayError` inside an `anyhow::Error`, looking through
+    /// any context layers added on the way up.
+    pub fn find(err: &anyhow::Error) -> Option<&GatewayError> {
+        err.chain().find_map(|e| e.downcast_ref::<GatewayError>())
+    }
+
+    /// Stable label used for tracker stats and logs.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::RateLimited { .. } => "rate_limited",
+            Self::SemaphoreTimeout { .. } => "semaphore_timeout",
+            Self::PoolExhausted { .. } => "pool_exhausted",
+            Self::ShuttingDown { .. } => "shutting_down",
+            Self::BackendUnavailable { .. } => "backend_unavailable",
+            Self::BackendStarting { .. } => "backend_starting",
+            Self::BackendNotFound { .. } => "backend_not_found",
+            Self::NotStarted { .. } => "not_started",
+            Self::ToolNotFound { .. } => "tool_not_found",
+            Self::Timeout { .. } => "timeout",
+            Self::Transport { .. } => "transport",
+            Self::Rejected { .. } => "rejected",
+            Self::ToolReported { .. } => "tool_error",
+            Self::InvalidArguments { .. } => "invalid_arguments",
+            Self::SandboxBusy => "sandbox_busy",
+            Self::Sandbox { .. } => "sandbox",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether the failure is about capacity or connectivity rather than the
+    /// request itself, so the same call may succeed on another backend.
+    /// Only these errors follow a backend's `fallback_chain`.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::RateLimited { .. }
+                | Self::SemaphoreTimeout { .. }
+                | Self::PoolExhausted { .. }
+                | Self::Timeout { .. }
+                | Self::Transport { .. }
+        )
+    }
+
+    /// Whether the backend could not serve the call at all because it is
+    /// stopped, starting, missing or disconnected (as opposed to the tool failing).
+    pub fn is_backend_unavailable(&self) -> bool {
+        matches!(
+            self,
+            Self::BackendUnavailable { .. }
+                | Self::BackendStarting { .. }
+                | Self::BackendNotFound { .. }
+                | Self::NotStarted { .. }
+                | Self::ShuttingDown { .. }
+                | Self::Transport { .. }
+        )
+    }
+
+    /// Whether the backend is stopped and an on-demand restart may help.
+    pub fn is_stopped(&self) -> bool {
+        matches!(
+            self,
+            Self::BackendUnavailable {
+                state: BackendState::Stopped,
+                ..
+            }
+        )
+    }
+}
+
+/// Label for any error: the `GatewayError` kind, or `"other"`.
+pub fn error_kind(err: &anyhow::Error) -> &'static str {
+    GatewayError::find(err).map_or("other", GatewayError::kind)
+}
+
+/// Whether an error should follow the backend's fallback chain.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    GatewayError::find(err).is_some_and(GatewayError::is_transient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    fn timeout() -> GatewayError {
+        GatewayError::Timeout {
+            backend: "exa".to_string(),
+            tool: "search".to_string(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+
+    #[test]
+    fn test_transient_classification() {
+        assert!(is_transient(&timeout().into()));
+        assert!(is_transient(
+            &GatewayError::RateLimited {
+                backend: "exa".to_string()
+            }
+            .into()
+        ));
+        assert!(
+            !is_transient(
+                &GatewayError::ToolReported {
+                    backend: "exa".to_string(),
+                    tool: "search".to_string(),
+                    message: "upstream timeout, rate limit exceeded".to_string(),
+                    structured: None,
+                }
+                .into()
+            ),
+            "tool-reported errors never fall back, whatever their text says"
+        );
+        assert!(
+            !is_transient(&anyhow::anyhow!("connection refused")),
+            "untyped errors are not classified by message"
+        );
+    }
+
+    #[test]
+    fn test_find_through_context() {
+        let err = anyhow::Error::from(timeout()).context("tool 'search' on backend 'exa'");
+        assert_eq!(error_kind(&err), "timeout");
+
+        let res: anyhow::Result<()> = Err(timeout().into());
+        let err = res.context("outer").context("outermost").unwrap_err();
+        assert!(matches!(
+            GatewayError::find(&err),
+            Some(GatewayError::Timeout { .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_reply_is_not_an_outage() {
+        let reply = GatewayError::from_service(
+            "exa",
+            "search",
+            ServiceError::McpError(rmcp::ErrorData::invalid_params("missing 'query'", None)),
+        );
+        assert_eq!(reply.kind(), "rejected");
+        assert!(!reply.is_transient());
+        assert!(!reply.is_backend_unavailable());
+
+        let closed = GatewayError::from_service("exa", "search", ServiceError::TransportClosed);
+        assert_eq!(closed.kind(), "transport");
+        assert!(closed.is_transient());
+        assert!(closed.is_backend_unavailable());
+    }
+
+    #[test]
+    fn test_stopped_and_unavailable() {
+        let stopped = GatewayError::BackendUnavailable {
+            backend: "b".to_string(),
+            state: BackendState::Stopped,
+        };
+        assert!(stopped.is_stopped());
+        assert!(stopped.is_backend_unavailable());
+        assert!(
+            stopped
+                .to_string()
+                .contains("not available (state: Stopped)")
+        );
+
+        let unhealthy = GatewayError::BackendUnavailable {
+            backend: "b".to_string(),
+            state: BackendState::Unhealthy,
+        };
+        assert!(!unhealthy.is_stopped());
+        assert_eq!(error_kind(&anyhow::anyhow!("plain")), "other");
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::Result;
 #[cfg(unix)]
@@ -146,12 +146,17 @@ pub async fn run(gw: InitializedGateway, bound: BoundSocket) -> Result<()> {
         gw.config.sandbox.max_concurrent_sandboxes as usize,
     ));
     let output_config = gw.config.sandbox.output.clone();
-    let session_id_gen = Arc::new(AtomicU64::new(1));
     let shutdown_notify = Arc::clone(&gw.shutdown_notify);
 
     // Track active client tasks for graceful shutdown.
     let client_tracker = tokio_util::task::TaskTracker::new();
 
+    // `call_tool_chain` tasks outlive the session that started them, so a
+    // reconnecting client can still poll them.
+    let tasks = crate::tasks::TaskStore::new();
+    // Likewise for full results kept when an output was cut down.
+    let results = crate::results::ResultStore::from_config(&output_config);
+
     // Session counter for idle shutdown.
     let active_sessions = Arc::new(AtomicUsize::new(0));
     // Notifies the accept loop when a client disconnects so the idle timer can be re-armed.
@@ -188,7 +193,7 @@ pub async fn run(gw: InitializedGateway, bound: BoundSocket) -> Result<()> {
                         Ok((stream, _addr)) => {
                             let sessions = Arc::clone(&active_sessions);
                             sessions.fetch_add(1, Ordering::SeqCst);
-                            let session_id = session_id_gen.fetch_add(1, Ordering::Relaxed);
+                            let session_id = backend_manager.new_session_id();
                             info!(active = sessions.load(Ordering::SeqCst), session = session_id, "client connected");
 
                             let server = GateminiServer::new(
@@ -199,6 +204,8 @@ pub async fn run(gw: InitializedGateway, bound: BoundSocket) -> Result<()> {
                                 allow_runtime_registration,
                                 max_dynamic_backends,
                                 Arc::clone(&sandbox_semaphore),
+                                Arc::clone(&tasks),
+                                Arc::clone(&results),
                                 Some(session_id),
                                 output_config.clone(),
                             );
@@ -245,7 +252,8 @@ pub async fn run(gw: InitializedGateway, bound: BoundSocket) -> Result<()> {
                     repair_requested = true;
                 }
                 () = &mut idle_sleep, if idle_enabled
-                                       && active_sessions.load(Ordering::SeqCst) == 0 => {
+                                       && active_sessions.load(Ordering::SeqCst) == 0
+                                       && tasks.running() == 0 => {
                     info!(
                         timeout = ?idle_timeout,
                         "idle timeout reached with no active clients, shutting down"
@@ -8,8 +8,11 @@ mod backend;
 mod cache;
 mod cli;
 mod config;
+#[cfg(test)]
+mod edge_case_tests;
 #[cfg(feature = "semantic")]
 mod embeddings;
+mod error;
 #[cfg(test)]
 mod integration_inventory;
 mod ipc;
@@ -19,11 +22,15 @@ mod oauth;
 mod prompts;
 mod registry;
 mod resources;
+mod results;
 mod sandbox;
+mod schema;
 mod secrets;
 mod server;
+mod tasks;
 #[cfg(test)]
 mod testutil;
+mod tokens;
 mod tools;
 mod trace_context;
 mod tracker;
@@ -164,6 +171,19 @@ pub async fn initialize(config_path: &Path) -> Result<InitializedGateway> {
         registry.set_aliases(config.aliases.clone());
     }
 
+    // Tools exposed directly in tools/list, next to the meta-tools
+    let pinned_tools = config.pinned_tool_names();
+    if !pinned_tools.is_empty() {
+        info!(count = pinned_tools.len(), "loading pinned tools");
+        registry.set_pinned_tools(pinned_tools);
+    }
+
+    // Named groups of tools that sessions can expose with enable_toolset
+    if !config.toolsets.is_empty() {
+        info!(count = config.toolsets.len(), "loading toolsets");
+        registry.set_toolsets(config.toolsets.clone());
+    }
+
     // Register composite tools (virtual backend — no child process)
     if !config.composite_tools.is_empty() {
         use backend::Backend as _;
@@ -218,6 +238,17 @@ pub async fn initialize(config_path: &Path) -> Result<InitializedGateway> {
         });
     }
 
+    // Re-discover tools when a backend reports tools/list_changed
+    if let Some(events) = backend_manager.take_event_receiver() {
+        let manager = Arc::clone(&backend_manager);
+        let reg = Arc::clone(&registry);
+        let shutdown = Arc::clone(&shutdown_notify);
+        let cp = cache_path.clone();
+        tokio::spawn(async move {
+            backend::events::run_event_listener(manager, reg, events, shutdown, cp).await;
+        });
+    }
+
     // Start config file watcher in background
     {
         let config_path = config_path.to_path_buf();
@@ -271,6 +302,8 @@ async fn run_direct(gw: InitializedGateway) -> Result<()> {
         gw.config.allow_runtime_registration,
         gw.config.max_dynamic_backends,
         sandbox_semaphore,
+        tasks::TaskStore::new(),
+        results::ResultStore::from_config(&gw.config.sandbox.output),
         Some(0), // direct mode uses session_id 0
         gw.config.sandbox.output.clone(),
     );
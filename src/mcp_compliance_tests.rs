@@ -38,6 +38,8 @@ mod tests {
             true,
             10,
             Arc::new(Semaphore::new(8)),
+            crate::tasks::TaskStore::new(),
+            crate::results::ResultStore::from_config(&Default::default()),
             None,
             Default::default(),
         );
@@ -88,6 +90,8 @@ mod tests {
             true,
             10,
             Arc::new(Semaphore::new(8)),
+            crate::tasks::TaskStore::new(),
+            crate::results::ResultStore::from_config(&Default::default()),
             None,
             Default::default(),
         );
@@ -121,23 +125,34 @@ mod tests {
         assert!(caps.tools.is_some(), "tools capability missing");
         assert!(caps.resources.is_some(), "resources capability missing");
         assert!(caps.prompts.is_some(), "prompts capability missing");
+        assert!(caps.tasks.is_some(), "tasks capability missing");
     }
 
     #[tokio::test]
-    async fn test_tools_list_returns_7_meta_tools() {
+    async fn test_tools_list_returns_12_meta_tools() {
         let (peer, _, _) = setup_mcp_client().await;
         let tools = peer.list_all_tools().await.unwrap();
 
-        assert_eq!(tools.len(), 7, "expected 7 meta-tools, got {}", tools.len());
+        assert_eq!(
+            tools.len(),
+            12,
+            "expected 12 meta-tools, got {}",
+            tools.len()
+        );
 
         let names: Vec<String> = tools.iter().map(|t| t.name.to_string()).collect();
         assert!(names.contains(&"search_tools".to_string()));
         assert!(names.contains(&"list_tools_meta".to_string()));
         assert!(names.contains(&"tool_info".to_string()));
+        assert!(names.contains(&"tool_types".to_string()));
         assert!(names.contains(&"call_tool_chain".to_string()));
         assert!(names.contains(&"register_manual".to_string()));
         assert!(names.contains(&"deregister_manual".to_string()));
         assert!(names.contains(&"get_required_keys_for_tool".to_string()));
+        assert!(names.contains(&"call_tools_batch".to_string()));
+        assert!(names.contains(&"read_result".to_string()));
+        assert!(names.contains(&"enable_toolset".to_string()));
+        assert!(names.contains(&"disable_toolset".to_string()));
     }
 
     #[tokio::test]
@@ -196,6 +211,199 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_only_call_tool_chain_supports_tasks() {
+        let (peer, _, _) = setup_mcp_client().await;
+        let tools = peer.list_all_tools().await.unwrap();
+
+        for tool in &tools {
+            let expected = if tool.name == "call_tool_chain" {
+                TaskSupport::Optional
+            } else {
+                TaskSupport::Forbidden
+            };
+            assert_eq!(tool.task_support(), expected, "tool '{}'", tool.name);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pinned_tool_listed_and_called_directly() {
+        let (peer, mock, registry) = setup_mcp_client().await;
+        registry.set_pinned_tools(vec!["test-backend.echo_tool".to_string()]);
+
+        let tools = peer.list_all_tools().await.unwrap();
+        assert_eq!(tools.len(), 13, "12 meta-tools + 1 pinned tool");
+        let pinned = tools
+            .iter()
+            .find(|tool| tool.name == "test-backend.echo_tool")
+            .expect("pinned tool should be listed");
+        assert_eq!(pinned.description.as_deref(), Some("Returns args as JSON"));
+
+        let result = peer
+            .call_tool(
+                CallToolRequestParams::new("test-backend.echo_tool").with_arguments(
+                    serde_json::json!({"msg": "hi"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+        assert!(extract_text(&result).contains("hi"));
+
+        let log = mock.call_log().await;
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].0, "echo_tool");
+    }
+
+    #[tokio::test]
+    async fn test_enabled_toolset_listed_for_session() {
+        let (peer, mock, registry) = setup_mcp_client().await;
+        let mut toolsets = std::collections::HashMap::new();
+        toolsets.insert(
+            "testing".to_string(),
+            crate::config::ToolsetConfig {
+                backends: vec!["test-backend".into()],
+                ..Default::default()
+            },
+        );
+        registry.set_toolsets(toolsets);
+        let toolset_call = |tool: &str, name: &str| {
+            CallToolRequestParams::new(tool.to_string()).with_arguments(
+                serde_json::json!({ "name": name })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            )
+        };
+
+        let result = peer
+            .call_tool(toolset_call("enable_toolset", "nope"))
+            .await
+            .unwrap();
+        assert!(result.is_error.unwrap_or(false));
+        assert!(extract_text(&result).contains("testing"));
+
+        let result = peer
+            .call_tool(toolset_call("enable_toolset", "testing"))
+            .await
+            .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+        assert!(extract_text(&result).contains("test-backend.echo_tool"));
+
+        let tools = peer.list_all_tools().await.unwrap();
+        assert!(tools.len() > 12, "toolset tools should be listed");
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool.name == "test-backend.echo_tool")
+        );
+
+        let result = peer
+            .call_tool(
+                CallToolRequestParams::new("test-backend.echo_tool").with_arguments(
+                    serde_json::json!({"msg": "hi"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+        assert!(extract_text(&result).contains("hi"));
+        assert_eq!(mock.call_log().await.len(), 1);
+
+        peer.call_tool(toolset_call("disable_toolset", "testing"))
+            .await
+            .unwrap();
+        assert_eq!(peer.list_all_tools().await.unwrap().len(), 12);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_output_readable_by_handle() {
+        let (peer, _, _) = setup_mcp_client().await;
+        let code = serde_json::json!({
+            "tool": "test-backend.echo_tool",
+            "arguments": {"data": "x".repeat(5000), "tail": "kept"}
+        })
+        .to_string();
+        let result = peer
+            .call_tool(
+                CallToolRequestParams::new("call_tool_chain").with_arguments(
+                    serde_json::json!({"code": code, "max_output_size": 1000})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+        let text = extract_text(&result);
+        let handle = text
+            .split("kept as '")
+            .nth(1)
+            .and_then(|rest| rest.split('\'').next())
+            .expect("truncated output should carry a handle")
+            .to_string();
+
+        let result = peer
+            .call_tool(
+                CallToolRequestParams::new("read_result").with_arguments(
+                    serde_json::json!({"handle": handle, "pointer": "/tail"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+        assert!(extract_text(&result).ends_with("\"kept\""));
+
+        let resource = peer
+            .read_resource(ReadResourceRequestParams::new(format!(
+                "gatemini://result/{handle}?bytes=0-99"
+            )))
+            .await
+            .unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &resource.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert!(text.contains("next: offset=100"));
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_chain_select_projects_result() {
+        let (peer, _, _) = setup_mcp_client().await;
+        let code = serde_json::json!({
+            "tool": "test-backend.echo_tool",
+            "arguments": {"items": [{"id": 1, "name": "a", "blob": "x"}, {"id": 2, "name": "b"}]}
+        })
+        .to_string();
+        let call = |select: &str| {
+            CallToolRequestParams::new("call_tool_chain").with_arguments(
+                serde_json::json!({"code": code, "select": select})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            )
+        };
+
+        let result = peer.call_tool(call(".items[] | {id, name}")).await.unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+        let projected: serde_json::Value = serde_json::from_str(&extract_text(&result)).unwrap();
+        assert_eq!(
+            projected,
+            serde_json::json!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}])
+        );
+
+        let result = peer.call_tool(call("$.missing")).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert!(extract_text(&result).contains("matched nothing"));
+    }
+
     #[tokio::test]
     async fn test_tools_call_search_success() {
         let (peer, _, _) = setup_mcp_client().await;
@@ -334,6 +542,39 @@ mod tests {
         assert!(names.contains(&"call_tool_chain".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_tool_types_and_types_resource() {
+        let (peer, _, _) = setup_mcp_client().await;
+        let call = |arguments: serde_json::Value| {
+            CallToolRequestParams::new("tool_types")
+                .with_arguments(arguments.as_object().unwrap().clone())
+        };
+
+        let result = peer
+            .call_tool(call(serde_json::json!({"backend": "test-backend"})))
+            .await
+            .unwrap();
+        assert!(!result.is_error.unwrap_or(false));
+        let declarations = extract_text(&result);
+        assert!(declarations.contains("declare namespace test_backend {"));
+        assert!(declarations.contains("function echo_tool(args?: EchoToolArgs): Promise<any>;"));
+
+        let result = peer.call_tool(call(serde_json::json!({}))).await.unwrap();
+        assert!(result.is_error.unwrap_or(false));
+        assert!(extract_text(&result).contains("test-backend"));
+
+        let resource = peer
+            .read_resource(ReadResourceRequestParams::new(
+                "gatemini://types/test-backend",
+            ))
+            .await
+            .unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &resource.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(text, &declarations);
+    }
+
     #[tokio::test]
     async fn test_resource_templates_list() {
         let (peer, _, _) = setup_mcp_client().await;
@@ -357,6 +598,10 @@ mod tests {
             uris.iter().any(|u| u.contains("guide/")),
             "should have guide/{{topic}} template"
         );
+        assert!(
+            uris.iter().any(|u| u.contains("types/")),
+            "should have types/{{backend}} template"
+        );
     }
 
     #[tokio::test]
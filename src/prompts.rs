@@ -5,12 +5,13 @@ use std::sync::Arc;
 use rmcp::{ErrorData as McpError, model::*};
 
 use crate::backend::BackendManager;
-use crate::registry::ToolRegistry;
+use crate::registry::{PromptEntry, ToolRegistry};
 use crate::tracker::CallTracker;
 
-/// Return the list of available prompts.
-pub fn list_prompts() -> Vec<Prompt> {
-    vec![
+/// Return the list of available prompts: gatemini's own, then backend
+/// prompts under their namespaced names.
+pub fn list_prompts(registry: &ToolRegistry) -> Vec<Prompt> {
+    let mut prompts = vec![
         Prompt::new(
             "discover",
             Some("Guided workflow for discovering gatemini tools progressively"),
@@ -34,10 +35,36 @@ pub fn list_prompts() -> Vec<Prompt> {
             None,
         )
         .with_title("Backend Status"),
-    ]
+    ];
+    prompts.extend(registry.get_all_prompts().iter().map(backend_prompt));
+    prompts
 }
 
-/// Handle get_prompt for gatemini prompts.
+fn backend_prompt(entry: &PromptEntry) -> Prompt {
+    let arguments = (!entry.arguments.is_empty()).then(|| {
+        entry
+            .arguments
+            .iter()
+            .map(|a| {
+                let mut argument = PromptArgument::new(&a.name).with_required(a.required);
+                if let Some(ref title) = a.title {
+                    argument = argument.with_title(title);
+                }
+                if let Some(ref description) = a.description {
+                    argument = argument.with_description(description);
+                }
+                argument
+            })
+            .collect()
+    });
+    let prompt = Prompt::new(&entry.name, entry.description.as_deref(), arguments);
+    match entry.title {
+        Some(ref title) => prompt.with_title(title),
+        None => prompt,
+    }
+}
+
+/// Handle get_prompt for gatemini prompts, forwarding backend prompts to their backend.
 pub async fn get_prompt(
     name: &str,
     arguments: Option<JsonObject>,
@@ -58,10 +85,15 @@ pub async fn get_prompt(
             Ok(find_tool_prompt(task, registry))
         }
         "backend_status" => Ok(backend_status_prompt(registry, backend_manager, tracker)),
-        _ => Err(McpError::invalid_params(
-            format!("Unknown prompt: {name}"),
-            None,
-        )),
+        _ => {
+            let entry = registry
+                .get_prompt(name)
+                .ok_or_else(|| McpError::invalid_params(format!("Unknown prompt: {name}"), None))?;
+            backend_manager
+                .get_backend_prompt(&entry, arguments)
+                .await
+                .map_err(|e| McpError::internal_error(format!("{e:#}"), None))
+        }
     }
 }
 
@@ -142,6 +174,7 @@ fn find_tool_prompt(task: &str, registry: &ToolRegistry) -> GetPromptResult {
                 "\n## Top Match: `{}`\n\n\
                  **Backend:** {}\n\
                  **Description:** {}\n\n\
+                 **Parameters:** `{}`\n\n\
                  **Input Schema:**\n```json\n{}\n```\n\n\
                  **Execute with:**\n```typescript\n\
                  const result = await {}.{}({{ /* params */ }});\n\
@@ -152,7 +185,8 @@ fn find_tool_prompt(task: &str, registry: &ToolRegistry) -> GetPromptResult {
                 top.name,
                 top.backend_name,
                 top.description,
-                serde_json::to_string_pretty(&top.input_schema).unwrap_or_default(),
+                top.signature(),
+                serde_json::to_string_pretty(&top.normalized_input_schema()).unwrap_or_default(),
                 top.backend_name.replace('-', "_"),
                 top.original_name.replace('-', "_"),
             ));
@@ -176,8 +210,8 @@ fn backend_status_prompt(
     let mut text = format!(
         "# Gatemini Backend Status\n\n\
          **Total:** {} backends, {} tools\n\n\
-         | Backend | Status | Available | Tools | p50 (ms) | p95 (ms) | Calls |\n\
-         |---------|--------|-----------|-------|----------|----------|-------|\n",
+         | Backend | Status | Available | Tools | p50 (ms) | p95 (ms) | Calls | Errors |\n\
+         |---------|--------|-----------|-------|----------|----------|-------|--------|\n",
         statuses.len(),
         registry.tool_count(),
     );
@@ -185,18 +219,24 @@ fn backend_status_prompt(
     for status in &statuses {
         let tool_count = registry.get_by_backend(&status.name).len();
         let available = if status.available { "Yes" } else { "No" };
-        let (p50, p95, calls) = if let Some(stats) = tracker.latency_stats(&status.name) {
+        let (p50, p95, calls, errors) = if let Some(stats) = tracker.latency_stats(&status.name) {
             (
                 format!("{:.1}", stats.p50_ms),
                 format!("{:.1}", stats.p95_ms),
                 format!("{}", stats.sample_count),
+                format!("{}", stats.error_count),
             )
         } else {
-            ("-".to_string(), "-".to_string(), "0".to_string())
+            (
+                "-".to_string(),
+                "-".to_string(),
+                "0".to_string(),
+                "0".to_string(),
+            )
         };
         text.push_str(&format!(
-            "| {} | {:?} | {} | {} | {} | {} | {} |\n",
-            status.name, status.state, available, tool_count, p50, p95, calls
+            "| {} | {:?} | {} | {} | {} | {} | {} | {} |\n",
+            status.name, status.state, available, tool_count, p50, p95, calls, errors
         ));
     }
 
@@ -1,14 +1,16 @@
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+use tokio::sync::broadcast;
 
+use crate::config::ToolsetConfig;
 #[cfg(feature = "semantic")]
 use crate::embeddings::EmbeddingIndex;
 
 /// A tool entry in the registry, linking a tool to its backend.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ToolEntry {
     /// The tool name as registered in the registry (may be namespaced: "github.get_repo").
     pub name: String,
@@ -25,6 +27,153 @@ pub struct ToolEntry {
     /// Tags for categorization and filtering (inherited from backend config).
     #[serde(default)]
     pub tags: Vec<String>,
+    /// JSON schema for the tool's `structuredContent`, when the backend declares one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    /// MCP tool annotations (behavior hints and display title) from the backend.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+}
+
+/// Behavior hints a backend declares for a tool (MCP `ToolAnnotations`).
+///
+/// Hints are advisory: they come from the backend and are not verified.
+/// Only explicitly set hints are reported; the MCP spec defaults
+/// (e.g. `destructiveHint: true`) are not assumed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_only_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destructive_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotent_hint: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_world_hint: Option<bool>,
+}
+
+impl ToolAnnotations {
+    /// Short labels for the hints that are explicitly set to true:
+    /// `read-only`, `destructive`, `idempotent`, `open-world`.
+    pub fn hint_labels(&self) -> Vec<&'static str> {
+        let read_only = self.read_only_hint == Some(true);
+        let mut labels = Vec::new();
+        if read_only {
+            labels.push("read-only");
+        }
+        // destructiveHint is only meaningful when the tool is not read-only.
+        if !read_only && self.destructive_hint == Some(true) {
+            labels.push("destructive");
+        }
+        if self.idempotent_hint == Some(true) {
+            labels.push("idempotent");
+        }
+        if self.open_world_hint == Some(true) {
+            labels.push("open-world");
+        }
+        labels
+    }
+}
+
+impl ToolEntry {
+    /// Annotation hint labels for this tool (empty when the backend sent none).
+    pub fn hints(&self) -> Vec<&'static str> {
+        self.annotations
+            .as_ref()
+            .map(ToolAnnotations::hint_labels)
+            .unwrap_or_default()
+    }
+
+    /// Whether the backend marked this tool read-only.
+    pub fn is_read_only(&self) -> bool {
+        self.annotations
+            .as_ref()
+            .is_some_and(|a| a.read_only_hint == Some(true))
+    }
+
+    /// Input schema as shown to models: local refs inlined, `allOf`
+    /// flattened and noise keywords dropped (see [`crate::schema::normalize`]).
+    pub fn normalized_input_schema(&self) -> Value {
+        crate::schema::normalize(&self.input_schema)
+    }
+
+    /// Output schema, normalized like [`Self::normalized_input_schema`].
+    pub fn normalized_output_schema(&self) -> Option<Value> {
+        self.output_schema.as_ref().map(crate::schema::normalize)
+    }
+
+    /// Compact TypeScript-like signature of the tool's parameters, e.g.
+    /// `{query: string, limit?: integer}`.
+    pub fn signature(&self) -> String {
+        crate::schema::signature(&self.normalized_input_schema())
+    }
+
+    /// Whether the tool matches any of `tags`. Annotation hint labels
+    /// (`read-only`, `destructive`, ...) match like config tags, so
+    /// `tag: "read-only"` filters to read-only tools.
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        self.tags.iter().any(|t| tags.contains(t))
+            || self.hints().iter().any(|h| tags.iter().any(|t| t == h))
+    }
+}
+
+/// A prompt published by a backend MCP server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptEntry {
+    /// The prompt name as listed by the gateway (namespaced: "github.review_pr").
+    pub name: String,
+    /// The prompt name on the backend ("review_pr"), used for `prompts/get`.
+    pub original_name: String,
+    /// The backend that owns this prompt.
+    pub backend_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<PromptArgumentEntry>,
+}
+
+/// One argument of a backend prompt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgumentEntry {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Backend tools exposed as native MCP tools: pinned tools and the tools of
+/// each toolset.
+#[derive(Default, PartialEq)]
+struct NativeTools {
+    pinned: Vec<ToolEntry>,
+    toolsets: BTreeMap<String, Vec<ToolEntry>>,
+}
+
+/// Capacity of the native tools change channel. A session that falls behind
+/// treats the gap as a change to everything.
+const NATIVE_CHANGE_CAPACITY: usize = 64;
+
+/// Which native tools changed: the pinned tools and/or some toolsets.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NativeToolsChange {
+    pub pinned: bool,
+    /// Toolsets whose tools changed, were added or were removed.
+    pub toolsets: BTreeSet<String>,
+}
+
+impl NativeToolsChange {
+    /// Whether a session with `enabled` toolsets sees a different tool list.
+    pub fn affects(&self, enabled: &BTreeSet<String>) -> bool {
+        self.pinned || !self.toolsets.is_disjoint(enabled)
+    }
 }
 
 /// Concurrent tool registry aggregating tools from all backends.
@@ -50,9 +199,19 @@ pub struct ToolRegistry {
     /// User-defined aliases: shortcut name -> target tool name.
     /// Resolved after direct lookup in get_by_name (one level, no chaining).
     aliases: DashMap<String, String>,
+    /// backend_name -> prompts published by that backend (namespaced names)
+    prompts: DashMap<String, Vec<PromptEntry>>,
+    /// Pinned tool names, exposed directly in `tools/list` (sorted).
+    pinned: RwLock<Vec<String>>,
+    /// Toolsets sessions can enable to expose their tools directly.
+    toolsets: RwLock<HashMap<String, ToolsetConfig>>,
+    /// Pinned and toolset tools as of the last change, to tell when they change.
+    native_tools: Mutex<NativeTools>,
+    /// Announces which pinned or toolset tools changed.
+    native_changes: broadcast::Sender<NativeToolsChange>,
     /// Optional semantic embedding index for hybrid search.
     #[cfg(feature = "semantic")]
-    embedding_index: Option<EmbeddingIndex>,
+    embedding_index: Option<Arc<EmbeddingIndex>>,
 }
 
 impl ToolRegistry {
@@ -62,6 +221,11 @@ impl ToolRegistry {
             backend_tools: DashMap::new(),
             bare_name_owners: DashMap::new(),
             aliases: DashMap::new(),
+            prompts: DashMap::new(),
+            pinned: RwLock::new(Vec::new()),
+            toolsets: RwLock::new(HashMap::new()),
+            native_tools: Mutex::new(NativeTools::default()),
+            native_changes: broadcast::channel(NATIVE_CHANGE_CAPACITY).0,
             #[cfg(feature = "semantic")]
             embedding_index: None,
         })
@@ -75,7 +239,12 @@ impl ToolRegistry {
             backend_tools: DashMap::new(),
             bare_name_owners: DashMap::new(),
             aliases: DashMap::new(),
-            embedding_index: Some(index),
+            prompts: DashMap::new(),
+            pinned: RwLock::new(Vec::new()),
+            toolsets: RwLock::new(HashMap::new()),
+            native_tools: Mutex::new(NativeTools::default()),
+            native_changes: broadcast::channel(NATIVE_CHANGE_CAPACITY).0,
+            embedding_index: Some(Arc::new(index)),
         })
     }
 
@@ -128,6 +297,12 @@ impl ToolRegistry {
                 for key in old_keys.value() {
                     self.tools.remove(key);
                 }
+                // Drop embeddings of tools the backend no longer lists;
+                // current tools are re-embedded below.
+                #[cfg(feature = "semantic")]
+                if let Some(ref index) = self.embedding_index {
+                    index.remove_tools(old_keys.value());
+                }
             }
         }
 
@@ -153,6 +328,8 @@ impl ToolRegistry {
                 backend_name: backend_name.to_string(),
                 input_schema: tool.input_schema.clone(),
                 tags: tool.tags.clone(),
+                output_schema: tool.output_schema.clone(),
+                annotations: tool.annotations.clone(),
             };
             self.tools.insert(ns_key.clone(), ns_entry.clone());
             registered_keys.push(ns_key);
@@ -185,6 +362,8 @@ impl ToolRegistry {
                     backend_name: backend_name.to_string(),
                     input_schema: tool.input_schema,
                     tags: tool.tags,
+                    output_schema: tool.output_schema,
+                    annotations: tool.annotations,
                 };
                 self.tools.insert(original.clone(), bare_entry);
                 registered_keys.push(original);
@@ -208,13 +387,15 @@ impl ToolRegistry {
 
         self.backend_tools
             .insert(backend_name.to_string(), registered_keys);
+        self.refresh_native_tools();
     }
 
-    /// Remove all tools belonging to a backend.
+    /// Remove all tools (and prompts) belonging to a backend.
     ///
     /// Also cleans up bare_name_owners and restores bare-name aliases if
     /// a collision resolves (goes from 2→1 owner).
     pub fn remove_backend_tools(&self, backend_name: &str) {
+        self.prompts.remove(backend_name);
         if let Some((_, tool_names)) = self.backend_tools.remove(backend_name) {
             #[cfg(feature = "semantic")]
             if let Some(ref index) = self.embedding_index {
@@ -256,6 +437,8 @@ impl ToolRegistry {
                         backend_name: remaining_backend,
                         input_schema: ns_entry.input_schema.clone(),
                         tags: ns_entry.tags.clone(),
+                        output_schema: ns_entry.output_schema.clone(),
+                        annotations: ns_entry.annotations.clone(),
                     })
                 };
 
@@ -266,6 +449,7 @@ impl ToolRegistry {
                 }
             }
         }
+        self.refresh_native_tools();
     }
 
     /// Get all tool entries.
@@ -301,6 +485,101 @@ impl ToolRegistry {
         for (alias, target) in aliases {
             self.aliases.insert(alias, target);
         }
+        self.refresh_native_tools();
+    }
+
+    /// Replace the pinned tool names. Names resolve like [`Self::get_by_name`].
+    pub fn set_pinned_tools(&self, mut names: Vec<String>) {
+        names.sort();
+        names.dedup();
+        *self.pinned.write().unwrap() = names;
+        self.refresh_native_tools();
+    }
+
+    /// Pinned tools that are registered, listed under their pinned name.
+    pub fn pinned_tools(&self) -> Vec<ToolEntry> {
+        self.pinned
+            .read()
+            .unwrap()
+            .iter()
+            .filter_map(|name| {
+                let mut entry = self.get_by_name(name)?;
+                entry.name = name.clone();
+                Some(entry)
+            })
+            .collect()
+    }
+
+    /// Replace the toolset definitions.
+    pub fn set_toolsets(&self, toolsets: HashMap<String, ToolsetConfig>) {
+        *self.toolsets.write().unwrap() = toolsets;
+        self.refresh_native_tools();
+    }
+
+    /// Toolset names and descriptions, sorted by name.
+    pub fn toolsets(&self) -> Vec<(String, Option<String>)> {
+        let mut toolsets: Vec<(String, Option<String>)> = self
+            .toolsets
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, toolset)| (name.clone(), toolset.description.clone()))
+            .collect();
+        toolsets.sort();
+        toolsets
+    }
+
+    /// Registered tools of a toolset under their namespaced names, sorted.
+    /// `None` if there is no such toolset.
+    pub fn toolset_tools(&self, name: &str) -> Option<Vec<ToolEntry>> {
+        let toolsets = self.toolsets.read().unwrap();
+        let toolset = toolsets.get(name)?;
+        let mut tools: Vec<ToolEntry> = self
+            .tools
+            .iter()
+            // Bare-name aliases would list the same tool twice.
+            .filter(|r| r.key() != &r.value().original_name && toolset.matches(r.value()))
+            .map(|r| r.value().clone())
+            .collect();
+        tools.sort_by(|a, b| a.name.cmp(&b.name));
+        Some(tools)
+    }
+
+    /// Subscribe to changes of the pinned or toolset tools (added, removed or
+    /// re-registered with a different schema).
+    pub fn subscribe_native_tools(&self) -> broadcast::Receiver<NativeToolsChange> {
+        self.native_changes.subscribe()
+    }
+
+    fn refresh_native_tools(&self) {
+        // Snapshot under the lock, so concurrent refreshes can't store an
+        // older snapshot over a newer one.
+        let mut previous = self.native_tools.lock().unwrap();
+        let names: Vec<String> = self.toolsets.read().unwrap().keys().cloned().collect();
+        let current = NativeTools {
+            pinned: self.pinned_tools(),
+            toolsets: names
+                .into_iter()
+                .filter_map(|name| Some((name.clone(), self.toolset_tools(&name)?)))
+                .collect(),
+        };
+        if *previous == current {
+            return;
+        }
+        let toolsets = previous
+            .toolsets
+            .keys()
+            .chain(current.toolsets.keys())
+            .filter(|name| previous.toolsets.get(*name) != current.toolsets.get(*name))
+            .cloned()
+            .collect();
+        let change = NativeToolsChange {
+            pinned: previous.pinned != current.pinned,
+            toolsets,
+        };
+        *previous = current;
+        // No receivers just means no session is connected.
+        let _ = self.native_changes.send(change);
     }
 
     /// Find a tool in a specific backend by its original_name.
@@ -318,7 +597,6 @@ impl ToolRegistry {
     }
 
     /// Get all tools for a specific backend.
-    #[allow(dead_code)]
     pub fn get_by_backend(&self, backend_name: &str) -> Vec<ToolEntry> {
         if let Some(names) = self.backend_tools.get(backend_name) {
             names
@@ -384,53 +662,14 @@ impl ToolRegistry {
         filter_tags: Option<&[String]>,
         tracker: Option<&crate::tracker::CallTracker>,
     ) -> Vec<ToolEntry> {
-        let corpus = self.build_corpus(filter_tags);
-        let n = corpus.len() as f64;
-        if n == 0.0 {
-            return Vec::new();
-        }
+        let (entries, docs): (Vec<ToolEntry>, Vec<Vec<String>>) =
+            self.build_corpus(filter_tags).into_iter().unzip();
+        let scores = bm25_scores(query_terms, &docs);
 
-        let avgdl: f64 = corpus.iter().map(|(_, t)| t.len() as f64).sum::<f64>() / n;
-
-        let mut df: HashMap<&str, f64> = HashMap::new();
-        for term in query_terms {
-            let count = corpus
-                .iter()
-                .filter(|(_, tokens)| tokens.iter().any(|t| t == term))
-                .count();
-            df.insert(term.as_str(), count as f64);
-        }
-
-        const K1: f64 = 1.2;
-        const B: f64 = 0.75;
-
-        let mut scored: Vec<(ToolEntry, f64)> = corpus
+        let mut scored: Vec<(ToolEntry, f64)> = entries
             .into_iter()
-            .filter_map(|(entry, tokens)| {
-                let dl = tokens.len() as f64;
-
-                let mut tf: HashMap<&str, f64> = HashMap::new();
-                for term in query_terms {
-                    let count = tokens
-                        .iter()
-                        .filter(|t| t.as_str() == term.as_str())
-                        .count();
-                    tf.insert(term.as_str(), count as f64);
-                }
-
-                let mut score = 0.0f64;
-                for term in query_terms {
-                    let term_freq = tf.get(term.as_str()).copied().unwrap_or(0.0);
-                    if term_freq == 0.0 {
-                        continue;
-                    }
-                    let doc_freq = df.get(term.as_str()).copied().unwrap_or(0.0);
-                    let idf = ((n - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln();
-                    let tf_norm =
-                        (term_freq * (K1 + 1.0)) / (term_freq + K1 * (1.0 - B + B * dl / avgdl));
-                    score += idf * tf_norm;
-                }
-
+            .zip(scores)
+            .filter_map(|(entry, mut score)| {
                 if score > 0.0 {
                     if let Some(t) = tracker {
                         let usage = t.usage_count(&entry.name) as f64;
@@ -530,7 +769,7 @@ impl ToolRegistry {
             .iter()
             .filter(|r| {
                 if let Some(tags) = filter_tags {
-                    r.value().tags.iter().any(|t| tags.contains(t))
+                    r.value().has_any_tag(tags)
                 } else {
                     true
                 }
@@ -642,6 +881,41 @@ impl ToolRegistry {
             .collect()
     }
 
+    /// Replace the prompts published by a backend. An empty list removes them.
+    pub fn register_backend_prompts(&self, backend_name: &str, prompts: Vec<PromptEntry>) {
+        if prompts.is_empty() {
+            self.prompts.remove(backend_name);
+        } else {
+            self.prompts.insert(backend_name.to_string(), prompts);
+        }
+    }
+
+    /// All backend prompts, sorted by name.
+    pub fn get_all_prompts(&self) -> Vec<PromptEntry> {
+        let mut prompts: Vec<PromptEntry> = self
+            .prompts
+            .iter()
+            .flat_map(|r| r.value().clone())
+            .collect();
+        prompts.sort_by(|a, b| a.name.cmp(&b.name));
+        prompts
+    }
+
+    /// Look up a backend prompt by its namespaced name.
+    pub fn get_prompt(&self, name: &str) -> Option<PromptEntry> {
+        self.prompts
+            .iter()
+            .find_map(|r| r.value().iter().find(|p| p.name == name).cloned())
+    }
+
+    /// Export backend prompts grouped by backend, for the cache.
+    pub fn prompt_snapshot(&self) -> HashMap<String, Vec<PromptEntry>> {
+        self.prompts
+            .iter()
+            .map(|r| (r.key().clone(), r.value().clone()))
+            .collect()
+    }
+
     /// Total number of registered tools.
     pub fn tool_count(&self) -> usize {
         self.tools.len()
@@ -721,7 +995,7 @@ impl ToolRegistry {
             .filter_map(|(name, _)| {
                 let entry = self.tools.get(&name)?.value().clone();
                 if let Some(tags) = filter_tags
-                    && !entry.tags.iter().any(|t| tags.contains(t))
+                    && !entry.has_any_tag(tags)
                 {
                     return None;
                 }
@@ -746,6 +1020,16 @@ impl ToolRegistry {
         }
     }
 
+    /// Embedding similarity for ranking output by intent, when an embedding
+    /// index is loaded.
+    pub fn similarity(&self) -> Option<Arc<dyn crate::tools::intent::Similarity>> {
+        #[cfg(feature = "semantic")]
+        if let Some(index) = &self.embedding_index {
+            return Some(Arc::clone(index) as _);
+        }
+        None
+    }
+
     /// Export all tools grouped by backend name (for cache serialization).
     ///
     /// Only exports namespaced entries (entries where `name != original_name`),
@@ -840,6 +1124,58 @@ fn max_edit_distance(word_len: usize) -> usize {
     }
 }
 
+/// BM25 score (k1=1.2, b=0.75) of each tokenized document for the query
+/// terms, with IDF taken over `docs`. Documents without any term score 0.
+pub(crate) fn bm25_scores(query_terms: &[String], docs: &[Vec<String>]) -> Vec<f64> {
+    let n = docs.len() as f64;
+    if n == 0.0 {
+        return Vec::new();
+    }
+
+    let avgdl: f64 = docs.iter().map(|t| t.len() as f64).sum::<f64>() / n;
+
+    let mut df: HashMap<&str, f64> = HashMap::new();
+    for term in query_terms {
+        let count = docs
+            .iter()
+            .filter(|tokens| tokens.iter().any(|t| t == term))
+            .count();
+        df.insert(term.as_str(), count as f64);
+    }
+
+    const K1: f64 = 1.2;
+    const B: f64 = 0.75;
+
+    docs.iter()
+        .map(|tokens| {
+            let dl = tokens.len() as f64;
+
+            let mut tf: HashMap<&str, f64> = HashMap::new();
+            for term in query_terms {
+                let count = tokens
+                    .iter()
+                    .filter(|t| t.as_str() == term.as_str())
+                    .count();
+                tf.insert(term.as_str(), count as f64);
+            }
+
+            let mut score = 0.0f64;
+            for term in query_terms {
+                let term_freq = tf.get(term.as_str()).copied().unwrap_or(0.0);
+                if term_freq == 0.0 {
+                    continue;
+                }
+                let doc_freq = df.get(term.as_str()).copied().unwrap_or(0.0);
+                let idf = ((n - doc_freq + 0.5) / (doc_freq + 0.5) + 1.0).ln();
+                let tf_norm =
+                    (term_freq * (K1 + 1.0)) / (term_freq + K1 * (1.0 - B + B * dl / avgdl));
+                score += idf * tf_norm;
+            }
+            score
+        })
+        .collect()
+}
+
 /// Tokenize text into lowercase terms, splitting on non-alphanumeric characters.
 pub(crate) fn tokenize(text: &str) -> Vec<String> {
     text.to_lowercase()
@@ -862,6 +1198,8 @@ mod tests {
             backend_name: backend.to_string(),
             input_schema: json!({"type": "object"}),
             tags: Vec::new(),
+            output_schema: None,
+            annotations: None,
         }
     }
 
@@ -910,6 +1248,36 @@ mod tests {
         assert!(reg.get_by_name("tavily.tavily_search").is_some());
     }
 
+    #[test]
+    fn test_backend_prompts() {
+        let reg = ToolRegistry::new();
+        let prompt = |backend: &str, name: &str| PromptEntry {
+            name: format!("{backend}.{name}"),
+            original_name: name.to_string(),
+            backend_name: backend.to_string(),
+            title: None,
+            description: None,
+            arguments: Vec::new(),
+        };
+        reg.register_backend_tools("gh", vec![make_entry("get_repo", "Get a repo", "gh")]);
+        reg.register_backend_prompts("gh", vec![prompt("gh", "review_pr")]);
+        reg.register_backend_prompts("docs", vec![prompt("docs", "summarize")]);
+
+        let names: Vec<String> = reg.get_all_prompts().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["docs.summarize", "gh.review_pr"]);
+        assert_eq!(
+            reg.get_prompt("gh.review_pr").unwrap().original_name,
+            "review_pr"
+        );
+        assert!(reg.get_prompt("review_pr").is_none());
+
+        // Prompts go away with the backend's tools.
+        reg.remove_backend_tools("gh");
+        assert!(reg.get_prompt("gh.review_pr").is_none());
+        reg.register_backend_prompts("docs", Vec::new());
+        assert!(reg.get_all_prompts().is_empty());
+    }
+
     #[test]
     fn test_no_collision_bare_and_namespaced() {
         let reg = ToolRegistry::new();
@@ -1224,6 +1592,8 @@ mod tests {
             backend_name: backend.to_string(),
             input_schema: json!({"type": "object"}),
             tags: tags.iter().map(|t| t.to_string()).collect(),
+            output_schema: None,
+            annotations: None,
         }
     }
 
@@ -1372,6 +1742,44 @@ mod tests {
         assert_eq!(ns_entry.tags, vec!["search", "web"]);
     }
 
+    #[test]
+    fn test_annotation_hints_filter_like_tags() {
+        let reg = ToolRegistry::new();
+        let mut read = make_entry("list_issues", "List issues in a repository", "github");
+        read.annotations = Some(ToolAnnotations {
+            read_only_hint: Some(true),
+            ..Default::default()
+        });
+        let mut write = make_entry("delete_issue", "Delete issues in a repository", "github");
+        write.annotations = Some(ToolAnnotations {
+            destructive_hint: Some(true),
+            ..Default::default()
+        });
+        reg.register_backend_tools("github", vec![read, write]);
+
+        let filter = vec!["read-only".to_string()];
+        let results = reg.search("issues repository", 10, Some(&filter), None);
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|r| r.is_read_only()));
+
+        let filter = vec!["destructive".to_string()];
+        let results = reg.search("issues repository", 10, Some(&filter), None);
+        assert!(results.iter().all(|r| r.original_name == "delete_issue"));
+    }
+
+    #[test]
+    fn test_hint_labels_ignore_destructive_when_read_only() {
+        let annotations = ToolAnnotations {
+            read_only_hint: Some(true),
+            destructive_hint: Some(true),
+            idempotent_hint: Some(true),
+            open_world_hint: Some(false),
+            title: None,
+        };
+        assert_eq!(annotations.hint_labels(), vec!["read-only", "idempotent"]);
+        assert!(ToolAnnotations::default().hint_labels().is_empty());
+    }
+
     // --- Phase 2: Tool alias tests ---
 
     #[test]
@@ -1446,6 +1854,127 @@ mod tests {
         assert_eq!(entry.name, "backend.tool_c");
     }
 
+    #[test]
+    fn test_pinned_tools_track_registration() {
+        let reg = ToolRegistry::new();
+        let mut changes = reg.subscribe_native_tools();
+        reg.set_pinned_tools(vec!["exa.web_search".into(), "tavily.search".into()]);
+        // Nothing registered yet, so nothing changed.
+        assert!(reg.pinned_tools().is_empty());
+        assert!(changes.try_recv().is_err());
+
+        reg.register_backend_tools(
+            "exa",
+            vec![
+                make_entry("web_search", "Search the web", "exa"),
+                make_entry("find_similar", "Find similar pages", "exa"),
+            ],
+        );
+        assert!(changes.try_recv().unwrap().pinned);
+        let pinned = reg.pinned_tools();
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(pinned[0].name, "exa.web_search");
+        assert_eq!(pinned[0].original_name, "web_search");
+
+        // Re-registering the same tools is not a change; a new schema is.
+        reg.register_backend_tools(
+            "exa",
+            vec![make_entry("web_search", "Search the web", "exa")],
+        );
+        assert!(changes.try_recv().is_err());
+        let mut entry = make_entry("web_search", "Search the web", "exa");
+        entry.input_schema = json!({"type": "object", "required": ["query"]});
+        reg.register_backend_tools("exa", vec![entry]);
+        assert!(changes.try_recv().unwrap().pinned);
+
+        reg.remove_backend_tools("exa");
+        assert!(changes.try_recv().unwrap().pinned);
+        assert!(reg.pinned_tools().is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_registrations_keep_latest_native_tools() {
+        let reg = ToolRegistry::new();
+        let backends: Vec<String> = (0..8).map(|i| format!("b{i}")).collect();
+        reg.set_pinned_tools(backends.iter().map(|b| format!("{b}.search")).collect());
+        std::thread::scope(|scope| {
+            for backend in &backends {
+                let reg = &reg;
+                scope.spawn(move || {
+                    reg.register_backend_tools(
+                        backend,
+                        vec![make_entry("search", "Search", backend)],
+                    );
+                });
+            }
+        });
+        // The stored snapshot is the latest, so the next change is noticed.
+        assert_eq!(reg.native_tools.lock().unwrap().pinned.len(), 8);
+        let mut changes = reg.subscribe_native_tools();
+        reg.remove_backend_tools("b0");
+        assert!(changes.try_recv().unwrap().pinned);
+    }
+
+    #[test]
+    fn test_toolset_tools() {
+        let reg = ToolRegistry::new();
+        let mut issue = make_entry("get_issue", "Get an issue", "github");
+        issue.tags = vec!["tracker".into()];
+        reg.register_backend_tools(
+            "github",
+            vec![issue, make_entry("create_pr", "Open a PR", "github")],
+        );
+        reg.register_backend_tools("linear", vec![make_entry("list_issues", "List", "linear")]);
+
+        let mut changes = reg.subscribe_native_tools();
+        let mut toolsets = HashMap::new();
+        toolsets.insert(
+            "issues".to_string(),
+            ToolsetConfig {
+                description: Some("Issue tracking".into()),
+                backends: vec!["linear".into()],
+                tags: vec!["tracker".into()],
+                tools: vec!["github.create_*".into()],
+            },
+        );
+        reg.set_toolsets(toolsets);
+        let change = changes.try_recv().unwrap();
+        assert!(!change.pinned);
+        assert_eq!(change.toolsets, BTreeSet::from(["issues".to_string()]));
+        // Only sessions that enabled the toolset see a different tool list.
+        assert!(change.affects(&BTreeSet::from(["issues".to_string()])));
+        assert!(!change.affects(&BTreeSet::new()));
+        assert!(!change.affects(&BTreeSet::from(["other".to_string()])));
+
+        assert_eq!(
+            reg.toolsets(),
+            vec![("issues".to_string(), Some("Issue tracking".to_string()))]
+        );
+        // Namespaced entries only, even though bare aliases exist.
+        let names: Vec<String> = reg
+            .toolset_tools("issues")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["github.create_pr", "github.get_issue", "linear.list_issues"]
+        );
+        assert!(reg.toolset_tools("nope").is_none());
+
+        // Tools outside every toolset are not a change.
+        reg.register_backend_tools("exa", vec![make_entry("search", "Search", "exa")]);
+        assert!(changes.try_recv().is_err());
+
+        reg.remove_backend_tools("linear");
+        assert_eq!(
+            changes.try_recv().unwrap().toolsets,
+            BTreeSet::from(["issues".to_string()])
+        );
+        assert_eq!(reg.toolset_tools("issues").unwrap().len(), 2);
+    }
+
     #[test]
     fn test_alias_hot_reload() {
         let reg = ToolRegistry::new();
@@ -1510,25 +2039,60 @@ mod tests {
 
     #[test]
     fn test_is_transient_error() {
-        use crate::backend::is_transient_error;
-
-        // Transient errors
-        assert!(is_transient_error(&anyhow::anyhow!("connection refused")));
-        assert!(is_transient_error(&anyhow::anyhow!("request timed out")));
-        assert!(is_transient_error(&anyhow::anyhow!("rate limit exceeded")));
-        assert!(is_transient_error(&anyhow::anyhow!("service unavailable")));
-        assert!(is_transient_error(&anyhow::anyhow!("network error")));
+        use crate::error::{GatewayError, is_transient};
+        use std::time::Duration;
+
+        // Transient errors: capacity and connectivity
+        assert!(is_transient(
+            &GatewayError::Transport {
+                backend: "exa".to_string(),
+                tool: "search".to_string(),
+                message: "connection refused".to_string(),
+            }
+            .into()
+        ));
+        assert!(is_transient(
+            &GatewayError::Timeout {
+                backend: "exa".to_string(),
+                tool: "search".to_string(),
+                timeout: Duration::from_secs(30),
+            }
+            .into()
+        ));
+        assert!(is_transient(
+            &GatewayError::RateLimited {
+                backend: "exa".to_string(),
+            }
+            .into()
+        ));
 
         // Non-transient errors
-        assert!(!is_transient_error(&anyhow::anyhow!("invalid parameters")));
-        assert!(!is_transient_error(&anyhow::anyhow!("tool not found")));
-        assert!(!is_transient_error(&anyhow::anyhow!(
-            "authentication failed"
-        )));
-        // Should NOT match generic "connection" substring (false positive fix)
-        assert!(!is_transient_error(&anyhow::anyhow!(
-            "invalid connection string"
-        )));
+        assert!(!is_transient(
+            &GatewayError::ToolNotFound {
+                backend: "exa".to_string(),
+                tool: "search".to_string(),
+            }
+            .into()
+        ));
+        assert!(!is_transient(
+            &GatewayError::InvalidArguments {
+                backend: "exa".to_string(),
+                tool: "search".to_string(),
+                message: "missing 'query'".to_string(),
+            }
+            .into()
+        ));
+        // Message text alone never classifies an error
+        assert!(!is_transient(&anyhow::anyhow!("request timed out")));
+        assert!(!is_transient(
+            &GatewayError::ToolReported {
+                backend: "exa".to_string(),
+                tool: "search".to_string(),
+                message: "upstream service unavailable".to_string(),
+                structured: None,
+            }
+            .into()
+        ));
     }
 
     #[test]
@@ -1575,6 +2139,8 @@ mod tests {
             backend_name: COMPOSITE_BACKEND_NAME.to_string(),
             input_schema: serde_json::json!({"type": "object"}),
             tags: vec!["composite".to_string()],
+            output_schema: None,
+            annotations: None,
         }];
         reg.register_backend_tools_namespaced(
             COMPOSITE_BACKEND_NAME,
@@ -1603,6 +2169,8 @@ mod tests {
             backend_name: COMPOSITE_BACKEND_NAME.to_string(),
             input_schema: serde_json::json!({"type": "object"}),
             tags: vec!["composite".to_string()],
+            output_schema: None,
+            annotations: None,
         }];
         reg.register_backend_tools_namespaced(
             COMPOSITE_BACKEND_NAME,
@@ -1666,6 +2234,8 @@ mod tests {
             backend_name: COMPOSITE_BACKEND_NAME.to_string(),
             input_schema: serde_json::json!({"type": "object"}),
             tags: vec!["composite".to_string()],
+            output_schema: None,
+            annotations: None,
         }];
         reg.register_backend_tools_namespaced(
             COMPOSITE_BACKEND_NAME,
@@ -7,6 +7,7 @@ use serde::Serialize;
 
 use crate::backend::BackendManager;
 use crate::registry::ToolRegistry;
+use crate::results::{ResultRange, ResultStore};
 use crate::tracker::CallTracker;
 
 /// Return the static resources available for @-mention discovery.
@@ -126,6 +127,15 @@ pub fn list_resource_templates() -> Vec<ResourceTemplate> {
                 .with_mime_type("application/json"),
             None,
         ),
+        Annotated::new(
+            RawResourceTemplate::new("gatemini://types/{backend_name}", "types")
+                .with_title("Backend Types")
+                .with_description(
+                    "TypeScript declarations for a backend's tools as called in call_tool_chain, generated from their schemas",
+                )
+                .with_mime_type("application/typescript"),
+            None,
+        ),
         Annotated::new(
             RawResourceTemplate::new("gatemini://recent/{limit}", "recent-limited")
                 .with_title("Recent Tool Calls (Custom Limit)")
@@ -135,6 +145,15 @@ pub fn list_resource_templates() -> Vec<ResourceTemplate> {
                 .with_mime_type("application/json"),
             None,
         ),
+        Annotated::new(
+            RawResourceTemplate::new("gatemini://result/{id}", "result")
+                .with_title("Kept Result")
+                .with_description(
+                    "Slice of a full tool result that was chunked or truncated. Add ?bytes=START-END (0-based), ?lines=START-END (1-based) or ?pointer=/json/pointer; both ends of a range are inclusive; defaults to the first 20000 bytes",
+                )
+                .with_mime_type("text/plain"),
+            None,
+        ),
         Annotated::new(
             RawResourceTemplate::new("gatemini://guide/{topic}", "guide")
                 .with_title("Guide")
@@ -155,6 +174,15 @@ struct CompactToolEntry {
     description: String,
 }
 
+/// Tool detail for the gatemini://tool/{name} template: the registry entry
+/// with normalized schemas and the parameter signature.
+#[derive(Debug, Serialize)]
+struct ToolDetail {
+    #[serde(flatten)]
+    entry: crate::registry::ToolEntry,
+    signature: String,
+}
+
 /// Backend info entry for the gatemini://backends resource.
 #[derive(Debug, Serialize)]
 struct BackendInfo {
@@ -197,6 +225,7 @@ pub async fn read_resource(
     registry: &Arc<ToolRegistry>,
     backend_manager: &Arc<BackendManager>,
     tracker: &Arc<CallTracker>,
+    results: &ResultStore,
 ) -> Result<ReadResourceResult, McpError> {
     // Parse the URI
     let path = uri
@@ -283,7 +312,21 @@ pub async fn read_resource(
         }
         _ => {
             // Try template matching
-            if let Some(limit_str) = path.strip_prefix("recent/") {
+            if path.starts_with("r/") {
+                // gatemini://r/{backend}/{uri} — proxied backend resource
+                if crate::backend::resources::split_namespaced_uri(uri).is_none() {
+                    return Err(McpError::invalid_params(
+                        format!(
+                            "Invalid backend resource URI '{uri}': expected gatemini://r/{{backend}}/{{uri}}"
+                        ),
+                        None,
+                    ));
+                }
+                backend_manager
+                    .read_backend_resource(uri)
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("{e:#}"), None))
+            } else if let Some(limit_str) = path.strip_prefix("recent/") {
                 // gatemini://recent/{limit}
                 let limit: usize = limit_str.parse().map_err(|_| {
                     McpError::invalid_params(
@@ -295,6 +338,13 @@ pub async fn read_resource(
                 let json = serde_json::to_string_pretty(&calls)
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
                 Ok(text_resource(uri, &json))
+            } else if let Some(rest) = path.strip_prefix("result/") {
+                // gatemini://result/{id}?bytes=..|lines=..|pointer=..
+                let (id, query) = rest.split_once('?').unwrap_or((rest, ""));
+                let slice = ResultRange::from_query(query)
+                    .and_then(|range| results.read(id, &range))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+                Ok(text_resource(uri, &slice.to_text(id)))
             } else if let Some(topic) = path.strip_prefix("guide/") {
                 match topic {
                     "call_tool_chain" => Ok(text_resource(uri, &call_tool_chain_guide_text())),
@@ -308,12 +358,29 @@ pub async fn read_resource(
                 }
             } else if let Some(tool_name) = path.strip_prefix("tool/") {
                 // gatemini://tool/{tool_name}
-                let entry = registry.get_by_name(tool_name).ok_or_else(|| {
+                let mut entry = registry.get_by_name(tool_name).ok_or_else(|| {
                     McpError::invalid_params(format!("Tool '{tool_name}' not found"), None)
                 })?;
-                let json = serde_json::to_string_pretty(&entry)
+                entry.input_schema = entry.normalized_input_schema();
+                entry.output_schema = entry.normalized_output_schema();
+                let detail = ToolDetail {
+                    signature: crate::schema::signature(&entry.input_schema),
+                    entry,
+                };
+                let json = serde_json::to_string_pretty(&detail)
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
                 Ok(text_resource(uri, &json))
+            } else if let Some(backend_name) = path.strip_prefix("types/") {
+                // gatemini://types/{backend_name}
+                let declarations =
+                    crate::tools::discovery::handle_tool_types(registry, Some(backend_name), None)
+                        .ok_or_else(|| {
+                            McpError::invalid_params(
+                                format!("Backend '{backend_name}' not found or has no tools"),
+                                None,
+                            )
+                        })?;
+                Ok(text_resource(uri, &declarations))
             } else if let Some(rest) = path.strip_prefix("backend/") {
                 if let Some(backend_name) = rest.strip_suffix("/tools") {
                     // gatemini://backend/{name}/tools
@@ -392,7 +459,10 @@ pub fn complete(
                     CompletionInfo::with_all_values(values)
                         .map_err(|e| McpError::internal_error(e, None))?,
                 ))
-            } else if uri.contains("backend/{backend_name}") || uri.contains("backend/") {
+            } else if uri.contains("backend/{backend_name}")
+                || uri.contains("backend/")
+                || uri.contains("types/")
+            {
                 // Complete backend names
                 let prefix = &request.argument.value;
                 let values: Vec<String> = registry
@@ -427,10 +497,10 @@ pub fn complete(
 fn overview_text(registry: &ToolRegistry) -> String {
     format!(
         "# Gatemini MCP Gateway\n\n\
-         You are connected to gatemini, an MCP gateway that aggregates {} tools from {} backends          into a single interface. You interact with it through 7 meta-tools — never call backend          tools directly as MCP tools.\n\n\
+         You are connected to gatemini, an MCP gateway that aggregates {} tools from {} backends          into a single interface. You interact with it through 12 meta-tools — never call backend          tools directly as MCP tools.\n\n\
          ## Discovery\n\n\
          1. `search_tools(task_description=\"what you need\")` — brief results (~60 tokens each)\n\
-         2. `tool_info(tool_name=\"backend.tool_name\")` — parameter names (~200 tokens)\n\
+         2. `tool_info(tool_name=\"backend.tool_name\")` — parameter names and signature (~200 tokens)\n\
          3. `tool_info(tool_name=\"...\", detail=\"full\")` — complete input schema (REQUIRED before first call — never guess parameter names)\n\
          4. `list_tools_meta` — paginated browsing of all {} tools\n\n\
          ## Execution\n\n\
@@ -447,6 +517,9 @@ fn overview_text(registry: &ToolRegistry) -> String {
          }}\n\
          return results;\n\
          ```\n\n\
+         Independent calls can also run in parallel without the sandbox via `call_tools_batch`:\n\
+         `calls=[{{\"tool\": \"exa.web_search_exa\", \"arguments\": {{...}}}}, ...]` returns one result or error per call.\n\n\
+         `tool_types(backend=\"exa\")` returns TypeScript declarations for a backend's tools; pass `type_check=true` to `call_tool_chain` to check argument literals against them before the code runs.\n\n\
          ## Naming Rules\n\n\
          - ALWAYS use qualified names: `backend.tool_name` (e.g. `exa.web_search_exa`)\n\
          - Hyphens become underscores in sandbox: `my-backend` -> `my_backend`\n\
@@ -456,6 +529,7 @@ fn overview_text(registry: &ToolRegistry) -> String {
          - `@gatemini://tools` — compact index of all tools (~3k tokens)\n\
          - `@gatemini://backends` — backend health status and tool counts\n\
          - `@gatemini://tool/{{name}}` — full schema for one tool\n\
+         - `@gatemini://types/{{backend}}` — TypeScript declarations for a backend's tools\n\
          - `@gatemini://call_tool_chain` — execution contract and examples\n\n\
          ## Prompts\n\n\
          - `/mcp__gatemini__discover` — guided discovery walkthrough\n\
@@ -526,7 +600,7 @@ fn llms_txt(registry: &ToolRegistry) -> String {
          - search_tools: BM25/trigram/fuzzy search across all tools\n\
          - tool_info: Get tool details (brief or full schema)\n\
          - list_tools_meta: Paginated tool list\n\
-         - call_tool_chain: Execute TypeScript with tool access (supports intent filtering)\n\
+         - call_tool_chain: Execute TypeScript with tool access (supports select projection and intent filtering)\n\
          - register_manual: Add backend at runtime\n\
          - deregister_manual: Remove backend at runtime\n\
          - get_required_keys_for_tool: Check required env vars\n\n\
@@ -575,7 +649,8 @@ fn llms_full_txt(registry: &ToolRegistry) -> String {
         text.push_str(&format!("### `{}`\n\n", entry.name));
         text.push_str(&format!("**Backend:** {}\n\n", entry.backend_name));
         text.push_str(&format!("**Description:** {}\n\n", entry.description));
-        if let Ok(schema) = serde_json::to_string_pretty(&entry.input_schema) {
+        text.push_str(&format!("**Parameters:** `{}`\n\n", entry.signature()));
+        if let Ok(schema) = serde_json::to_string_pretty(&entry.normalized_input_schema()) {
             text.push_str(&format!("**Input Schema:**\n```json\n{}\n```\n\n", schema));
         }
     }
@@ -0,0 +1,434 @@
+//! Full copies of tool results the output pipeline cut down.
+//!
+//! When chunking or truncation drops part of a result, the raw text is kept
+//! here and the returned output carries a handle to it. `read_result` and
+//! `gatemini://result/{id}` then return byte ranges, line ranges or JSON
+//! pointer slices of the full text. The daemon keeps one store for every
+//! session; it is bounded in bytes (oldest results are evicted first) and
+//! results expire after a TTL.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// Bytes returned by a read that sets no range.
+pub const DEFAULT_SLICE_BYTES: usize = 20_000;
+
+/// Lines returned by a line read that sets no count.
+pub const DEFAULT_SLICE_LINES: usize = 200;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResultError {
+    #[error("unknown or expired result '{0}'")]
+    NotFound(String),
+
+    #[error("result '{0}' is not JSON, use a byte or line range")]
+    NotJson(String),
+
+    #[error("JSON pointer '{pointer}' not found in result '{id}'")]
+    PointerNotFound { id: String, pointer: String },
+
+    #[error("invalid range: {0}")]
+    InvalidRange(String),
+}
+
+/// Part of a stored result to return.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResultRange {
+    /// `length` bytes from `offset`, snapped to UTF-8 character boundaries
+    /// (at least one character, so a short range never comes back empty).
+    Bytes { offset: usize, length: usize },
+    /// `count` lines from line `start` (1-based).
+    Lines { start: usize, count: usize },
+    /// The value at a JSON pointer (RFC 6901), pretty-printed.
+    Pointer(String),
+}
+
+impl Default for ResultRange {
+    fn default() -> Self {
+        ResultRange::Bytes {
+            offset: 0,
+            length: DEFAULT_SLICE_BYTES,
+        }
+    }
+}
+
+impl ResultRange {
+    /// Range from `read_result` arguments. At most one kind of range may be set.
+    pub fn from_args(
+        offset: Option<usize>,
+        length: Option<usize>,
+        start_line: Option<usize>,
+        line_count: Option<usize>,
+        pointer: Option<String>,
+    ) -> Result<Self, ResultError> {
+        if length == Some(0) || line_count == Some(0) {
+            return Err(ResultError::InvalidRange(
+                "length and line_count must be at least 1".into(),
+            ));
+        }
+        let bytes = offset.is_some() || length.is_some();
+        let lines = start_line.is_some() || line_count.is_some();
+        match (bytes, lines, pointer) {
+            (false, false, None) => Ok(Self::default()),
+            (_, false, None) => Ok(ResultRange::Bytes {
+                offset: offset.unwrap_or(0),
+                length: length.unwrap_or(DEFAULT_SLICE_BYTES),
+            }),
+            (false, _, None) => Ok(ResultRange::Lines {
+                start: start_line.unwrap_or(1).max(1),
+                count: line_count.unwrap_or(DEFAULT_SLICE_LINES),
+            }),
+            (false, false, Some(pointer)) => Ok(ResultRange::Pointer(pointer)),
+            _ => Err(ResultError::InvalidRange(
+                "use only one of offset/length, start_line/line_count or pointer".into(),
+            )),
+        }
+    }
+
+    /// Range from the query of a `gatemini://result/{id}` URI:
+    /// `bytes=START-END` (0-based), `lines=START-END` (1-based) or
+    /// `pointer=/path`. Both ends of a span are inclusive.
+    pub fn from_query(query: &str) -> Result<Self, ResultError> {
+        let params: HashMap<String, String> = serde_urlencoded::from_str(query)
+            .map_err(|e| ResultError::InvalidRange(e.to_string()))?;
+        let span = |value: &str| -> Result<(usize, usize), ResultError> {
+            let invalid =
+                || ResultError::InvalidRange(format!("expected START-END, got '{value}'"));
+            let (start, end) = value.split_once('-').ok_or_else(invalid)?;
+            let start: usize = start.trim().parse().map_err(|_| invalid())?;
+            let end: usize = end.trim().parse().map_err(|_| invalid())?;
+            if end < start {
+                return Err(invalid());
+            }
+            Ok((start, end))
+        };
+        let bytes = params.get("bytes").map(|v| span(v)).transpose()?;
+        let lines = params.get("lines").map(|v| span(v)).transpose()?;
+        Self::from_args(
+            bytes.map(|(start, _)| start),
+            bytes.map(|(start, end)| end - start + 1),
+            lines.map(|(start, _)| start),
+            lines.map(|(start, end)| (end + 1).saturating_sub(start.max(1))),
+            params.get("pointer").cloned(),
+        )
+    }
+}
+
+/// A slice of a stored result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSlice {
+    pub text: String,
+    pub total_bytes: usize,
+    pub total_lines: usize,
+    /// Where the next read continues, if the range did not reach the end.
+    pub next: Option<ResultRange>,
+}
+
+impl ResultSlice {
+    /// The slice with a one-line header saying where it is in the result.
+    pub fn to_text(&self, id: &str) -> String {
+        let next = match &self.next {
+            Some(ResultRange::Bytes { offset, .. }) => format!("; next: offset={offset}"),
+            Some(ResultRange::Lines { start, .. }) => format!("; next: start_line={start}"),
+            _ => String::new(),
+        };
+        format!(
+            "[result {id}: {} bytes, {} lines{next}]\n{}",
+            self.total_bytes, self.total_lines, self.text
+        )
+    }
+}
+
+/// Cut `range` out of `text`.
+pub fn slice(id: &str, text: &str, range: &ResultRange) -> Result<ResultSlice, ResultError> {
+    let total_bytes = text.len();
+    let total_lines = text.lines().count();
+    let (text, next) = match range {
+        ResultRange::Bytes { offset, length } => {
+            let start = text.floor_char_boundary(*offset);
+            let mut end = text.floor_char_boundary(start.saturating_add(*length));
+            if end == start && start < total_bytes {
+                // Shorter than the character at `start`: return that
+                // character rather than nothing, so paging makes progress.
+                end = text.ceil_char_boundary(start + 1);
+            }
+            let next = (end < total_bytes).then_some(ResultRange::Bytes {
+                offset: end,
+                length: *length,
+            });
+            (text[start..end].to_string(), next)
+        }
+        ResultRange::Lines { start, count } => {
+            let first = start.saturating_sub(1);
+            let lines: String = text
+                .split_inclusive('\n')
+                .skip(first)
+                .take(*count)
+                .collect();
+            let end = first.saturating_add(*count);
+            let next = (end < total_lines).then(|| ResultRange::Lines {
+                start: end + 1,
+                count: *count,
+            });
+            (lines, next)
+        }
+        ResultRange::Pointer(pointer) => {
+            let value: Value =
+                serde_json::from_str(text).map_err(|_| ResultError::NotJson(id.to_string()))?;
+            let found = value
+                .pointer(pointer)
+                .ok_or_else(|| ResultError::PointerNotFound {
+                    id: id.to_string(),
+                    pointer: pointer.clone(),
+                })?;
+            let pretty = serde_json::to_string_pretty(found).unwrap_or_else(|_| found.to_string());
+            (pretty, None)
+        }
+    };
+    Ok(ResultSlice {
+        text,
+        total_bytes,
+        total_lines,
+        next,
+    })
+}
+
+/// Results kept for later reads, shared by every session of the daemon.
+pub struct ResultStore {
+    max_bytes: usize,
+    ttl: Duration,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Default)]
+struct Entries {
+    results: HashMap<String, StoredResult>,
+    /// Ids, oldest first.
+    order: VecDeque<String>,
+    bytes: usize,
+}
+
+struct StoredResult {
+    text: Arc<str>,
+    stored_at: Instant,
+}
+
+impl Entries {
+    fn remove_oldest(&mut self) -> bool {
+        let Some(id) = self.order.pop_front() else {
+            return false;
+        };
+        if let Some(result) = self.results.remove(&id) {
+            self.bytes -= result.text.len();
+        }
+        true
+    }
+
+    fn purge_expired(&mut self, ttl: Duration) {
+        while let Some(id) = self.order.front() {
+            let expired = self
+                .results
+                .get(id)
+                .is_none_or(|result| result.stored_at.elapsed() >= ttl);
+            if !expired {
+                break;
+            }
+            self.remove_oldest();
+        }
+    }
+}
+
+impl ResultStore {
+    /// A store holding at most `max_bytes` of results, each for `ttl`.
+    /// `max_bytes` of 0 keeps nothing.
+    pub fn new(max_bytes: usize, ttl: Duration) -> Arc<Self> {
+        Arc::new(Self {
+            max_bytes,
+            ttl,
+            entries: Mutex::new(Entries::default()),
+        })
+    }
+
+    /// A store sized by the `sandbox.output` config.
+    pub fn from_config(config: &crate::config::OutputConfig) -> Arc<Self> {
+        Self::new(config.result_store_bytes, config.result_ttl)
+    }
+
+    /// Keep `text` and return its handle, evicting the oldest results to
+    /// make room. `None` if the text alone exceeds the store's size.
+    pub fn put(&self, text: String) -> Option<String> {
+        if text.is_empty() || text.len() > self.max_bytes {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.purge_expired(self.ttl);
+        while entries.bytes + text.len() > self.max_bytes && entries.remove_oldest() {}
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        entries.bytes += text.len();
+        entries.order.push_back(id.clone());
+        entries.results.insert(
+            id.clone(),
+            StoredResult {
+                text: text.into(),
+                stored_at: Instant::now(),
+            },
+        );
+        Some(id)
+    }
+
+    /// Full text of a result.
+    pub fn get(&self, id: &str) -> Result<Arc<str>, ResultError> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.purge_expired(self.ttl);
+        entries
+            .results
+            .get(id)
+            .map(|result| Arc::clone(&result.text))
+            .ok_or_else(|| ResultError::NotFound(id.to_string()))
+    }
+
+    /// A range of a result.
+    pub fn read(&self, id: &str, range: &ResultRange) -> Result<ResultSlice, ResultError> {
+        let text = self.get(id)?;
+        slice(id, &text, range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_store_evicts_oldest_over_budget() {
+        let store = ResultStore::new(10, HOUR);
+        let a = store.put("aaaa".into()).unwrap();
+        let b = store.put("bbbb".into()).unwrap();
+        // Needs 4 more bytes than fit: `a` goes.
+        let c = store.put("cccccc".into()).unwrap();
+        assert!(matches!(store.get(&a), Err(ResultError::NotFound(_))));
+        assert_eq!(&*store.get(&b).unwrap(), "bbbb");
+        assert_eq!(&*store.get(&c).unwrap(), "cccccc");
+        // Larger than the whole store: not kept.
+        assert!(store.put("x".repeat(11)).is_none());
+
+        let expiring = ResultStore::new(10, Duration::ZERO);
+        let id = expiring.put("gone".into()).unwrap();
+        assert!(matches!(expiring.get(&id), Err(ResultError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_slice_bytes_and_lines() {
+        let text = "line1\nline2\nline3\nline4\n";
+        let first = slice(
+            "r",
+            text,
+            &ResultRange::Bytes {
+                offset: 0,
+                length: 8,
+            },
+        )
+        .unwrap();
+        assert_eq!(first.text, "line1\nli");
+        assert_eq!(first.total_bytes, 24);
+        assert_eq!(first.total_lines, 4);
+        assert_eq!(
+            first.next,
+            Some(ResultRange::Bytes {
+                offset: 8,
+                length: 8
+            })
+        );
+        assert!(
+            first
+                .to_text("r")
+                .starts_with("[result r: 24 bytes, 4 lines; next: offset=8]\n")
+        );
+
+        let lines = slice("r", text, &ResultRange::Lines { start: 2, count: 2 }).unwrap();
+        assert_eq!(lines.text, "line2\nline3\n");
+        assert_eq!(lines.next, Some(ResultRange::Lines { start: 4, count: 2 }));
+        let last = slice("r", text, &ResultRange::Lines { start: 4, count: 2 }).unwrap();
+        assert_eq!(last.text, "line4\n");
+        assert_eq!(last.next, None);
+
+        // Never splits a UTF-8 character.
+        let utf8 = slice(
+            "r",
+            "héllo",
+            &ResultRange::Bytes {
+                offset: 0,
+                length: 2,
+            },
+        )
+        .unwrap();
+        assert_eq!(utf8.text, "h");
+
+        // A range shorter than the character it starts in still advances.
+        let short = slice(
+            "r",
+            "héllo",
+            &ResultRange::Bytes {
+                offset: 1,
+                length: 1,
+            },
+        )
+        .unwrap();
+        assert_eq!(short.text, "é");
+        assert_eq!(
+            short.next,
+            Some(ResultRange::Bytes {
+                offset: 3,
+                length: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_slice_json_pointer() {
+        let text = r#"{"items": [{"id": 1}, {"id": 2}]}"#;
+        let item = slice("r", text, &ResultRange::Pointer("/items/1".into())).unwrap();
+        assert_eq!(item.text, "{\n  \"id\": 2\n}");
+        assert!(matches!(
+            slice("r", text, &ResultRange::Pointer("/nope".into())),
+            Err(ResultError::PointerNotFound { .. })
+        ));
+        assert!(matches!(
+            slice("r", "plain text", &ResultRange::Pointer("/a".into())),
+            Err(ResultError::NotJson(_))
+        ));
+    }
+
+    #[test]
+    fn test_range_from_query_and_args() {
+        assert_eq!(ResultRange::from_query("").unwrap(), ResultRange::default());
+        assert_eq!(
+            ResultRange::from_query("bytes=100-599").unwrap(),
+            ResultRange::Bytes {
+                offset: 100,
+                length: 500
+            }
+        );
+        assert_eq!(
+            ResultRange::from_query("lines=10-19").unwrap(),
+            ResultRange::Lines {
+                start: 10,
+                count: 10
+            }
+        );
+        assert_eq!(
+            ResultRange::from_query("pointer=%2Fitems%2F0").unwrap(),
+            ResultRange::Pointer("/items/0".into())
+        );
+        assert!(ResultRange::from_query("bytes=9-1").is_err());
+        assert!(ResultRange::from_args(Some(0), None, Some(1), None, None).is_err());
+        // Zero-sized reads would page forever.
+        assert!(ResultRange::from_args(None, Some(0), None, None, None).is_err());
+        assert!(ResultRange::from_args(None, None, Some(3), Some(0), None).is_err());
+    }
+}
@@ -11,7 +11,12 @@ use std::collections::HashMap;
 /// };
 /// ```
 ///
-/// Also generates `__interfaces` and `__getToolInterface()` for discovery.
+/// The methods go through `BackendManager::call_tool`, which checks the
+/// arguments against the tool's input schema first: a call with bad
+/// arguments rejects with the list of problems and the expected parameters.
+///
+/// Also generates `__interfaces` and `__getToolInterface()` for discovery,
+/// with each tool's normalized schemas and parameter signature.
 pub fn generate_preamble(tools: &[ToolEntry]) -> String {
     // Group tools by backend
     let mut by_backend: HashMap<&str, Vec<&ToolEntry>> = HashMap::new();
@@ -115,12 +120,22 @@ pub fn generate_preamble(tools: &[ToolEntry]) -> String {
             } else {
                 &tool.original_name
             };
-            let schema_json = serde_json::to_string(&tool.input_schema).unwrap_or_default();
+            let input_schema = tool.normalized_input_schema();
+            let schema_json = serde_json::to_string(&input_schema).unwrap_or_default();
+            let signature_json =
+                serde_json::to_string(&crate::schema::signature(&input_schema)).unwrap_or_default();
             let desc_json = serde_json::to_string(&tool.description).unwrap_or_default();
             let name_json = serde_json::to_string(orig).unwrap_or_default();
+            let output_json = match tool.normalized_output_schema() {
+                Some(schema) => format!(
+                    ", output_schema: {}",
+                    serde_json::to_string(&schema).unwrap_or_default()
+                ),
+                None => String::new(),
+            };
             preamble.push_str(&format!(
-                "    {}: {{ name: {}, description: {}, input_schema: {} }},\n",
-                name_json, name_json, desc_json, schema_json
+                "    {}: {{ name: {}, description: {}, signature: {}, input_schema: {}{} }},\n",
+                name_json, name_json, desc_json, signature_json, schema_json, output_json
             ));
         }
         preamble.push_str("  },\n");
@@ -179,6 +194,8 @@ mod tests {
             backend_name: backend.to_string(),
             input_schema: json!({"type": "object", "properties": {"query": {"type": "string"}}}),
             tags: Vec::new(),
+            output_schema: None,
+            annotations: None,
         }
     }
 
@@ -214,8 +231,9 @@ mod tests {
         assert!(preamble.contains(r#"__ct("exa", "web_search""#));
         assert!(preamble.contains(r#"__ct("tavily", "tavily_search""#));
 
-        // Should contain __interfaces
+        // Should contain __interfaces, with each tool's signature
         assert!(preamble.contains("const __interfaces = {"));
+        assert!(preamble.contains(r#"signature: "{query?: string}""#));
 
         // Should contain __getToolInterface
         assert!(preamble.contains("function __getToolInterface(dotted_name)"));
@@ -300,6 +318,18 @@ mod tests {
         assert!(preamble.contains("globalThis['my-search-backend'] = my_search_backend;"));
     }
 
+    #[test]
+    fn test_generate_preamble_includes_output_schema() {
+        let mut entry = make_entry("get_weather", "Weather", "wx");
+        entry.output_schema =
+            Some(json!({"type": "object", "properties": {"temp": {"type": "number"}}}));
+        let preamble = generate_preamble(&[entry, make_entry("plain", "No output schema", "wx")]);
+        assert!(preamble.contains(
+            r#"output_schema: {"properties":{"temp":{"type":"number"}},"type":"object"}"#
+        ));
+        assert_eq!(preamble.matches("output_schema:").count(), 1);
+    }
+
     #[test]
     fn test_generate_preamble_contains_require_shim() {
         let preamble = generate_preamble(&[]);
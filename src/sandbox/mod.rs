@@ -10,11 +10,17 @@ use anyhow::Result;
 #[cfg(feature = "sandbox")]
 use serde_json::Value;
 #[cfg(feature = "sandbox")]
+use tokio_util::sync::CancellationToken;
+#[cfg(feature = "sandbox")]
 use tracing::{debug, info, warn};
 
 #[cfg(feature = "sandbox")]
 use crate::backend::BackendManager;
 #[cfg(feature = "sandbox")]
+use crate::backend::progress::{self, ChainProgress};
+#[cfg(feature = "sandbox")]
+use crate::error::GatewayError;
+#[cfg(feature = "sandbox")]
 use crate::registry::ToolRegistry;
 
 /// Execute TypeScript code in a sandboxed V8 runtime with access to
@@ -22,6 +28,13 @@ use crate::registry::ToolRegistry;
 ///
 /// The sandbox runs on a dedicated OS thread (V8 isolates are !Send).
 /// Tool calls are dispatched back to the main tokio runtime via Handle::spawn.
+/// Returns the entrypoint's value; non-text content blocks returned by tools
+/// (images, audio, resources) are preserved as typed objects. Tool calls keep
+/// reporting to the caller's progress scope, if any.
+///
+/// When the returned future completes or is dropped (the client cancelled the
+/// request), the isolate is terminated and tool calls the script still has in
+/// flight are cancelled.
 #[cfg(feature = "sandbox")]
 pub async fn execute(
     registry: &Arc<ToolRegistry>,
@@ -30,7 +43,7 @@ pub async fn execute(
     timeout: Duration,
     max_heap_size: Option<usize>,
     session_id: Option<u64>,
-) -> Result<String> {
+) -> Result<Value> {
     let main_handle = tokio::runtime::Handle::current();
     let manager = Arc::clone(manager);
     let tools = registry.get_all();
@@ -45,6 +58,9 @@ pub async fn execute(
     );
 
     let registry = Arc::clone(registry);
+    let progress = progress::current();
+    let cancel = CancellationToken::new();
+    let _cancel_on_drop = cancel.clone().drop_guard();
     let (tx, rx) = tokio::sync::oneshot::channel();
 
     std::thread::Builder::new()
@@ -59,6 +75,8 @@ pub async fn execute(
                 timeout,
                 heap_size,
                 session_id,
+                progress,
+                cancel,
             );
             let _ = tx.send(result);
         })?;
@@ -79,7 +97,9 @@ fn run_sandbox(
     timeout: Duration,
     max_heap_size: usize,
     session_id: Option<u64>,
-) -> Result<String> {
+    progress: Option<Arc<ChainProgress>>,
+    cancel: CancellationToken,
+) -> Result<Value> {
     use rustyscript::{Module, Runtime, RuntimeOptions};
     use std::pin::Pin;
 
@@ -91,6 +111,14 @@ fn run_sandbox(
     })
     .map_err(|e| anyhow::anyhow!("failed to create sandbox runtime: {e}"))?;
 
+    // Terminate the isolate from the main runtime once the caller goes away.
+    let isolate = runtime.deno_runtime().v8_isolate().thread_safe_handle();
+    let terminate = cancel.clone();
+    main_handle.spawn(async move {
+        terminate.cancelled().await;
+        isolate.terminate_execution();
+    });
+
     // Register __call_tool: dispatches tool calls to the main tokio runtime
     // where the rmcp backend services live.
     let mgr = manager;
@@ -104,6 +132,8 @@ fn run_sandbox(
                 let mgr = mgr.clone();
                 let handle = handle.clone();
                 let reg = reg.clone();
+                let progress = progress.clone();
+                let cancel = cancel.clone();
                 Box::pin(async move {
                     if args.len() < 2 {
                         return Err(rustyscript::Error::Runtime(
@@ -137,10 +167,15 @@ fn run_sandbox(
                     let bn = backend_name.clone();
                     let tn = tool_name.clone();
                     let sid = session_id;
+                    let call_progress = progress.clone();
+                    let call_cancel = cancel.clone();
                     let result = handle
-                        .spawn(async move {
-                            mgr.call_tool(&bn, &tn, arguments, sid).await
-                        })
+                        .spawn(progress::scope(call_progress, async move {
+                            tokio::select! {
+                                result = mgr.call_tool(&bn, &tn, arguments, sid) => result,
+                                _ = call_cancel.cancelled() => Err(GatewayError::Cancelled.into()),
+                            }
+                        }))
                         .await
                         .map_err(|e| {
                             rustyscript::Error::Runtime(format!("task join error: {e}"))
@@ -150,9 +185,10 @@ fn run_sandbox(
                         Ok(value) => Ok(value),
                         Err(e) => {
                             let err_str = e.to_string();
+                            let gateway_err = GatewayError::find(&e);
 
                             // On-demand restart for stopped backends
-                            if err_str.contains("not available") && err_str.contains("Stopped") {
+                            if gateway_err.is_some_and(GatewayError::is_stopped) {
                                 info!(backend = %backend_name, tool = %tool_name,
                                       "attempting on-demand restart for stopped backend");
                                 let restart_reg = reg.clone();
@@ -174,9 +210,12 @@ fn run_sandbox(
                                     let retry_tn = tool_name.clone();
                                     let retry_sid = session_id;
                                     let retry_result = handle
-                                        .spawn(async move {
-                                            retry_mgr.call_tool(&retry_bn, &retry_tn, args_for_retry, retry_sid).await
-                                        })
+                                        .spawn(progress::scope(progress, async move {
+                                            tokio::select! {
+                                                result = retry_mgr.call_tool(&retry_bn, &retry_tn, args_for_retry, retry_sid) => result,
+                                                _ = cancel.cancelled() => Err(GatewayError::Cancelled.into()),
+                                            }
+                                        }))
                                         .await
                                         .map_err(|e| rustyscript::Error::Runtime(format!("retry join: {e}")))?;
                                     return match retry_result {
@@ -190,8 +229,13 @@ fn run_sandbox(
                             }
 
                             // Enhance error if tool is cached but backend isn't ready
-                            if (err_str.contains("not found") || err_str.contains("still starting"))
-                                && reg.get_by_name(&tool_name).is_some()
+                            if matches!(
+                                gateway_err,
+                                Some(
+                                    GatewayError::BackendStarting { .. }
+                                        | GatewayError::BackendNotFound { .. }
+                                )
+                            ) && reg.get_by_name(&tool_name).is_some()
                             {
                                 return Err(rustyscript::Error::Runtime(format!(
                                     "Backend '{}' is still starting. Tool '{}' is cached \
@@ -201,7 +245,7 @@ fn run_sandbox(
                             }
 
                             // Backend stopped or transport closed
-                            if err_str.contains("not available") || err_str.contains("Transport closed") {
+                            if gateway_err.is_some_and(GatewayError::is_backend_unavailable) {
                                 return Err(rustyscript::Error::Runtime(format!(
                                     "Backend '{}' is not available for tool '{}'. \
                                      The backend may have stopped or lost connection.\n\
@@ -262,17 +306,24 @@ fn run_sandbox(
 
     debug!("sandbox execution complete");
 
-    serde_json::to_string_pretty(&result).map_err(|e| anyhow::anyhow!("result serialization: {e}"))
+    Ok(result)
 }
 
-/// Enhance sandbox errors with actionable hints for common LLM mistakes.
+/// Convert a sandbox failure into a [`GatewayError::Sandbox`] carrying
+/// actionable hints for common LLM mistakes.
 #[cfg(feature = "sandbox")]
 fn enhance_sandbox_error(
     err: rustyscript::Error,
     backend_names: &std::collections::HashSet<String>,
 ) -> anyhow::Error {
-    let msg = err.to_string();
+    GatewayError::Sandbox {
+        message: sandbox_error_message(&err.to_string(), backend_names),
+    }
+    .into()
+}
 
+#[cfg(feature = "sandbox")]
+fn sandbox_error_message(msg: &str, backend_names: &std::collections::HashSet<String>) -> String {
     // Pattern: `const auggie = await auggie.codebase_retrieval(...)` shadows the backend const.
     // V8 error: "ReferenceError: Cannot access 'X' before initialization"
     if msg.contains("Cannot access '")
@@ -283,7 +334,7 @@ fn enhance_sandbox_error(
         if let Some(end) = rest.find('\'') {
             let var_name = &rest[..end];
             if backend_names.contains(var_name) {
-                return anyhow::anyhow!(
+                return format!(
                     "sandbox execution error: {msg}\n\n\
                      HINT: `{var_name}` is a backend name. Writing `const {var_name} = await {var_name}.tool(...)` \
                      shadows the backend variable before it's read. \
@@ -311,7 +362,7 @@ fn enhance_sandbox_error(
                 })
                 .cloned();
             if let Some(correct) = suggestion {
-                return anyhow::anyhow!(
+                return format!(
                     "sandbox execution error: {msg}\n\n\
                      HINT: Did you mean `{correct}`? Available backends with similar names: {correct}"
                 );
@@ -327,7 +378,7 @@ fn enhance_sandbox_error(
         let rest = &msg[start + 9..];
         if let Some(end) = rest.find('\'') {
             let backend = &rest[..end];
-            return anyhow::anyhow!(
+            return format!(
                 "sandbox execution error: {msg}\n\n\
                  HINT: Check backend status: load @gatemini://backend/{backend}\n\
                  To see all backends: load @gatemini://backends"
@@ -352,7 +403,7 @@ fn enhance_sandbox_error(
                 || var_name == "tool_info"
                 || var_name == "list_tools_meta"
             {
-                return anyhow::anyhow!(
+                return format!(
                     "sandbox execution error: {msg}\n\n\
                      HINT: '{var_name}' is a gatemini meta-tool, NOT a backend. \
                      Meta-tools (search_tools, tool_info, list_tools_meta, call_tool_chain) \
@@ -378,7 +429,7 @@ fn enhance_sandbox_error(
                     .iter()
                     .any(|bn| bn.eq_ignore_ascii_case(var_name))
             {
-                return anyhow::anyhow!(
+                return format!(
                     "sandbox execution error: {msg}\n\n\
                      HINT: '{var_name}' is not defined. If this is a tool name, \
                      call it as `backend_name.{var_name}({{args}})`. \
@@ -388,5 +439,5 @@ fn enhance_sandbox_error(
         }
     }
 
-    anyhow::anyhow!("sandbox execution error: {msg}")
+    format!("sandbox execution error: {msg}")
 }
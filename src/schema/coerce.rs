@@ -0,0 +1,367 @@
+//! Schema-guided repair of common argument mistakes.
+//!
+//! Models often send `"5"` for an integer, `"true"` for a boolean, a bare
+//! value where an array is expected, an array or object serialized into a
+//! string, or `num_results` for a `numResults` property. When a backend
+//! enables `coerce_arguments`, such values are converted to what the input
+//! schema asks for before validation and dispatch. Only unambiguous repairs
+//! are made: a value that already fits its schema is never touched, and a
+//! property is only renamed onto a single schema property that the
+//! arguments do not set yet. Renames also need the object to be wrong as it
+//! is: it fails its schema, or the schema forbids unknown properties.
+
+use serde_json::{Map, Number, Value};
+
+use super::validate::{has_type, matches, types};
+
+/// What was changed to make an argument fit its schema.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepairKind {
+    /// `"5"` → `5`
+    StringToNumber,
+    /// `"true"` → `true`
+    StringToBoolean,
+    /// `5` → `"5"`
+    ToString,
+    /// `"a"` → `["a"]`
+    WrapInArray,
+    /// `"[1, 2]"` → `[1, 2]`
+    ParseJson,
+    /// `num_results` → `numResults`
+    RenameProperty,
+}
+
+impl RepairKind {
+    /// Stable label used for tracker stats and logs.
+    pub fn label(self) -> &'static str {
+        match self {
+            RepairKind::StringToNumber => "string_to_number",
+            RepairKind::StringToBoolean => "string_to_boolean",
+            RepairKind::ToString => "to_string",
+            RepairKind::WrapInArray => "wrap_in_array",
+            RepairKind::ParseJson => "parse_json",
+            RepairKind::RenameProperty => "rename_property",
+        }
+    }
+}
+
+/// One repair, at a property path like those of validation issues.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Repair {
+    pub path: String,
+    pub kind: RepairKind,
+}
+
+/// Repair `arguments` in place to fit `schema`. Returns the repairs made.
+pub fn coerce(schema: &Value, arguments: &mut Value) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    coerce_value(schema, arguments, "", &mut repairs);
+    repairs
+}
+
+fn coerce_value(schema: &Value, value: &mut Value, path: &str, repairs: &mut Vec<Repair>) {
+    if !schema.is_object() || schema.get("$ref").is_some() {
+        return;
+    }
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        for branch in all {
+            coerce_value(branch, value, path, repairs);
+        }
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+            if branches.iter().any(|branch| matches(branch, value)) {
+                return;
+            }
+            // Take the first branch the value can be repaired to fit.
+            for branch in branches {
+                let mut candidate = value.clone();
+                let mut branch_repairs = Vec::new();
+                coerce_value(branch, &mut candidate, path, &mut branch_repairs);
+                if !branch_repairs.is_empty() && matches(branch, &candidate) {
+                    *value = candidate;
+                    repairs.extend(branch_repairs);
+                    return;
+                }
+            }
+            return;
+        }
+    }
+
+    let mut types = types(schema);
+    if types.is_empty() && schema.get("properties").is_some() {
+        types.push("object");
+    }
+    if !types.is_empty()
+        && !types.iter().any(|t| has_type(value, t))
+        && let Some((converted, kind)) = types.iter().find_map(|t| convert(value, t))
+    {
+        *value = converted;
+        repairs.push(Repair {
+            path: if path.is_empty() {
+                "arguments".to_string()
+            } else {
+                path.to_string()
+            },
+            kind,
+        });
+    }
+
+    // A key the schema doesn't name may be a deliberate extra, unless the
+    // schema forbids those or the object doesn't fit as it is.
+    let additional = schema.get("additionalProperties");
+    let rename =
+        value.is_object() && (additional == Some(&Value::Bool(false)) || !matches(schema, value));
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            if let Some(properties) = properties
+                && rename
+            {
+                for (from, to) in renames(object, properties) {
+                    if let Some(member) = object.remove(&from) {
+                        object.insert(to.clone(), member);
+                        repairs.push(Repair {
+                            path: join(path, &from),
+                            kind: RepairKind::RenameProperty,
+                        });
+                    }
+                }
+            }
+            for (key, member) in object.iter_mut() {
+                if let Some(property) = properties.and_then(|p| p.get(key)).or(additional) {
+                    coerce_value(property, member, &join(path, key), repairs);
+                }
+            }
+        }
+        Value::Array(items) => match schema.get("items") {
+            Some(Value::Array(tuple)) => {
+                for (i, (item, item_schema)) in items.iter_mut().zip(tuple).enumerate() {
+                    coerce_value(item_schema, item, &format!("{path}[{i}]"), repairs);
+                }
+            }
+            Some(item_schema) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    coerce_value(item_schema, item, &format!("{path}[{i}]"), repairs);
+                }
+            }
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+/// `value` converted to `json_type`, if that is a repair this layer makes.
+fn convert(value: &Value, json_type: &str) -> Option<(Value, RepairKind)> {
+    match (json_type, value) {
+        ("integer", Value::String(s)) => {
+            let s = s.trim();
+            let n = s.parse::<i64>().ok().map(Number::from).or_else(|| {
+                let f = s.parse::<f64>().ok()?;
+                (f.fract() == 0.0 && f.abs() < i64::MAX as f64).then(|| Number::from(f as i64))
+            })?;
+            Some((Value::Number(n), RepairKind::StringToNumber))
+        }
+        ("number", Value::String(s)) => {
+            let s = s.trim();
+            let n = s
+                .parse::<i64>()
+                .ok()
+                .map(Number::from)
+                .or_else(|| s.parse::<f64>().ok().and_then(Number::from_f64))?;
+            Some((Value::Number(n), RepairKind::StringToNumber))
+        }
+        ("boolean", Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" => Some((Value::Bool(true), RepairKind::StringToBoolean)),
+            "false" => Some((Value::Bool(false), RepairKind::StringToBoolean)),
+            _ => None,
+        },
+        ("string", Value::Number(_) | Value::Bool(_)) => {
+            Some((Value::String(value.to_string()), RepairKind::ToString))
+        }
+        ("array", Value::Null) => None,
+        ("array", _) => Some(
+            value
+                .as_str()
+                .filter(|s| s.trim_start().starts_with('['))
+                .and_then(|s| serde_json::from_str::<Value>(s).ok())
+                .filter(Value::is_array)
+                .map_or_else(
+                    || (Value::Array(vec![value.clone()]), RepairKind::WrapInArray),
+                    |parsed| (parsed, RepairKind::ParseJson),
+                ),
+        ),
+        ("object", Value::String(s)) if s.trim_start().starts_with('{') => {
+            serde_json::from_str::<Value>(s)
+                .ok()
+                .filter(Value::is_object)
+                .map(|parsed| (parsed, RepairKind::ParseJson))
+        }
+        _ => None,
+    }
+}
+
+/// Keys of `object` that are not schema properties but match exactly one
+/// property up to case and `_`/`-` separators, with the property they match.
+fn renames(object: &Map<String, Value>, properties: &Map<String, Value>) -> Vec<(String, String)> {
+    let normalize = |name: &str| -> String {
+        name.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let mut renames: Vec<(String, String)> = Vec::new();
+    for key in object.keys().filter(|key| !properties.contains_key(*key)) {
+        let wanted = normalize(key);
+        let mut candidates = properties
+            .keys()
+            .filter(|property| !object.contains_key(*property) && normalize(property) == wanted);
+        if let (Some(property), None) = (candidates.next(), candidates.next())
+            && !renames.iter().any(|(_, to)| to == property)
+        {
+            renames.push((key.clone(), property.clone()));
+        }
+    }
+    renames
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "numResults": {"type": "integer"},
+                "verbose": {"type": "boolean"},
+                "domains": {"type": "array", "items": {"type": "string"}},
+                "filter": {
+                    "type": "object",
+                    "properties": {"max_age": {"type": "number"}},
+                    "additionalProperties": false
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    fn kinds(repairs: &[Repair]) -> Vec<(&str, &'static str)> {
+        repairs
+            .iter()
+            .map(|r| (r.path.as_str(), r.kind.label()))
+            .collect()
+    }
+
+    #[test]
+    fn test_coerce_common_mistakes() {
+        let mut args = json!({
+            "query": 42,
+            "num_results": "5",
+            "verbose": "TRUE",
+            "domains": "example.com",
+            "filter": "{\"maxAge\": \"1.5\"}"
+        });
+        let repairs = coerce(&schema(), &mut args);
+        assert_eq!(
+            args,
+            json!({
+                "query": "42",
+                "numResults": 5,
+                "verbose": true,
+                "domains": ["example.com"],
+                "filter": {"max_age": 1.5}
+            })
+        );
+        let mut kinds = kinds(&repairs);
+        kinds.sort();
+        assert_eq!(
+            kinds,
+            [
+                ("domains", "wrap_in_array"),
+                ("filter", "parse_json"),
+                ("filter.maxAge", "rename_property"),
+                ("filter.max_age", "string_to_number"),
+                ("numResults", "string_to_number"),
+                ("num_results", "rename_property"),
+                ("query", "to_string"),
+                ("verbose", "string_to_boolean"),
+            ]
+        );
+        assert!(matches(&schema(), &args));
+    }
+
+    #[test]
+    fn test_coerce_leaves_valid_and_ambiguous_values() {
+        let mut args = json!({"query": "rust", "domains": "[draft]"});
+        coerce(&schema(), &mut args);
+        assert_eq!(args["domains"], json!(["[draft]"]));
+
+        let mut args = json!({"query": "rust", "numResults": 3, "domains": ["a", "b"]});
+        let before = args.clone();
+        assert!(coerce(&schema(), &mut args).is_empty());
+        assert_eq!(args, before);
+
+        // Not a number, not a boolean: left for validation to report.
+        let mut args = json!({"query": "rust", "numResults": "five", "verbose": "yes"});
+        assert!(coerce(&schema(), &mut args).is_empty());
+
+        // Both spellings present: nothing is renamed.
+        let mut args = json!({"query": "rust", "numResults": 1, "num_results": 2});
+        assert!(coerce(&schema(), &mut args).is_empty());
+    }
+
+    #[test]
+    fn test_coerce_renames_only_when_the_object_is_wrong() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"numResults": {"type": "integer"}}
+        });
+        // Fits as it is: the extra key may be meant for something else.
+        let mut args = json!({"num_results": 5});
+        assert!(coerce(&schema, &mut args).is_empty());
+        assert_eq!(args, json!({"num_results": 5}));
+
+        // Unknown properties forbidden: renamed.
+        let mut strict = schema.clone();
+        strict["additionalProperties"] = json!(false);
+        let mut args = json!({"num_results": 5});
+        let repairs = coerce(&strict, &mut args);
+        assert_eq!(args, json!({"numResults": 5}));
+        assert_eq!(kinds(&repairs), [("num_results", "rename_property")]);
+
+        // Fails its schema: renamed.
+        let mut required = schema.clone();
+        required["required"] = json!(["numResults"]);
+        let mut args = json!({"num_results": 5});
+        coerce(&required, &mut args);
+        assert_eq!(args, json!({"numResults": 5}));
+    }
+
+    #[test]
+    fn test_coerce_picks_repairable_branch() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"id": {"anyOf": [{"type": "integer"}, {"type": "null"}]}}
+        });
+        let mut args = json!({"id": "7"});
+        let repairs = coerce(&schema, &mut args);
+        assert_eq!(args, json!({"id": 7}));
+        assert_eq!(kinds(&repairs), [("id", "string_to_number")]);
+
+        let mut args = json!("{\"id\": null}");
+        let repairs = coerce(&schema, &mut args);
+        assert_eq!(args, json!({"id": null}));
+        assert_eq!(kinds(&repairs), [("arguments", "parse_json")]);
+    }
+}
@@ -0,0 +1,22 @@
+//! Tool input schemas: normalization, argument validation, coercion, compact
+//! signatures and TypeScript declarations.
+//!
+//! Backends describe their tools' arguments with JSON Schema. The gateway
+//! shows models a [`normalize`]d form of each schema, with refs inlined and
+//! noise dropped, or just a short [`signature`] of its parameters. Before
+//! dispatch it can repair common mistakes in the arguments ([`coerce`]) and
+//! checks them against the schema ([`validate`]); the signature goes into
+//! the error, so a model that guessed wrong can fix its call without reading
+//! the full schema. For code written against the sandbox, [`typescript`]
+//! turns the schemas into `.d.ts` declarations.
+
+pub mod coerce;
+pub mod normalize;
+pub mod signature;
+pub mod typescript;
+pub mod validate;
+
+pub use coerce::coerce;
+pub use normalize::normalize;
+pub use signature::signature;
+pub use validate::validate;
@@ -0,0 +1,296 @@
+//! Normalization of tool schemas for model-facing output.
+//!
+//! Schemas generated from code tend to put every type under `$defs` and
+//! point at it with `$ref`, compose objects with `allOf`, and carry keywords
+//! meant for other tools (`$schema`, `title`, `examples`, ...). [`normalize`]
+//! inlines local refs, merges `allOf` branches into their parent where that
+//! keeps the same constraints, and drops those keywords, so a model reads the
+//! same parameters in far fewer tokens.
+//!
+//! A recursive ref is expanded once; the inner occurrence stays a `$ref`
+//! (rendered by name in signatures, skipped by validation).
+
+use serde_json::{Map, Value};
+
+/// Keywords with no bearing on what arguments a tool accepts.
+const NOISE: &[&str] = &[
+    "$schema",
+    "$id",
+    "$anchor",
+    "$comment",
+    "$defs",
+    "definitions",
+    "title",
+    "examples",
+];
+
+/// Refs inlined per schema before the rest are left as `$ref`s, so schemas
+/// whose definitions reference each other many times stay small.
+const MAX_EXPANSIONS: usize = 256;
+
+struct Refs<'a> {
+    root: &'a Value,
+    /// Refs being expanded, innermost last.
+    stack: Vec<String>,
+    expansions: usize,
+}
+
+/// `schema` with local refs inlined, `allOf` flattened and noise dropped.
+pub fn normalize(schema: &Value) -> Value {
+    let mut refs = Refs {
+        root: schema,
+        stack: Vec::new(),
+        expansions: 0,
+    };
+    walk(schema, &mut refs)
+}
+
+fn walk(schema: &Value, refs: &mut Refs) -> Value {
+    let Some(object) = schema.as_object() else {
+        return schema.clone();
+    };
+    if let Some(reference) = object.get("$ref").and_then(Value::as_str)
+        && let Some(target) = resolve(refs.root, reference)
+        && !refs.stack.iter().any(|r| r == reference)
+        && refs.expansions < MAX_EXPANSIONS
+    {
+        refs.expansions += 1;
+        refs.stack.push(reference.to_string());
+        let mut resolved = walk(target, refs);
+        refs.stack.pop();
+        // Keywords next to the ref (usually a description) refine the target.
+        if let Value::Object(ref mut resolved) = resolved {
+            for (key, value) in object {
+                if key != "$ref" && !NOISE.contains(&key.as_str()) {
+                    resolved.insert(key.clone(), walk_keyword(key, value, refs));
+                }
+            }
+        }
+        return resolved;
+    }
+
+    let mut out = Map::new();
+    for (key, value) in object {
+        if NOISE.contains(&key.as_str()) {
+            continue;
+        }
+        let value = walk_keyword(key, value, refs);
+        // `true` is the default and says nothing.
+        if key == "additionalProperties" && value == Value::Bool(true) {
+            continue;
+        }
+        out.insert(key.clone(), value);
+    }
+    if let Some(Value::Array(branches)) = out.remove("allOf") {
+        let rest: Vec<Value> = branches
+            .into_iter()
+            .filter_map(|branch| merge(&mut out, branch))
+            .collect();
+        if !rest.is_empty() {
+            out.insert("allOf".to_string(), Value::Array(rest));
+        }
+    }
+    Value::Object(out)
+}
+
+/// Normalize the value of one keyword: subschemas are walked, everything
+/// else (`enum`, `const`, `default`, ...) is data and copied as is.
+fn walk_keyword(key: &str, value: &Value, refs: &mut Refs) -> Value {
+    match (key, value) {
+        ("properties" | "patternProperties", Value::Object(properties)) => Value::Object(
+            properties
+                .iter()
+                .map(|(name, schema)| (name.clone(), walk(schema, refs)))
+                .collect(),
+        ),
+        ("allOf" | "anyOf" | "oneOf" | "prefixItems" | "items", Value::Array(schemas)) => {
+            Value::Array(schemas.iter().map(|schema| walk(schema, refs)).collect())
+        }
+        (
+            "items"
+            | "additionalProperties"
+            | "not"
+            | "if"
+            | "then"
+            | "else"
+            | "contains"
+            | "propertyNames",
+            _,
+        ) => walk(value, refs),
+        _ => value.clone(),
+    }
+}
+
+/// Merge an `allOf` branch into its parent. Returns the branch if merging
+/// would change what the schema accepts (conflicting keywords, or a ref).
+fn merge(parent: &mut Map<String, Value>, branch: Value) -> Option<Value> {
+    let Value::Object(object) = branch else {
+        return Some(branch);
+    };
+    let compatible = !object.contains_key("$ref")
+        && object.iter().all(|(key, value)| match key.as_str() {
+            "description" | "required" => true,
+            "properties" => match (parent.get(key), value) {
+                (Some(Value::Object(ours)), Value::Object(theirs)) => theirs
+                    .iter()
+                    .all(|(name, schema)| ours.get(name).is_none_or(|s| s == schema)),
+                (None, _) => true,
+                _ => false,
+            },
+            _ => parent.get(key).is_none_or(|ours| ours == value),
+        });
+    if !compatible {
+        return Some(Value::Object(object));
+    }
+
+    for (key, value) in object {
+        match (key.as_str(), parent.get_mut(&key), value) {
+            ("properties", Some(Value::Object(ours)), Value::Object(theirs)) => {
+                for (name, schema) in theirs {
+                    ours.entry(name).or_insert(schema);
+                }
+            }
+            ("required", Some(Value::Array(ours)), Value::Array(theirs)) => {
+                for name in theirs {
+                    if !ours.contains(&name) {
+                        ours.push(name);
+                    }
+                }
+            }
+            // The parent's own description wins.
+            (_, Some(_), _) => {}
+            (_, None, value) => {
+                parent.insert(key, value);
+            }
+        }
+    }
+    None
+}
+
+/// Target of a local ref (`#`, `#/$defs/Name`, ...). Remote refs are not fetched.
+fn resolve<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        Some(root)
+    } else {
+        root.pointer(pointer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::signature::signature;
+    use serde_json::json;
+
+    #[test]
+    fn test_inlines_refs_and_drops_noise() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "SearchArgs",
+            "type": "object",
+            "properties": {
+                "query": {"title": "Query", "type": "string", "examples": ["rust"]},
+                "filter": {"$ref": "#/$defs/Filter", "description": "Narrow results"},
+                "sort": {"$ref": "#/definitions/Sort"},
+                "remote": {"$ref": "https://example.com/schema.json"}
+            },
+            "required": ["query"],
+            "additionalProperties": true,
+            "$defs": {
+                "Filter": {
+                    "title": "Filter",
+                    "type": "object",
+                    "properties": {"since": {"type": "string", "format": "date"}},
+                    "additionalProperties": false
+                }
+            },
+            "definitions": {"Sort": {"enum": ["date", "relevance"], "default": "relevance"}}
+        });
+        assert_eq!(
+            normalize(&schema),
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "filter": {
+                        "type": "object",
+                        "properties": {"since": {"type": "string", "format": "date"}},
+                        "additionalProperties": false,
+                        "description": "Narrow results"
+                    },
+                    "sort": {"enum": ["date", "relevance"], "default": "relevance"},
+                    "remote": {"$ref": "https://example.com/schema.json"}
+                },
+                "required": ["query"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_flattens_all_of() {
+        let schema = json!({
+            "allOf": [
+                {"$ref": "#/$defs/Paging"},
+                {
+                    "type": "object",
+                    "properties": {"query": {"type": "string"}},
+                    "required": ["query"]
+                },
+                {"properties": {"limit": {"type": "string"}}}
+            ],
+            "$defs": {
+                "Paging": {
+                    "type": "object",
+                    "properties": {"limit": {"type": "integer"}, "cursor": {"type": "string"}},
+                    "required": ["limit"]
+                }
+            }
+        });
+        let normalized = normalize(&schema);
+        assert_eq!(
+            normalized,
+            json!({
+                "type": "object",
+                "properties": {
+                    "limit": {"type": "integer"},
+                    "cursor": {"type": "string"},
+                    "query": {"type": "string"}
+                },
+                "required": ["limit", "query"],
+                // Conflicts with the merged `limit`: kept as a constraint.
+                "allOf": [{"properties": {"limit": {"type": "string"}}}]
+            })
+        );
+        assert_eq!(
+            signature(&normalized),
+            "{limit: integer, query: string, cursor?: string}"
+        );
+    }
+
+    #[test]
+    fn test_recursive_ref_expanded_once() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"root": {"$ref": "#/$defs/Node"}},
+            "$defs": {
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string"},
+                        "parts": {"type": "array", "items": {"$ref": "#/$defs/Node"}}
+                    }
+                }
+            }
+        });
+        let normalized = normalize(&schema);
+        assert_eq!(
+            normalized["properties"]["root"]["properties"]["parts"]["items"],
+            json!({"$ref": "#/$defs/Node"})
+        );
+        assert_eq!(
+            signature(&normalized),
+            "{root?: {name?: string, parts?: Node[]}}"
+        );
+    }
+}
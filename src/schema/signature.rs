@@ -0,0 +1,160 @@
+//! Compact, TypeScript-like rendering of an input schema.
+//!
+//! `{"type": "object", "properties": {"query": {"type": "string"}, "limit":
+//! {"type": "integer"}}, "required": ["query"]}` renders as
+//! `{query: string, limit?: integer}`: required parameters first, optional
+//! ones marked with `?`, enums as literal unions and arrays as `T[]`.
+
+use serde_json::{Map, Value};
+
+/// Nested objects deeper than this render as `object`.
+const MAX_DEPTH: usize = 2;
+
+/// Enum values listed before the rest is elided.
+const MAX_ENUM_VALUES: usize = 6;
+
+/// Signature of the parameters an input schema accepts.
+pub fn signature(schema: &Value) -> String {
+    if let Some(object) = schema.as_object()
+        && let Some(properties) = object.get("properties").and_then(Value::as_object)
+    {
+        return object_type(object, properties, 0);
+    }
+    type_name(schema, 0)
+}
+
+/// Type of a value matching `schema`, e.g. `string`, `"a" | "b"` or `integer[]`.
+pub fn type_name(schema: &Value, depth: usize) -> String {
+    let Some(schema) = schema.as_object() else {
+        return "any".to_string();
+    };
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        let mut names: Vec<String> = values
+            .iter()
+            .take(MAX_ENUM_VALUES)
+            .map(Value::to_string)
+            .collect();
+        if values.len() > MAX_ENUM_VALUES {
+            names.push("...".to_string());
+        }
+        return names.join(" | ");
+    }
+    if let Some(value) = schema.get("const") {
+        return value.to_string();
+    }
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit('/')
+            .next()
+            .unwrap_or(reference)
+            .to_string();
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+            return branches
+                .iter()
+                .map(|branch| type_name(branch, depth))
+                .collect::<Vec<_>>()
+                .join(" | ");
+        }
+    }
+
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ if schema.contains_key("properties") => vec!["object"],
+        _ => return "any".to_string(),
+    };
+    types
+        .into_iter()
+        .map(|t| match t {
+            "array" => match schema.get("items") {
+                Some(items) => {
+                    let item = type_name(items, depth + 1);
+                    if item.contains(' ') && !item.starts_with('{') {
+                        format!("({item})[]")
+                    } else {
+                        format!("{item}[]")
+                    }
+                }
+                None => "any[]".to_string(),
+            },
+            "object" => match schema.get("properties").and_then(Value::as_object) {
+                Some(properties) if depth < MAX_DEPTH => object_type(schema, properties, depth + 1),
+                _ => "object".to_string(),
+            },
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+fn object_type(
+    schema: &Map<String, Value>,
+    properties: &Map<String, Value>,
+    depth: usize,
+) -> String {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let (required_props, optional_props): (Vec<_>, Vec<_>) = properties
+        .iter()
+        .partition(|(name, _)| required.contains(&name.as_str()));
+
+    let fields: Vec<String> = required_props
+        .into_iter()
+        .map(|(name, prop)| format!("{name}: {}", type_name(prop, depth)))
+        .chain(
+            optional_props
+                .into_iter()
+                .map(|(name, prop)| format!("{name}?: {}", type_name(prop, depth))),
+        )
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_signature_required_first() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "limit": {"type": "integer"},
+                "mode": {"enum": ["fast", "deep"]},
+                "query": {"type": "string"},
+                "tags": {"type": "array", "items": {"type": "string"}},
+            },
+            "required": ["query"]
+        });
+        assert_eq!(
+            signature(&schema),
+            r#"{query: string, limit?: integer, mode?: "fast" | "deep", tags?: string[]}"#
+        );
+        assert_eq!(signature(&json!({"type": "object"})), "object");
+    }
+
+    #[test]
+    fn test_type_name_nesting() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "filter": {
+                    "type": "object",
+                    "properties": {"since": {"type": ["string", "null"]}},
+                    "required": ["since"]
+                },
+                "ids": {"type": "array", "items": {"anyOf": [{"type": "string"}, {"type": "integer"}]}},
+            }
+        });
+        assert_eq!(
+            signature(&schema),
+            "{filter?: {since: string | null}, ids?: (string | integer)[]}"
+        );
+    }
+}
@@ -0,0 +1,381 @@
+//! TypeScript declarations for the sandbox's tool surface.
+//!
+//! In `call_tool_chain` every backend is an object of async functions
+//! (`await exa.web_search({...})`). [`declarations`] describes them as a
+//! `.d.ts` file: a namespace per backend, an `...Args` type per tool from its
+//! normalized input schema and a `...Result` type from its output schema.
+//! Tools without an output schema return `Promise<any>`, since their result
+//! is whatever text or JSON the backend sends.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value};
+
+use crate::registry::ToolEntry;
+use crate::sandbox::bridge::sanitize_identifier;
+
+use super::normalize;
+
+/// `.d.ts` declarations for `tools`, grouped by backend in name order.
+pub fn declarations(tools: &[ToolEntry]) -> String {
+    let mut by_backend: BTreeMap<&str, BTreeMap<&str, &ToolEntry>> = BTreeMap::new();
+    for tool in tools {
+        let name = if tool.original_name.is_empty() {
+            &tool.name
+        } else {
+            &tool.original_name
+        };
+        by_backend
+            .entry(&tool.backend_name)
+            .or_default()
+            .entry(name)
+            .or_insert(tool);
+    }
+
+    let mut out =
+        String::from("// Tools callable in call_tool_chain, generated from their schemas.\n");
+    for (backend, tools) in by_backend {
+        out.push_str(&format!(
+            "\ndeclare namespace {} {{\n",
+            sanitize_identifier(backend)
+        ));
+        for (i, (name, tool)) in tools.into_iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            tool_declarations(name, tool, &mut out);
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn tool_declarations(name: &str, tool: &ToolEntry, out: &mut String) {
+    let function = sanitize_identifier(name);
+    let pascal = pascal_case(&function);
+
+    let input = normalize(&tool.input_schema);
+    let args = format!("{pascal}Args");
+    named_type(&args, &input, out);
+    // Calls may leave out the argument object when nothing is required.
+    let optional = input
+        .get("required")
+        .and_then(Value::as_array)
+        .is_none_or(Vec::is_empty);
+
+    let result = match &tool.output_schema {
+        Some(schema) => {
+            let result = format!("{pascal}Result");
+            named_type(&result, &normalize(schema), out);
+            result
+        }
+        None => "any".to_string(),
+    };
+
+    let description = tool.description.split("\n\n").next().unwrap_or_default();
+    doc_comment(description, 1, out);
+    out.push_str(&format!(
+        "  function {function}(args{}: {args}): Promise<{result}>;\n",
+        if optional { "?" } else { "" }
+    ));
+}
+
+/// `interface Name {...}` for a plain object schema, `type Name = ...` otherwise.
+fn named_type(name: &str, schema: &Value, out: &mut String) {
+    let is_plain_object = schema.as_object().is_some_and(|object| {
+        object.get("properties").is_some_and(Value::is_object)
+            && !["enum", "const", "anyOf", "oneOf", "allOf"]
+                .iter()
+                .any(|keyword| object.contains_key(*keyword))
+    });
+    if is_plain_object {
+        out.push_str(&format!("  interface {name} {}\n", ts_type(schema, 1)));
+    } else {
+        out.push_str(&format!("  type {name} = {};\n", ts_type(schema, 1)));
+    }
+}
+
+/// TypeScript type of a value matching `schema`, with nested object types
+/// indented `indent` levels.
+pub fn ts_type(schema: &Value, indent: usize) -> String {
+    let Some(object) = schema.as_object() else {
+        return match schema {
+            Value::Bool(false) => "never",
+            _ => "unknown",
+        }
+        .to_string();
+    };
+    if let Some(values) = object.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>()
+            .join(" | ");
+    }
+    if let Some(value) = object.get("const") {
+        return value.to_string();
+    }
+    // Refs left after normalization are remote or recursive.
+    if object.contains_key("$ref") {
+        return "unknown".to_string();
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(branches) = object.get(keyword).and_then(Value::as_array) {
+            return union(branches.iter().map(|branch| ts_type(branch, indent)));
+        }
+    }
+
+    let mut types: Vec<&str> = match object.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if types.is_empty() {
+        if object.contains_key("properties") {
+            types.push("object");
+        } else if object.contains_key("items") {
+            types.push("array");
+        }
+    }
+    let base = if types.is_empty() {
+        "unknown".to_string()
+    } else {
+        union(types.into_iter().map(|t| match t {
+            "string" | "boolean" | "null" => t.to_string(),
+            "integer" | "number" => "number".to_string(),
+            "array" => array_type(object, indent),
+            "object" => object_type(object, indent),
+            _ => "unknown".to_string(),
+        }))
+    };
+
+    match object.get("allOf").and_then(Value::as_array) {
+        Some(branches) if !branches.is_empty() => {
+            let parts: Vec<String> = std::iter::once(base)
+                .filter(|base| base != "unknown")
+                .chain(branches.iter().map(|branch| ts_type(branch, indent)))
+                .map(|part| parenthesize(&part))
+                .collect();
+            parts.join(" & ")
+        }
+        _ => base,
+    }
+}
+
+fn array_type(schema: &Map<String, Value>, indent: usize) -> String {
+    match schema.get("items") {
+        Some(Value::Array(tuple)) => format!(
+            "[{}]",
+            tuple
+                .iter()
+                .map(|item| ts_type(item, indent))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Some(items) => format!("{}[]", parenthesize(&ts_type(items, indent))),
+        None => "unknown[]".to_string(),
+    }
+}
+
+fn object_type(schema: &Map<String, Value>, indent: usize) -> String {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return match schema.get("additionalProperties") {
+            Some(Value::Bool(false)) => "Record<string, never>".to_string(),
+            Some(extra @ Value::Object(_)) => {
+                format!("Record<string, {}>", ts_type(extra, indent))
+            }
+            _ => "Record<string, unknown>".to_string(),
+        };
+    };
+    if properties.is_empty() {
+        return "{}".to_string();
+    }
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let pad = "  ".repeat(indent + 1);
+    let mut out = String::from("{\n");
+    for (name, property) in properties {
+        if let Some(description) = property.get("description").and_then(Value::as_str) {
+            doc_comment(description, indent + 1, &mut out);
+        }
+        let optional = if required.contains(&name.as_str()) {
+            ""
+        } else {
+            "?"
+        };
+        out.push_str(&format!(
+            "{pad}{}{optional}: {};\n",
+            property_name(name),
+            ts_type(property, indent + 1)
+        ));
+    }
+    out.push_str(&"  ".repeat(indent));
+    out.push('}');
+    out
+}
+
+fn union(types: impl Iterator<Item = String>) -> String {
+    let mut seen: Vec<String> = Vec::new();
+    for t in types {
+        if !seen.contains(&t) {
+            seen.push(t);
+        }
+    }
+    seen.join(" | ")
+}
+
+/// Wrap a union in parentheses so it can be suffixed with `[]` or joined with `&`.
+fn parenthesize(ty: &str) -> String {
+    if ty.contains(" | ") && !ty.starts_with('{') {
+        format!("({ty})")
+    } else {
+        ty.to_string()
+    }
+}
+
+/// Property name as written in a type: bare if it is an identifier.
+fn property_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    if is_identifier {
+        name.to_string()
+    } else {
+        Value::String(name.to_string()).to_string()
+    }
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn doc_comment(text: &str, indent: usize, out: &mut String) {
+    let text = text.trim();
+    if text.is_empty() {
+        return;
+    }
+    let pad = "  ".repeat(indent);
+    let text = text.replace("*/", "*\\/");
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    if let [line] = lines.as_slice() {
+        out.push_str(&format!("{pad}/** {line} */\n"));
+    } else {
+        out.push_str(&format!("{pad}/**\n"));
+        for line in lines {
+            if line.is_empty() {
+                out.push_str(&format!("{pad} *\n"));
+            } else {
+                out.push_str(&format!("{pad} * {line}\n"));
+            }
+        }
+        out.push_str(&format!("{pad} */\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(backend: &str, name: &str, input: Value, output: Option<Value>) -> ToolEntry {
+        ToolEntry {
+            name: format!("{backend}.{name}"),
+            original_name: name.to_string(),
+            description: format!("Run {name}.\n\nLonger details."),
+            backend_name: backend.to_string(),
+            input_schema: input,
+            tags: Vec::new(),
+            output_schema: output,
+            annotations: None,
+        }
+    }
+
+    #[test]
+    fn test_declarations() {
+        let search = entry(
+            "my-search",
+            "web_search",
+            json!({
+                "type": "object",
+                "properties": {
+                    "domains": {"type": "array", "items": {"type": "string"}},
+                    "max-age": {"type": ["integer", "null"]},
+                    "mode": {"$ref": "#/$defs/Mode"},
+                    "query": {"type": "string", "description": "What to look for"}
+                },
+                "required": ["query"],
+                "$defs": {"Mode": {"enum": ["fast", "deep"]}}
+            }),
+            Some(json!({
+                "type": "object",
+                "properties": {"urls": {"type": "array", "items": {"type": "string"}}},
+                "required": ["urls"]
+            })),
+        );
+        let status = entry("my-search", "status", json!({"type": "object"}), None);
+        assert_eq!(
+            declarations(&[search, status]),
+            "// Tools callable in call_tool_chain, generated from their schemas.
+
+declare namespace my_search {
+  type StatusArgs = Record<string, unknown>;
+  /** Run status. */
+  function status(args?: StatusArgs): Promise<any>;
+
+  interface WebSearchArgs {
+    domains?: string[];
+    \"max-age\"?: number | null;
+    mode?: \"fast\" | \"deep\";
+    /** What to look for */
+    query: string;
+  }
+  interface WebSearchResult {
+    urls: string[];
+  }
+  /** Run web_search. */
+  function web_search(args: WebSearchArgs): Promise<WebSearchResult>;
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_ts_type_combinators() {
+        assert_eq!(
+            ts_type(
+                &json!({"type": "array", "items": {"anyOf": [{"type": "string"}, {"type": "integer"}]}}),
+                0
+            ),
+            "(string | number)[]"
+        );
+        assert_eq!(
+            ts_type(
+                &json!({"allOf": [{"$ref": "https://example.com/a"}, {"type": "object", "additionalProperties": {"type": "boolean"}}]}),
+                0
+            ),
+            "unknown & Record<string, boolean>"
+        );
+        assert_eq!(
+            ts_type(
+                &json!({"type": "array", "items": [{"type": "string"}, {"const": 1}]}),
+                0
+            ),
+            "[string, 1]"
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Pre-dispatch validation of tool arguments against `input_schema`.
+//!
+//! Covers the parts of JSON Schema that catch the usual mistakes of a model
+//! calling a tool: `type`, `required`, `properties`, `patternProperties` and
+//! `additionalProperties: false`, `enum`/`const`, `items`, and the
+//! `allOf`/`anyOf`/`oneOf` combinators. Other keywords (`format`,
+//! `minimum`, `pattern`, ...) and unresolved `$ref`s are left to the backend.
+//!
+//! `oneOf` is deliberately checked like `anyOf`: a value that fits several
+//! branches passes. Generated tool schemas often have overlapping branches
+//! (a string enum next to a plain string, say), and rejecting those calls
+//! would refuse arguments the backend accepts.
+//!
+//! `patternProperties` regexes are compiled once and cached by pattern, since
+//! the same schemas are checked on every call.
+
+use std::fmt;
+use std::sync::LazyLock;
+
+use dashmap::DashMap;
+use regex::Regex;
+use serde_json::Value;
+
+use super::signature::{signature, type_name};
+
+/// Compiled `patternProperties` keys, `None` for those that don't compile.
+static PATTERNS: LazyLock<DashMap<String, Option<Regex>>> = LazyLock::new(DashMap::new);
+
+/// Past this many distinct patterns, new ones are compiled on each use.
+const MAX_CACHED_PATTERNS: usize = 1024;
+
+/// One way the arguments do not fit the schema. `path` is the property path
+/// from the arguments object, e.g. `filter.since` or `ids[2]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    Missing {
+        path: String,
+    },
+    Unknown {
+        path: String,
+    },
+    Type {
+        path: String,
+        expected: String,
+        got: &'static str,
+    },
+    NotAllowed {
+        path: String,
+        expected: String,
+        got: String,
+    },
+}
+
+/// Everything wrong with a call's arguments, with the signature the tool expects.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArgumentErrors {
+    pub issues: Vec<Issue>,
+    pub expected: String,
+}
+
+impl Issue {
+    /// Path of the argument the issue is about (`arguments` for the root).
+    pub fn path(&self) -> &str {
+        match self {
+            Issue::Missing { path }
+            | Issue::Unknown { path }
+            | Issue::Type { path, .. }
+            | Issue::NotAllowed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Missing { path } => write!(f, "missing required property '{path}'"),
+            Issue::Unknown { path } => write!(f, "unknown property '{path}'"),
+            Issue::Type {
+                path,
+                expected,
+                got,
+            } => write!(f, "'{path}' should be {expected}, got {got}"),
+            Issue::NotAllowed {
+                path,
+                expected,
+                got,
+            } => write!(f, "'{path}' should be one of {expected}, got {got}"),
+        }
+    }
+}
+
+impl fmt::Display for ArgumentErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "- {issue}")?;
+        }
+        write!(f, "expected parameters: {}", self.expected)
+    }
+}
+
+/// Check `arguments` (`None` meaning no arguments) against `schema`.
+pub fn validate(schema: &Value, arguments: Option<&Value>) -> Result<(), ArgumentErrors> {
+    let empty = Value::Object(Default::default());
+    let arguments = match arguments {
+        None | Some(Value::Null) => &empty,
+        Some(value) => value,
+    };
+    let mut issues = Vec::new();
+    check(schema, arguments, "", &mut issues);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(ArgumentErrors {
+            issues,
+            expected: signature(schema),
+        })
+    }
+}
+
+/// The compiled regex for a `patternProperties` key, from the cache if seen.
+fn pattern(source: &str) -> Option<Regex> {
+    if let Some(regex) = PATTERNS.get(source) {
+        return regex.clone();
+    }
+    let regex = Regex::new(source).ok();
+    if PATTERNS.len() < MAX_CACHED_PATTERNS {
+        PATTERNS.insert(source.to_string(), regex.clone());
+    }
+    regex
+}
+
+/// Whether `value` fits `schema`.
+pub fn matches(schema: &Value, value: &Value) -> bool {
+    let mut issues = Vec::new();
+    check(schema, value, "", &mut issues);
+    issues.is_empty()
+}
+
+fn check(schema: &Value, value: &Value, path: &str, issues: &mut Vec<Issue>) {
+    if !schema.is_object() || schema.get("$ref").is_some() {
+        return;
+    }
+    let at = || {
+        if path.is_empty() {
+            "arguments".to_string()
+        } else {
+            path.to_string()
+        }
+    };
+
+    if let Some(all) = schema.get("allOf").and_then(Value::as_array) {
+        for branch in all {
+            check(branch, value, path, issues);
+        }
+    }
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array)
+            && !branches.is_empty()
+            && !branches.iter().any(|branch| matches(branch, value))
+        {
+            issues.push(Issue::Type {
+                path: at(),
+                expected: type_name(schema, 1),
+                got: json_type(value),
+            });
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        issues.push(Issue::NotAllowed {
+            path: at(),
+            expected: type_name(schema, 1),
+            got: value.to_string(),
+        });
+        return;
+    }
+    if let Some(constant) = schema.get("const")
+        && constant != value
+    {
+        issues.push(Issue::NotAllowed {
+            path: at(),
+            expected: constant.to_string(),
+            got: value.to_string(),
+        });
+        return;
+    }
+
+    let types = types(schema);
+    if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+        issues.push(Issue::Type {
+            path: at(),
+            expected: types.join(" | "),
+            got: json_type(value),
+        });
+        return;
+    }
+
+    match value {
+        Value::Object(object) => {
+            let join = |key: &str| {
+                if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{path}.{key}")
+                }
+            };
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !object.contains_key(name) {
+                        issues.push(Issue::Missing { path: join(name) });
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let patterns = schema.get("patternProperties").and_then(Value::as_object);
+            let patterns: Vec<(Option<Regex>, &Value)> = patterns
+                .into_iter()
+                .flatten()
+                .map(|(source, schema)| (pattern(source), schema))
+                .collect();
+            // A pattern we can't compile might match any key.
+            let additional = schema
+                .get("additionalProperties")
+                .filter(|_| patterns.iter().all(|(regex, _)| regex.is_some()));
+            for (key, member) in object {
+                let property = properties.and_then(|p| p.get(key));
+                if let Some(property) = property {
+                    check(property, member, &join(key), issues);
+                }
+                let mut matched = false;
+                for (regex, pattern_schema) in &patterns {
+                    if let Some(regex) = regex
+                        && regex.is_match(key)
+                    {
+                        matched = true;
+                        check(pattern_schema, member, &join(key), issues);
+                    }
+                }
+                if property.is_some() || matched {
+                    continue;
+                }
+                match additional {
+                    Some(Value::Bool(false)) => issues.push(Issue::Unknown { path: join(key) }),
+                    Some(extra) => check(extra, member, &join(key), issues),
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => match schema.get("items") {
+            Some(Value::Array(tuple)) => {
+                for (i, (item, item_schema)) in items.iter().zip(tuple).enumerate() {
+                    check(item_schema, item, &format!("{path}[{i}]"), issues);
+                }
+            }
+            Some(item_schema) => {
+                for (i, item) in items.iter().enumerate() {
+                    check(item_schema, item, &format!("{path}[{i}]"), issues);
+                }
+            }
+            None => {}
+        },
+        _ => {}
+    }
+}
+
+/// Type names listed by a schema's `type`, empty if it has none.
+pub fn types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn has_type(value: &Value, json_type: &str) -> bool {
+    match json_type {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        // Unknown type names are not ours to reject.
+        _ => true,
+    }
+}
+
+/// JSON type of a value, as named in schemas.
+pub fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "limit": {"type": "integer"},
+                "mode": {"enum": ["fast", "deep"]},
+                "filter": {
+                    "type": "object",
+                    "properties": {"since": {"type": "string"}},
+                    "required": ["since"],
+                    "additionalProperties": false
+                },
+                "ids": {"type": "array", "items": {"type": "integer"}}
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn test_valid_arguments() {
+        let args = json!({"query": "rust", "limit": 5, "mode": "deep", "ids": [1, 2.0]});
+        assert_eq!(validate(&schema(), Some(&args)), Ok(()));
+        // No schema constraints: anything goes.
+        assert_eq!(validate(&json!({}), Some(&json!({"x": 1}))), Ok(()));
+        assert_eq!(validate(&json!({"type": "object"}), None), Ok(()));
+    }
+
+    #[test]
+    fn test_reports_every_issue() {
+        let args = json!({
+            "querry": "rust",
+            "limit": "5",
+            "mode": "slow",
+            "filter": {"until": "today"},
+            "ids": [1, "two"]
+        });
+        let errors = validate(&schema(), Some(&args)).unwrap_err();
+        let mut messages: Vec<String> = errors.issues.iter().map(ToString::to_string).collect();
+        messages.sort();
+        assert_eq!(
+            messages,
+            [
+                "'ids[1]' should be integer, got string",
+                "'limit' should be integer, got string",
+                r#"'mode' should be one of "fast" | "deep", got "slow""#,
+                "missing required property 'filter.since'",
+                "missing required property 'query'",
+                "unknown property 'filter.until'",
+                "unknown property 'querry'",
+            ]
+        );
+        assert!(errors.to_string().ends_with(
+            "expected parameters: {query: string, filter?: {since: string}, \
+             ids?: integer[], limit?: integer, mode?: \"fast\" | \"deep\"}"
+        ));
+    }
+
+    #[test]
+    fn test_pattern_properties_are_not_unknown() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"name": {"type": "string"}},
+            "patternProperties": {"^x-": {"type": "string"}},
+            "additionalProperties": false
+        });
+        let args = json!({"name": "a", "x-trace": "1"});
+        assert_eq!(validate(&schema, Some(&args)), Ok(()));
+        // Compiled once, then reused from the cache.
+        assert!(PATTERNS.get("^x-").is_some_and(|regex| regex.is_some()));
+
+        let errors = validate(&schema, Some(&json!({"x-trace": 1, "other": true}))).unwrap_err();
+        let mut messages: Vec<String> = errors.issues.iter().map(ToString::to_string).collect();
+        messages.sort();
+        assert_eq!(
+            messages,
+            [
+                "'x-trace' should be string, got integer",
+                "unknown property 'other'",
+            ]
+        );
+
+        // An invalid pattern could match anything, so no key is unknown.
+        let schema = json!({
+            "type": "object",
+            "patternProperties": {"(": {}},
+            "additionalProperties": false
+        });
+        assert_eq!(validate(&schema, Some(&json!({"other": true}))), Ok(()));
+        assert!(PATTERNS.get("(").is_some_and(|regex| regex.is_none()));
+        assert_eq!(validate(&schema, Some(&json!({"other": true}))), Ok(()));
+    }
+
+    #[test]
+    fn test_combinators_and_non_object_arguments() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "id": {"anyOf": [{"type": "string"}, {"type": "integer"}]},
+                "$ref_prop": {"$ref": "#/$defs/Thing"}
+            }
+        });
+        assert_eq!(validate(&schema, Some(&json!({"id": 3}))), Ok(()));
+        assert_eq!(
+            validate(&schema, Some(&json!({"$ref_prop": [1]}))),
+            Ok(()),
+            "unresolved refs are left to the backend"
+        );
+        let errors = validate(&schema, Some(&json!({"id": true}))).unwrap_err();
+        assert_eq!(
+            errors.issues,
+            [Issue::Type {
+                path: "id".to_string(),
+                expected: "string | integer".to_string(),
+                got: "boolean"
+            }]
+        );
+
+        let errors = validate(&schema, Some(&json!("rust"))).unwrap_err();
+        assert_eq!(
+            errors.issues[0].to_string(),
+            "'arguments' should be object, got string"
+        );
+    }
+}
@@ -1,23 +1,30 @@
 //! Public MCP server surface for the shared gateway.
 
+use std::collections::BTreeSet;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler,
-    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
+    handler::server::{router::tool::ToolRouter, tool::ToolCallContext, wrapper::Parameters},
     model::*,
     schemars,
-    service::RequestContext,
-    tool, tool_handler, tool_router,
+    service::{NotificationContext, RequestContext},
+    tool, tool_router,
 };
 use serde::Deserialize;
 use serde_json::Value;
 
 use crate::backend::BackendManager;
-use crate::registry::ToolRegistry;
+use crate::backend::events::ClientNotice;
+use crate::backend::progress::{self, ChainProgress};
+use crate::error::GatewayError;
+use crate::registry::{ToolEntry, ToolRegistry};
+use crate::results::{ResultRange, ResultStore};
+use crate::tasks::{DEFAULT_TASK_TIMEOUT_MS, TaskError, TaskStore};
+use crate::tools::sandbox::ChainOutput;
 
-use tokio::sync::Semaphore;
+use tokio::sync::{Semaphore, broadcast};
 
 // --- Parameter structs for each meta-tool ---
 
@@ -44,6 +51,8 @@ pub struct SearchToolsParams {
     #[serde(default = "default_true")]
     pub brief: bool,
     /// Optional tag to filter results. Only tools with this tag are returned.
+    /// Backend annotation hints also work as tags: "read-only", "destructive",
+    /// "idempotent", "open-world" (e.g. tag="read-only" for read-only tools only).
     #[serde(default)]
     pub tag: Option<String>,
 }
@@ -64,7 +73,7 @@ fn default_page_size() -> u32 {
 pub struct ToolInfoParams {
     /// Name of the tool to get information for.
     pub tool_name: String,
-    /// Detail level: "brief" returns name, backend, first-sentence description, parameter names (~200 tokens). "full" returns complete schema (~10k tokens). Default: "brief".
+    /// Detail level: "brief" returns name, backend, first-sentence description, parameter names and a compact signature like `{query: string, limit?: integer}` (~200 tokens). "full" returns the complete schema with refs inlined. Default: "brief".
     #[serde(default = "default_detail")]
     pub detail: String,
 }
@@ -73,6 +82,16 @@ fn default_detail() -> String {
     "brief".to_string()
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ToolTypesParams {
+    /// Backend whose tools to declare, e.g. "exa".
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// Single tool to declare instead of a whole backend, e.g. "exa.web_search".
+    #[serde(default)]
+    pub tool_name: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ListToolsMetaParams {
     /// Pagination cursor from a previous response.
@@ -95,15 +114,77 @@ pub struct CallToolChainParams {
     pub code: String,
     /// Optional timeout in milliseconds (default: 30000).
     pub timeout: Option<u64>,
-    /// Optional maximum output size in characters (default: 200000).
+    /// Optional maximum output size in bytes, or estimated tokens when the
+    /// gateway's budget unit is tokens (default: 200000 bytes / 50000 tokens).
     pub max_output_size: Option<usize>,
     /// Optional intent description. When provided and output exceeds 5KB,
-    /// automatically filters to sections matching this intent instead of
-    /// returning the full raw output.
+    /// keeps only the sections, or JSON array items, that rank best for this
+    /// intent instead of returning the full raw output.
+    #[serde(default)]
+    pub intent: Option<String>,
+    /// Optional JSONPath (`$.items[*].name`) or jq-style path
+    /// (`.items[] | {id, name}`) applied to the JSON result before chunking
+    /// and truncation. Fails if it matches nothing.
+    #[serde(default)]
+    pub select: Option<String>,
+    /// Check object literals passed to tools (`backend.tool({...})`) against
+    /// the tools' input schemas before running, and fail with the mistakes
+    /// found instead of running the code (default: false).
+    #[serde(default)]
+    pub type_check: bool,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CallToolsBatchParams {
+    /// Tool calls to run in parallel: `[{"tool": "backend.tool", "arguments": {...}}]`.
+    pub calls: Vec<crate::tools::batch::BatchCall>,
+    /// Maximum number of calls running at once (default: 8).
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// Optional maximum output size for the whole batch, in the same unit as
+    /// call_tool_chain's, shared evenly by the calls (default: 200000 bytes).
+    #[serde(default)]
+    pub max_output_size: Option<usize>,
+    /// Optional intent description, applied to each call's output as in
+    /// call_tool_chain.
     #[serde(default)]
     pub intent: Option<String>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ReadResultParams {
+    /// Handle of a kept full result, from the footer of a cut-down output.
+    pub handle: String,
+    /// Byte offset to start reading at (default: 0).
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// Number of bytes to read, at least 1 (default: 20000).
+    #[serde(default)]
+    pub length: Option<usize>,
+    /// First line to read, 1-based. Reads lines instead of bytes.
+    #[serde(default)]
+    pub start_line: Option<usize>,
+    /// Number of lines to read, at least 1 (default: 200).
+    #[serde(default)]
+    pub line_count: Option<usize>,
+    /// JSON pointer (e.g. `/items/3/name`) of the part of a JSON result to read.
+    #[serde(default)]
+    pub pointer: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EnableToolsetParams {
+    /// Name of the toolset to enable. Omit to list the configured toolsets.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DisableToolsetParams {
+    /// Name of the toolset to disable.
+    pub name: String,
+}
+
 /// The MCP server exposed to Claude Code over stdio.
 #[derive(Clone)]
 pub struct GateminiServer {
@@ -115,11 +196,16 @@ pub struct GateminiServer {
     pub max_dynamic_backends: usize,
     /// Limits concurrent V8 sandbox executions to prevent OOM.
     pub sandbox_semaphore: Arc<Semaphore>,
+    /// `call_tool_chain` tasks, shared by every session of the daemon.
+    pub tasks: Arc<TaskStore>,
+    /// Full copies of cut-down results, shared by every session of the daemon.
+    pub results: Arc<ResultStore>,
     /// Session ID for dedicated instance pool routing. None for direct mode legacy.
     pub session_id: Option<u64>,
     /// Output processing configuration (auto-chunking, smart truncation).
     pub output_config: crate::config::OutputConfig,
-    #[allow(dead_code)]
+    /// Toolsets this session enabled; their tools are listed natively.
+    enabled_toolsets: Arc<RwLock<BTreeSet<String>>>,
     tool_router: ToolRouter<Self>,
 }
 
@@ -133,9 +219,17 @@ impl GateminiServer {
         allow_runtime_registration: bool,
         max_dynamic_backends: usize,
         sandbox_semaphore: Arc<Semaphore>,
+        tasks: Arc<TaskStore>,
+        results: Arc<ResultStore>,
         session_id: Option<u64>,
         output_config: crate::config::OutputConfig,
     ) -> Self {
+        let mut tool_router = Self::tool_router();
+        // Long chains can also be started as tasks and polled for their result.
+        if let Some(route) = tool_router.map.get_mut("call_tool_chain") {
+            route.attr.execution =
+                Some(ToolExecution::new().with_task_support(TaskSupport::Optional));
+        }
         Self {
             registry,
             backend_manager,
@@ -144,13 +238,120 @@ impl GateminiServer {
             allow_runtime_registration,
             max_dynamic_backends,
             sandbox_semaphore,
+            tasks,
+            results,
             session_id,
             output_config,
-            tool_router: Self::tool_router(),
+            enabled_toolsets: Arc::default(),
+            tool_router,
+        }
+    }
+
+    /// Execute a chain in the given progress scope.
+    async fn run_chain(
+        &self,
+        params: CallToolChainParams,
+        progress: Option<Arc<ChainProgress>>,
+    ) -> CallToolResult {
+        let result = progress::scope(
+            progress,
+            crate::tools::sandbox::handle_call_tool_chain(
+                &self.registry,
+                &self.backend_manager,
+                &params.code,
+                params.timeout,
+                params.max_output_size,
+                &self.sandbox_semaphore,
+                self.session_id,
+                params.intent.as_deref(),
+                params.select.as_deref(),
+                params.type_check,
+                &self.output_config,
+                Some(&self.results),
+            ),
+        )
+        .await;
+        self.record_output("call_tool_chain", &result);
+        tool_output(result)
+    }
+
+    /// Count the returned and raw sizes of a call's output in the stats.
+    fn record_output(&self, tool_name: &str, result: &anyhow::Result<ChainOutput>) {
+        if let Ok(output) = result {
+            self.tracker
+                .record_output(tool_name, output.returned, output.processed);
+        }
+    }
+
+    /// Backend tools listed natively for this session: pinned tools, then
+    /// the tools of its enabled toolsets.
+    fn native_tools(&self) -> Vec<ToolEntry> {
+        let mut tools = self.registry.pinned_tools();
+        for toolset in self.enabled_toolsets.read().unwrap().iter() {
+            tools.extend(self.registry.toolset_tools(toolset).unwrap_or_default());
+        }
+        let mut seen = BTreeSet::new();
+        // Meta-tools take precedence over a native name that clashes.
+        tools.retain(|entry| {
+            !self.tool_router.has_route(&entry.name) && seen.insert(entry.name.clone())
+        });
+        tools
+    }
+
+    /// Error result for a toolset that is not configured.
+    fn unknown_toolset(&self, name: &str) -> CallToolResult {
+        let available: Vec<String> = self
+            .registry
+            .toolsets()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        CallToolResult::error(vec![Content::text(format!(
+            "Toolset '{}' not found. Available toolsets: [{}]",
+            name,
+            available.join(", ")
+        ))])
+    }
+
+    /// Tell this session its tools/list changed after enabling or disabling
+    /// a toolset.
+    async fn notify_tool_list_changed(&self, peer: &rmcp::service::Peer<RoleServer>) {
+        if let Err(e) = peer.notify_tool_list_changed().await {
+            tracing::debug!(session = ?self.session_id, error = %e, "failed to send tools/list_changed");
+        }
+    }
+
+    /// Meta-tools followed by the native backend tools.
+    fn list_all_tools(&self) -> Vec<Tool> {
+        let mut tools = self.tool_router.list_all();
+        tools.extend(
+            self.native_tools()
+                .iter()
+                .map(crate::tools::native::to_mcp_tool),
+        );
+        tools
+    }
+}
+
+/// Tool result for the output of a chain, batch or native tool call.
+fn tool_output(result: anyhow::Result<ChainOutput>) -> CallToolResult {
+    match result {
+        Ok(output) => {
+            let mut content = vec![Content::text(output.text)];
+            content.extend(output.content_blocks.into_iter().map(|block| {
+                serde_json::from_value::<Content>(block.clone())
+                    .unwrap_or_else(|_| Content::text(block.to_string()))
+            }));
+            CallToolResult::success(content)
         }
+        Err(e) => CallToolResult::error(vec![Content::text(format!("{:#}", e))]),
     }
 }
 
+fn task_error(e: TaskError) -> McpError {
+    McpError::invalid_params(e.to_string(), None)
+}
+
 #[tool_router]
 impl GateminiServer {
     #[tool(description = "Registers a new tool provider by providing its call template.")]
@@ -311,6 +512,39 @@ impl GateminiServer {
         Ok(CallToolResult::success(vec![Content::text(json)]))
     }
 
+    #[tool(
+        description = "TypeScript declarations (.d.ts) for the tools of a backend, or of one tool, as called in call_tool_chain: argument and result types generated from their schemas. Pass backend or tool_name."
+    )]
+    async fn tool_types(
+        &self,
+        Parameters(params): Parameters<ToolTypesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let declarations = crate::tools::discovery::handle_tool_types(
+            &self.registry,
+            params.backend.as_deref(),
+            params.tool_name.as_deref(),
+        );
+        match declarations {
+            Some(declarations) => Ok(CallToolResult::success(vec![Content::text(declarations)])),
+            None => {
+                let mut backends = self.registry.get_backend_names();
+                backends.sort();
+                let message = match (params.tool_name, params.backend) {
+                    (Some(name), _) => format!("Tool '{name}' not found"),
+                    (None, Some(backend)) => format!(
+                        "Backend '{backend}' has no tools. Backends: {}",
+                        backends.join(", ")
+                    ),
+                    (None, None) => format!(
+                        "Pass backend or tool_name. Backends: {}",
+                        backends.join(", ")
+                    ),
+                };
+                Ok(CallToolResult::error(vec![Content::text(message)]))
+            }
+        }
+    }
+
     #[tool(description = "Get required environment variables for a registered tool.")]
     async fn get_required_keys_for_tool(
         &self,
@@ -335,61 +569,183 @@ impl GateminiServer {
         }
     }
 
+    #[tool(
+        description = "Call several backend tools in parallel without the TypeScript sandbox. Takes an array of {tool, arguments} and returns one result or error per call, in order. Use call_tool_chain when a call depends on another's result."
+    )]
+    async fn call_tools_batch(
+        &self,
+        Parameters(params): Parameters<CallToolsBatchParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let progress = context
+            .meta
+            .get_progress_token()
+            .map(|token| ChainProgress::new(context.peer.clone(), token));
+        let batch = progress::scope(
+            progress,
+            crate::tools::batch::handle_call_tools_batch(
+                &self.registry,
+                &self.backend_manager,
+                params.calls,
+                params.max_concurrency,
+                params.max_output_size,
+                self.session_id,
+                params.intent.as_deref(),
+                &self.output_config,
+                Some(&self.results),
+            ),
+        );
+        tokio::select! {
+            result = batch => {
+                self.record_output("call_tools_batch", &result);
+                Ok(tool_output(result))
+            }
+            _ = context.ct.cancelled() => Ok(tool_output(Err(GatewayError::Cancelled.into()))),
+        }
+    }
+
+    #[tool(
+        description = "Read part of a full tool result that was chunked or truncated. Pass the handle from the output footer plus a byte range (offset/length), a line range (start_line/line_count) or a JSON pointer."
+    )]
+    async fn read_result(
+        &self,
+        Parameters(params): Parameters<ReadResultParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let slice = ResultRange::from_args(
+            params.offset,
+            params.length,
+            params.start_line,
+            params.line_count,
+            params.pointer,
+        )
+        .and_then(|range| self.results.read(&params.handle, &range));
+        match slice {
+            Ok(slice) => Ok(CallToolResult::success(vec![Content::text(
+                slice.to_text(&params.handle),
+            )])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
+
+    #[tool(
+        description = "Enable a configured toolset for this session: its tools are then listed in tools/list and can be called directly. Omit `name` to list the configured toolsets."
+    )]
+    async fn enable_toolset(
+        &self,
+        Parameters(params): Parameters<EnableToolsetParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let Some(name) = params.name else {
+            let enabled = self.enabled_toolsets.read().unwrap().clone();
+            let toolsets: Vec<_> = self
+                .registry
+                .toolsets()
+                .into_iter()
+                .map(|(name, description)| {
+                    serde_json::json!({
+                        "name": name,
+                        "description": description,
+                        "enabled": enabled.contains(&name),
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&serde_json::json!({ "toolsets": toolsets }))
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            return Ok(CallToolResult::success(vec![Content::text(json)]));
+        };
+        let Some(tools) = self.registry.toolset_tools(&name) else {
+            return Ok(self.unknown_toolset(&name));
+        };
+        let added = self.enabled_toolsets.write().unwrap().insert(name.clone());
+        if added {
+            self.notify_tool_list_changed(&context.peer).await;
+        }
+        let names: Vec<&str> = tools.iter().map(|entry| entry.name.as_str()).collect();
+        let result = serde_json::json!({ "toolset": name, "enabled": true, "tools": names });
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
+    #[tool(description = "Disable a toolset enabled with enable_toolset for this session.")]
+    async fn disable_toolset(
+        &self,
+        Parameters(params): Parameters<DisableToolsetParams>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if self.registry.toolset_tools(&params.name).is_none() {
+            return Ok(self.unknown_toolset(&params.name));
+        }
+        let removed = self.enabled_toolsets.write().unwrap().remove(&params.name);
+        if removed {
+            self.notify_tool_list_changed(&context.peer).await;
+        }
+        let result = serde_json::json!({ "toolset": params.name, "enabled": false });
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
+
     #[tool(
         description = "Execute TypeScript code with direct access to all registered tools as hierarchical functions (e.g., manual.tool()). IMPORTANT: the tool result is the value your code returns. `console.log(...)` output is not returned; if you do not return a value, the result is usually `null`."
     )]
     async fn call_tool_chain(
         &self,
         Parameters(params): Parameters<CallToolChainParams>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
-        let result = crate::tools::sandbox::handle_call_tool_chain(
-            &self.registry,
-            &self.backend_manager,
-            &params.code,
-            params.timeout,
-            params.max_output_size,
-            &self.sandbox_semaphore,
-            self.session_id,
-            params.intent.as_deref(),
-            &self.output_config,
-        )
-        .await;
-
-        match result {
-            Ok(output) => Ok(CallToolResult::success(vec![Content::text(output)])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "{:#}",
-                e
-            ))])),
+        // Relay backend progress if the client asked for it.
+        let progress = context
+            .meta
+            .get_progress_token()
+            .map(|token| ChainProgress::new(context.peer.clone(), token));
+        // `ct` fires on `notifications/cancelled` and when the session closes.
+        // Dropping the chain cancels its backend calls and sandbox, and frees
+        // the permits it holds.
+        tokio::select! {
+            result = self.run_chain(params, progress) => Ok(result),
+            _ = context.ct.cancelled() => Ok(tool_output(Err(GatewayError::Cancelled.into()))),
         }
     }
 }
 
-#[tool_handler]
 impl ServerHandler for GateminiServer {
+    // `logging` is deprecated by SEP-2577 but clients still negotiate it.
+    #[allow(deprecated)]
     fn get_info(&self) -> ServerInfo {
-        ServerInfo::new(
-            ServerCapabilities::builder()
-                .enable_tools()
-                .enable_resources()
-                .enable_prompts()
-                .build(),
-        )
-        .with_protocol_version(ProtocolVersion::V_2025_11_25)
-        .with_instructions(
+        let mut capabilities = ServerCapabilities::builder()
+            .enable_tools()
+            .enable_tool_list_changed()
+            .enable_resources()
+            .enable_resources_list_changed()
+            .enable_resources_subscribe()
+            .enable_prompts()
+            .enable_prompts_list_changed()
+            .enable_logging()
+            .build();
+        // `call_tool_chain` can run as a task; see `enqueue_task`.
+        capabilities.tasks = Some(TasksCapability::server_default());
+        ServerInfo::new(capabilities)
+            .with_protocol_version(ProtocolVersion::V_2025_11_25)
+            .with_instructions(
                 "gatemini is an MCP gateway that aggregates tools from multiple backend MCP servers.\n\n\
                  IMPORTANT: Backend tools (e.g. firecrawl_search, web_search_exa) are NOT direct MCP tools. \
-                 Do NOT call them directly. They MUST be called via call_tool_chain.\n\n\
+                 Do NOT call them directly. They MUST be called via call_tool_chain. \
+                 (Exception: tools pinned in the gateway config, and tools of toolsets enabled with enable_toolset, are listed in tools/list under their `backend.tool` name and can be called directly.)\n\n\
                  ## Discovery Workflow (use progressive disclosure to save context)\n\
                  1. search_tools(\"your task\") → brief results by default (~60 tokens/result)\n\
-                 2. tool_info(\"name\") → brief: name, backend, description, param names (~200 tokens)\n\
+                 2. tool_info(\"name\") → brief: name, backend, description, param signature (~200 tokens)\n\
                  3. tool_info(\"name\", detail=\"full\") → complete schema, ONLY when ready to call (~10k tokens)\n\
                  4. call_tool_chain(\"code\") → execute TypeScript: `const r = await backend.tool({params}); return r;`\n\n\
                  ## Key Tools\n\
                  - search_tools: BM25 search across all tools. brief=true (default) or brief=false for full descriptions\n\
                  - tool_info: Get tool details. detail=\"brief\" (default) or detail=\"full\" for complete input schema\n\
+                 - tool_types: TypeScript declarations for a backend's tools (or one tool_name): typed args and results for call_tool_chain code\n\
                  - list_tools_meta: Paginated tool list. cursor + page_size (default 50)\n\
-                 - call_tool_chain: Execute TypeScript with tools as `backend.tool_name(args)`. Use __interfaces for introspection\n\n\
+                 - call_tool_chain: Execute TypeScript with tools as `backend.tool_name(args)`. Use __interfaces for introspection\n\
+                 - read_result: Page through a chunked/truncated result by the handle in its footer: byte range, line range or JSON pointer\n\
+                 - call_tools_batch: Run independent tool calls in parallel: calls=[{tool: \"backend.tool\", arguments: {...}}]. No sandbox needed\n\
+                 - enable_toolset / disable_toolset: Expose a configured group of tools directly for this session. enable_toolset() with no name lists them\n\n\
                  IMPORTANT: Never guess parameter names. Always call tool_info(detail=\"full\") or __getToolInterface(\"backend.tool\") before first use of any tool.\n\n\
                  IMPORTANT: Inside call_tool_chain you can ONLY call backend tools (e.g. `exa.web_search_exa`). \
                  You CANNOT call gatemini meta-tools (search_tools, tool_info, list_tools_meta) from inside the sandbox. \
@@ -399,11 +755,15 @@ impl ServerHandler for GateminiServer {
                  - @gatemini://backends → all backends with status and tool counts\n\
                  - @gatemini://tools → compact index of ALL tools (~3k tokens vs ~40k for full schemas)\n\
                  - @gatemini://tool/{name} → full schema for one tool (on-demand)\n\
-                 - @gatemini://backend/{name} → backend details + tool list\n\n\
+                 - @gatemini://backend/{name} → backend details + tool list\n\
+                 - @gatemini://types/{backend} → TypeScript declarations for a backend's tools\n\
+                 - gatemini://result/{id} → slice of a kept full result (?bytes=START-END, ?lines=START-END or ?pointer=/path)\n\
+                 - gatemini://r/{backend}/{uri} → resources exposed by backend MCP servers, proxied as-is\n\n\
                  ## Prompts\n\
                  - /mcp__gatemini__discover → guided progressive discovery walkthrough\n\
                  - /mcp__gatemini__find_tool → search + top match's full schema + execution example\n\
-                 - /mcp__gatemini__backend_status → health/status table for all backends\n\n\
+                 - /mcp__gatemini__backend_status → health/status table for all backends\n\
+                 - Backend prompts are listed as `backend.prompt` and rendered by the owning backend\n\n\
                  ## Naming Conventions\n\
                  - ALWAYS use qualified names: `backend.tool_name` (e.g. `exa.web_search_exa`)\n\
                  - Bare names (e.g. `web_search`) may not resolve if the backend is still starting\n\
@@ -415,16 +775,20 @@ impl ServerHandler for GateminiServer {
                  - Always return a value from the entrypoint. `console.log(...)` is for debugging only and is not surfaced as the tool result; omitting `return` usually yields `null`\n\
                  - Hyphens in names are auto-converted to underscores for valid JS identifiers\n\
                  - Introspection: `__getToolInterface('backend.tool')` returns schema\n\
+                 - type_check=true checks object literal arguments against the tools' schemas before running, and fails with the mistakes found\n\
                  - Backends are top-level `const` variables AND mirrored on `globalThis` — both `exa.tool()` and `globalThis['exa'].tool()` work\n\
                  - Dynamic dispatch via `__backends[name][tool]({args})` also works\n\
                  - For multi-tool loops, make separate call_tool_chain calls instead of dynamic resolution\n\
                  - Standard JS only: JSON, Math, Array, Object, Promise, async/await, console\n\
-                 - If a backend is stopped, the tool call will auto-restart it\n\n\
+                 - If a backend is stopped, the tool call will auto-restart it\n\
+                 - Multi-minute chains can run as MCP tasks (task-augmented `tools/call`): poll `tasks/get`, fetch `tasks/result`, or `tasks/cancel`. Tasks keep running across reconnects\n\
+                 - Image/audio/resource results are typed objects (`{type: 'image', data, mimeType}`); returned ones come back as native content blocks, referenced in the text by `content_block` index\n\n\
                  ## Output Processing (automatic)\n\
                  - Large JSON responses (>10KB) are auto-chunked into path summaries\n\
                  - Uniform arrays (same-structure items) are collapsed: first 3 + identity summary\n\
                  - Use `intent` parameter to filter output by relevance (e.g., intent=\"error handling\")\n\
-                 - Response metadata shows KB returned vs processed when reduction occurs\n\
+                 - Use `select` to project JSON results before chunking, JSONPath or jq style (e.g., select=\"$.items[*].name\" or \".items[] | {id, name}\")\n\
+                 - Response metadata shows size returned vs processed when reduction occurs (KB, or estimated tokens when `sandbox.output.budget_unit` is `tokens`)\n\
                  - Smart truncation preserves head (60%) and tail (40%) of output at line boundaries\n\
                  - Search supports typos: three-tier fallback (BM25 → trigram → fuzzy Levenshtein)\n\n\
                  ## Example: Find and use a web search tool\n\
@@ -434,7 +798,146 @@ impl ServerHandler for GateminiServer {
                  tool_info(\"web_search_exa\", detail=\"full\") → {input_schema: {properties: {...}}}\n\
                  call_tool_chain(`const r = await exa.web_search_exa({query: \"MCP protocol\"}); return r;`)\n\
                  ```"
-        )
+            )
+    }
+
+    fn call_tool(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
+        async move {
+            let is_native = !self.tool_router.has_route(&request.name)
+                && self
+                    .native_tools()
+                    .iter()
+                    .any(|entry| entry.name == request.name);
+            if !is_native {
+                let tcc = ToolCallContext::new(self, request, context);
+                return self.tool_router.call(tcc).await;
+            }
+            // Native backend tool: progress and cancellation work as for call_tool_chain.
+            let progress = context
+                .meta
+                .get_progress_token()
+                .map(|token| ChainProgress::new(context.peer.clone(), token));
+            let call = progress::scope(
+                progress,
+                crate::tools::native::call_native_tool(
+                    &self.registry,
+                    &self.backend_manager,
+                    &request.name,
+                    request.arguments,
+                    self.session_id,
+                    &self.output_config,
+                    Some(&self.results),
+                ),
+            );
+            tokio::select! {
+                result = call => {
+                    self.record_output(&request.name, &result);
+                    Ok(tool_output(result))
+                }
+                _ = context.ct.cancelled() => Ok(tool_output(Err(GatewayError::Cancelled.into()))),
+            }
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ListToolsResult, McpError>> + Send + '_ {
+        std::future::ready(Ok(ListToolsResult::with_all_items(self.list_all_tools())))
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Tool> {
+        self.tool_router.get(name).cloned().or_else(|| {
+            self.native_tools()
+                .iter()
+                .find(|entry| entry.name == name)
+                .map(crate::tools::native::to_mcp_tool)
+        })
+    }
+
+    #[allow(deprecated)] // notify_logging_message, see `get_info`
+    fn on_initialized(
+        &self,
+        context: NotificationContext<RoleServer>,
+    ) -> impl std::future::Future<Output = ()> + Send + '_ {
+        // Forward gateway changes (e.g. a backend's tool list changed, native
+        // tools changed) and log messages to this session until the client
+        // disconnects.
+        let mut notices = self.backend_manager.subscribe_client_notices();
+        let mut native_tools = self.registry.subscribe_native_tools();
+        let enabled_toolsets = Arc::clone(&self.enabled_toolsets);
+        let manager = Arc::clone(&self.backend_manager);
+        let peer = context.peer;
+        let session_id = self.session_id;
+        // Backend elicitation and sampling requests caused by this session's calls are sent here.
+        // `closed` fires when the session is released on disconnect.
+        let closed = self
+            .backend_manager
+            .register_client_session(session_id.unwrap_or(0), peer.clone());
+        tokio::spawn(async move {
+            loop {
+                let notice = tokio::select! {
+                    _ = closed.cancelled() => break,
+                    notice = notices.recv() => notice,
+                    change = native_tools.recv() => {
+                        let affected = match change {
+                            Ok(change) => change.affects(
+                                &enabled_toolsets.read().unwrap_or_else(|e| e.into_inner()),
+                            ),
+                            Err(broadcast::error::RecvError::Lagged(_)) => true,
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        };
+                        if affected && peer.notify_tool_list_changed().await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                let sent = match notice {
+                    Ok(ClientNotice::ResourceListChanged) => {
+                        peer.notify_resource_list_changed().await
+                    }
+                    Ok(ClientNotice::PromptListChanged) => peer.notify_prompt_list_changed().await,
+                    Ok(ClientNotice::ResourceUpdated { uri }) => {
+                        if !manager.is_subscribed(session_id.unwrap_or(0), &uri) {
+                            continue;
+                        }
+                        peer.notify_resource_updated(ResourceUpdatedNotificationParam::new(uri))
+                            .await
+                    }
+                    Ok(ClientNotice::Log {
+                        level,
+                        logger,
+                        data,
+                    }) => {
+                        if !manager.wants_log(session_id.unwrap_or(0), level) {
+                            continue;
+                        }
+                        let mut params = LoggingMessageNotificationParam::new(level, data);
+                        params.logger = Some(logger);
+                        peer.notify_logging_message(params).await
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!(session = ?session_id, skipped, "client notices lagged");
+                        match peer.notify_resource_list_changed().await {
+                            Ok(()) => peer.notify_prompt_list_changed().await,
+                            Err(e) => Err(e),
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if sent.is_err() {
+                    break; // session closed
+                }
+            }
+        });
+        tracing::info!(session = ?session_id, "client initialized");
+        std::future::ready(())
     }
 
     fn list_resources(
@@ -442,11 +945,15 @@ impl ServerHandler for GateminiServer {
         _request: Option<PaginatedRequestParams>,
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
-        std::future::ready(Ok(ListResourcesResult {
-            meta: None,
-            next_cursor: None,
-            resources: crate::resources::list_static_resources(),
-        }))
+        async move {
+            let mut resources = crate::resources::list_static_resources();
+            resources.extend(self.backend_manager.list_backend_resources().await);
+            Ok(ListResourcesResult {
+                meta: None,
+                next_cursor: None,
+                resources,
+            })
+        }
     }
 
     fn list_resource_templates(
@@ -455,11 +962,15 @@ impl ServerHandler for GateminiServer {
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourceTemplatesResult, McpError>> + Send + '_
     {
-        std::future::ready(Ok(ListResourceTemplatesResult {
-            meta: None,
-            next_cursor: None,
-            resource_templates: crate::resources::list_resource_templates(),
-        }))
+        async move {
+            let mut resource_templates = crate::resources::list_resource_templates();
+            resource_templates.extend(self.backend_manager.list_backend_resource_templates().await);
+            Ok(ListResourceTemplatesResult {
+                meta: None,
+                next_cursor: None,
+                resource_templates,
+            })
+        }
     }
 
     fn read_resource(
@@ -470,9 +981,46 @@ impl ServerHandler for GateminiServer {
         let registry = Arc::clone(&self.registry);
         let backend_manager = Arc::clone(&self.backend_manager);
         let tracker = Arc::clone(&self.tracker);
+        let results = Arc::clone(&self.results);
+        async move {
+            crate::resources::read_resource(
+                &request.uri,
+                &registry,
+                &backend_manager,
+                &tracker,
+                &results,
+            )
+            .await
+        }
+    }
+
+    fn subscribe(
+        &self,
+        request: SubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
+        async move {
+            // gatemini's own resources are computed on read; there is nothing to watch.
+            if crate::backend::resources::split_namespaced_uri(&request.uri).is_none() {
+                return Ok(());
+            }
+            self.backend_manager
+                .subscribe_resource(self.session_id.unwrap_or(0), &request.uri)
+                .await
+                .map_err(|e| McpError::internal_error(format!("{e:#}"), None))
+        }
+    }
+
+    fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
         async move {
-            crate::resources::read_resource(&request.uri, &registry, &backend_manager, &tracker)
+            self.backend_manager
+                .unsubscribe_resource(self.session_id.unwrap_or(0), &request.uri)
                 .await
+                .map_err(|e| McpError::internal_error(format!("{e:#}"), None))
         }
     }
 
@@ -484,7 +1032,7 @@ impl ServerHandler for GateminiServer {
         std::future::ready(Ok(ListPromptsResult {
             meta: None,
             next_cursor: None,
-            prompts: crate::prompts::list_prompts(),
+            prompts: crate::prompts::list_prompts(&self.registry),
         }))
     }
 
@@ -514,6 +1062,131 @@ impl ServerHandler for GateminiServer {
         _context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<CompleteResult, McpError>> + Send + '_ {
         let registry = Arc::clone(&self.registry);
-        async move { crate::resources::complete(&request, &registry) }
+        let backend_manager = Arc::clone(&self.backend_manager);
+        async move {
+            if let Some((backend, forwarded)) =
+                crate::backend::prompts::route_completion(&request, &registry)
+            {
+                return backend_manager
+                    .complete_backend(&backend, forwarded)
+                    .await
+                    .map_err(|e| McpError::internal_error(format!("{e:#}"), None));
+            }
+            crate::resources::complete(&request, &registry)
+        }
+    }
+
+    fn set_level(
+        &self,
+        request: SetLevelRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
+        // Backends keep logging at their own level; messages are filtered per session.
+        self.backend_manager
+            .set_client_log_level(self.session_id.unwrap_or(0), request.level);
+        std::future::ready(Ok(()))
+    }
+
+    fn enqueue_task(
+        &self,
+        request: CallToolRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<CreateTaskResult, McpError>> + Send + '_ {
+        async move {
+            if request.name != "call_tool_chain" {
+                return Err(McpError::invalid_params(
+                    format!("tool '{}' does not support tasks", request.name),
+                    None,
+                ));
+            }
+            let mut params: CallToolChainParams =
+                serde_json::from_value(Value::Object(request.arguments.unwrap_or_default()))
+                    .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+            // Tasks are for chains that outlive the default 30s sandbox timeout.
+            params.timeout.get_or_insert(DEFAULT_TASK_TIMEOUT_MS);
+            let ttl = request
+                .task
+                .as_ref()
+                .and_then(|task| task.get("ttl"))
+                .and_then(Value::as_u64);
+
+            // The task runs detached from this request and session, so it
+            // records progress for `tasks/get` instead of notifying, and runs
+            // in its own session so this client disconnecting doesn't release
+            // pool instances the chain is still using. Dropping the chain
+            // (finished or cancelled) releases the task's session.
+            let progress = ChainProgress::recorded();
+            let session = self.backend_manager.detached_session(self.session_id);
+            let task_session = session.id();
+            let mut server = self.clone();
+            server.session_id = Some(task_session);
+            let chain_progress = Arc::clone(&progress);
+            let task = self.tasks.start(ttl, progress, async move {
+                let _session = session;
+                server.run_chain(params, Some(chain_progress)).await
+            });
+            tracing::info!(task = %task.task_id, session = ?self.session_id, task_session, "started call_tool_chain task");
+            Ok(CreateTaskResult::new(task))
+        }
+    }
+
+    fn list_tasks(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ListTasksResult, McpError>> + Send + '_ {
+        let tasks = self.tasks.list();
+        let total = tasks.len() as u64;
+        let mut result = ListTasksResult::new(tasks);
+        result.total = Some(total);
+        std::future::ready(Ok(result))
+    }
+
+    fn get_task_info(
+        &self,
+        request: GetTaskInfoParams,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<GetTaskResult, McpError>> + Send + '_ {
+        std::future::ready(
+            self.tasks
+                .get(&request.task_id)
+                .map(|task| GetTaskResult { meta: None, task })
+                .map_err(task_error),
+        )
+    }
+
+    fn get_task_result(
+        &self,
+        request: GetTaskResultParams,
+        context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<GetTaskPayloadResult, McpError>> + Send + '_ {
+        async move {
+            // Waits for the task to finish, unless this request is cancelled.
+            let result = tokio::select! {
+                result = self.tasks.result(&request.task_id) => result.map_err(task_error)?,
+                _ = context.ct.cancelled() => {
+                    return Err(McpError::internal_error(
+                        GatewayError::Cancelled.to_string(),
+                        None,
+                    ));
+                }
+            };
+            serde_json::to_value(result)
+                .map(GetTaskPayloadResult::new)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))
+        }
+    }
+
+    fn cancel_task(
+        &self,
+        request: CancelTaskParams,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<CancelTaskResult, McpError>> + Send + '_ {
+        std::future::ready(
+            self.tasks
+                .cancel(&request.task_id)
+                .map(|task| CancelTaskResult { meta: None, task })
+                .map_err(task_error),
+        )
     }
 }
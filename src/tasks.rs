@@ -0,0 +1,317 @@
+//! MCP tasks for long-running `call_tool_chain` executions.
+//!
+//! A chain started with task augmentation runs in the background and the
+//! client gets a task id back straight away. The daemon keeps one
+//! [`TaskStore`] shared by every client session, so a task keeps running when
+//! the session that started it disconnects, and can be polled, fetched or
+//! cancelled from a later session (e.g. after the proxy reconnects). Finished
+//! tasks are kept for their TTL, then purged.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use rmcp::model::{CallToolResult, Task, TaskStatus};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::backend::progress::ChainProgress;
+
+/// How long a finished task is kept, unless the client asks for less.
+pub const DEFAULT_TASK_TTL: Duration = Duration::from_secs(3600);
+
+/// Suggested interval between `tasks/get` polls.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sandbox timeout of a task's chain when the call sets none, in milliseconds.
+pub const DEFAULT_TASK_TIMEOUT_MS: u64 = 600_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum TaskError {
+    #[error("unknown task '{0}'")]
+    NotFound(String),
+
+    #[error("task '{0}' was cancelled")]
+    Cancelled(String),
+}
+
+/// Tasks started by any client session of this gateway.
+#[derive(Default)]
+pub struct TaskStore {
+    tasks: DashMap<String, Arc<TaskEntry>>,
+}
+
+struct TaskEntry {
+    id: String,
+    created_at: String,
+    ttl: Duration,
+    cancel: CancellationToken,
+    /// Progress of the chain, for the status message of a working task.
+    progress: Arc<ChainProgress>,
+    state: watch::Sender<TaskState>,
+}
+
+#[derive(Clone)]
+struct TaskState {
+    status: TaskStatus,
+    message: Option<String>,
+    updated_at: String,
+    /// When the task reached a terminal status.
+    finished: Option<Instant>,
+    result: Option<CallToolResult>,
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+fn is_terminal(status: &TaskStatus) -> bool {
+    matches!(
+        status,
+        TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled
+    )
+}
+
+impl TaskEntry {
+    fn task(&self) -> Task {
+        let state = self.state.borrow();
+        let status_message = match state.status {
+            TaskStatus::Working => self.progress.snapshot().map(|p| {
+                format!(
+                    "{:.1}/{:.0} backend calls done; {}",
+                    p.progress, p.total, p.message
+                )
+            }),
+            _ => state.message.clone(),
+        };
+        let mut task = Task::new(
+            self.id.clone(),
+            state.status.clone(),
+            self.created_at.clone(),
+            state.updated_at.clone(),
+        )
+        .with_ttl(self.ttl.as_millis() as u64)
+        .with_poll_interval(POLL_INTERVAL.as_millis() as u64);
+        task.status_message = status_message;
+        task
+    }
+
+    /// Move to a terminal status, unless the task already reached one.
+    fn finish(&self, status: TaskStatus, message: Option<String>, result: Option<CallToolResult>) {
+        self.state.send_if_modified(|state| {
+            if is_terminal(&state.status) {
+                return false;
+            }
+            *state = TaskState {
+                status,
+                message,
+                updated_at: now(),
+                finished: Some(Instant::now()),
+                result,
+            };
+            true
+        });
+    }
+
+    fn expired(&self) -> bool {
+        self.state
+            .borrow()
+            .finished
+            .is_some_and(|finished| finished.elapsed() >= self.ttl)
+    }
+}
+
+impl TaskStore {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Run `chain` as a new task and return it in the `working` status.
+    ///
+    /// `ttl_ms` is the retention the client asked for, capped at
+    /// [`DEFAULT_TASK_TTL`]. `progress` must be the progress scope `chain`
+    /// runs in.
+    pub fn start<F>(&self, ttl_ms: Option<u64>, progress: Arc<ChainProgress>, chain: F) -> Task
+    where
+        F: Future<Output = CallToolResult> + Send + 'static,
+    {
+        self.purge_expired();
+
+        let ttl = ttl_ms
+            .map(Duration::from_millis)
+            .map_or(DEFAULT_TASK_TTL, |ttl| ttl.min(DEFAULT_TASK_TTL));
+        let created_at = now();
+        let (state, _) = watch::channel(TaskState {
+            status: TaskStatus::Working,
+            message: None,
+            updated_at: created_at.clone(),
+            finished: None,
+            result: None,
+        });
+        let entry = Arc::new(TaskEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at,
+            ttl,
+            cancel: CancellationToken::new(),
+            progress,
+            state,
+        });
+        self.tasks.insert(entry.id.clone(), Arc::clone(&entry));
+        let task = entry.task();
+
+        tokio::spawn(async move {
+            // Dropping the chain on cancellation cancels its backend calls and sandbox.
+            let result = tokio::select! {
+                result = chain => result,
+                _ = entry.cancel.cancelled() => return,
+            };
+            if result.is_error == Some(true) {
+                entry.finish(
+                    TaskStatus::Failed,
+                    Some("chain returned an error".into()),
+                    Some(result),
+                );
+            } else {
+                entry.finish(TaskStatus::Completed, None, Some(result));
+            }
+        });
+
+        task
+    }
+
+    /// Current state of a task.
+    pub fn get(&self, task_id: &str) -> Result<Task, TaskError> {
+        self.entry(task_id).map(|entry| entry.task())
+    }
+
+    /// All tasks, oldest first.
+    pub fn list(&self) -> Vec<Task> {
+        self.purge_expired();
+        let mut tasks: Vec<Task> = self.tasks.iter().map(|r| r.value().task()).collect();
+        tasks.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        tasks
+    }
+
+    /// Number of tasks still running.
+    pub fn running(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|r| !is_terminal(&r.value().state.borrow().status))
+            .count()
+    }
+
+    /// Cancel a task. Finished tasks are left as they are.
+    pub fn cancel(&self, task_id: &str) -> Result<Task, TaskError> {
+        let entry = self.entry(task_id)?;
+        entry.finish(
+            TaskStatus::Cancelled,
+            Some("cancelled by client".into()),
+            None,
+        );
+        entry.cancel.cancel();
+        Ok(entry.task())
+    }
+
+    /// Result of a task, waiting for it to finish.
+    pub async fn result(&self, task_id: &str) -> Result<CallToolResult, TaskError> {
+        let entry = self.entry(task_id)?;
+        let mut state = entry.state.subscribe();
+        // The sender lives in `entry`, so this only returns once terminal.
+        let state = state
+            .wait_for(|state| is_terminal(&state.status))
+            .await
+            .map(|state| state.clone())
+            .map_err(|_| TaskError::NotFound(task_id.to_string()))?;
+        state
+            .result
+            .ok_or_else(|| TaskError::Cancelled(task_id.to_string()))
+    }
+
+    fn entry(&self, task_id: &str) -> Result<Arc<TaskEntry>, TaskError> {
+        self.tasks
+            .get(task_id)
+            .map(|r| Arc::clone(r.value()))
+            .ok_or_else(|| TaskError::NotFound(task_id.to_string()))
+    }
+
+    fn purge_expired(&self) {
+        self.tasks.retain(|_, entry| !entry.expired());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::Content;
+
+    fn ok(text: &str) -> CallToolResult {
+        CallToolResult::success(vec![Content::text(text)])
+    }
+
+    #[tokio::test]
+    async fn test_task_completes_with_result() {
+        let store = TaskStore::new();
+        let task = store.start(None, ChainProgress::recorded(), async { ok("done") });
+        assert_eq!(task.status, TaskStatus::Working);
+        assert_eq!(task.ttl, Some(DEFAULT_TASK_TTL.as_millis() as u64));
+
+        let result = store.result(&task.task_id).await.unwrap();
+        assert_eq!(result, ok("done"));
+        assert_eq!(
+            store.get(&task.task_id).unwrap().status,
+            TaskStatus::Completed
+        );
+        assert_eq!(store.running(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_error_result_fails_task() {
+        let store = TaskStore::new();
+        let task = store.start(None, ChainProgress::recorded(), async {
+            CallToolResult::error(vec![Content::text("boom")])
+        });
+        let result = store.result(&task.task_id).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(store.get(&task.task_id).unwrap().status, TaskStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_running_task() {
+        let store = TaskStore::new();
+        let task = store.start(Some(1000), ChainProgress::recorded(), async {
+            std::future::pending::<()>().await;
+            ok("never")
+        });
+        assert_eq!(task.ttl, Some(1000));
+        assert_eq!(store.running(), 1);
+
+        let cancelled = store.cancel(&task.task_id).unwrap();
+        assert_eq!(cancelled.status, TaskStatus::Cancelled);
+        assert!(matches!(
+            store.result(&task.task_id).await,
+            Err(TaskError::Cancelled(_))
+        ));
+        assert_eq!(store.running(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_expired_tasks() {
+        let store = TaskStore::new();
+        assert!(matches!(store.get("nope"), Err(TaskError::NotFound(_))));
+        assert!(matches!(store.cancel("nope"), Err(TaskError::NotFound(_))));
+
+        let task = store.start(Some(0), ChainProgress::recorded(), async { ok("done") });
+        store.result(&task.task_id).await.unwrap();
+        // A zero TTL expires as soon as the task finishes.
+        assert!(store.list().is_empty());
+        assert!(matches!(
+            store.get(&task.task_id),
+            Err(TaskError::NotFound(_))
+        ));
+    }
+}
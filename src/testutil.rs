@@ -43,8 +43,8 @@ pub struct MockBackend {
     inject_error: AtomicBool,
     /// Record of all call parameters: (tool_name, arguments).
     call_log: Mutex<Vec<(String, Option<Value>)>>,
-    /// Tools this mock provides.
-    tools: Vec<ToolEntry>,
+    /// Tools this mock provides (mutable to simulate runtime tool list changes).
+    tools: std::sync::Mutex<Vec<ToolEntry>>,
 }
 
 impl MockBackend {
@@ -58,7 +58,7 @@ impl MockBackend {
             call_delay,
             inject_error: AtomicBool::new(false),
             call_log: Mutex::new(Vec::new()),
-            tools: vec![
+            tools: std::sync::Mutex::new(vec![
                 ToolEntry {
                     name: "echo_tool".to_string(),
                     original_name: "echo_tool".to_string(),
@@ -66,6 +66,8 @@ impl MockBackend {
                     backend_name: name.to_string(),
                     input_schema: serde_json::json!({"type": "object", "properties": {}}),
                     tags: Vec::new(),
+                    output_schema: None,
+                    annotations: None,
                 },
                 ToolEntry {
                     name: "slow_tool".to_string(),
@@ -74,6 +76,8 @@ impl MockBackend {
                     backend_name: name.to_string(),
                     input_schema: serde_json::json!({"type": "object", "properties": {}}),
                     tags: Vec::new(),
+                    output_schema: None,
+                    annotations: None,
                 },
                 ToolEntry {
                     name: "error_tool".to_string(),
@@ -82,6 +86,8 @@ impl MockBackend {
                     backend_name: name.to_string(),
                     input_schema: serde_json::json!({"type": "object", "properties": {}}),
                     tags: Vec::new(),
+                    output_schema: None,
+                    annotations: None,
                 },
                 ToolEntry {
                     name: "counter_tool".to_string(),
@@ -90,11 +96,38 @@ impl MockBackend {
                     backend_name: name.to_string(),
                     input_schema: serde_json::json!({"type": "object", "properties": {}}),
                     tags: Vec::new(),
+                    output_schema: None,
+                    annotations: None,
                 },
-            ],
+            ]),
         })
     }
 
+    /// Add a tool at runtime (returns `{"tool": name, "status": "ok"}` when called).
+    pub fn add_tool(&self, tool_name: &str) {
+        self.tools
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ToolEntry {
+                name: tool_name.to_string(),
+                original_name: tool_name.to_string(),
+                description: format!("Runtime-added tool {tool_name}"),
+                backend_name: self.name.clone(),
+                input_schema: serde_json::json!({"type": "object", "properties": {}}),
+                tags: Vec::new(),
+                output_schema: None,
+                annotations: None,
+            });
+    }
+
+    /// Remove a tool at runtime.
+    pub fn remove_tool(&self, tool_name: &str) {
+        self.tools
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|t| t.name != tool_name);
+    }
+
     /// Enable or disable error injection for all calls.
     pub fn set_inject_error(&self, inject: bool) {
         self.inject_error.store(inject, Ordering::SeqCst);
@@ -185,7 +218,7 @@ impl Backend for MockBackend {
     }
 
     async fn discover_tools(&self) -> Result<Vec<ToolEntry>> {
-        Ok(self.tools.clone())
+        Ok(self.tools.lock().unwrap_or_else(|e| e.into_inner()).clone())
     }
 
     fn is_available(&self) -> bool {
@@ -571,7 +604,10 @@ mod tests {
             &semaphore,
             None,
             None,
+            None,
+            false,
             &Default::default(),
+            None,
         )
         .await;
         assert!(
@@ -598,7 +634,10 @@ mod tests {
             &semaphore,
             None,
             None,
+            None,
+            false,
             &Default::default(),
+            None,
         )
         .await;
         assert!(result.is_err());
@@ -0,0 +1,232 @@
+//! Token estimates for output budgets and context stats.
+//!
+//! Bytes are a poor proxy for context cost: CJK text costs about one token
+//! per character (three bytes), base64 and hashes tokenize far worse than
+//! prose, and pretty-printed JSON indentation is nearly free. A
+//! [`TokenEstimator`] gives a cheap offline estimate; [`Meter`] measures text
+//! in whichever unit the `sandbox.output` budgets are set in.
+
+use serde::{Deserialize, Serialize};
+
+/// Estimates how many tokens a text costs in a model's context.
+pub trait TokenEstimator: Send + Sync {
+    fn estimate(&self, text: &str) -> usize;
+}
+
+/// Character-class heuristic close to BPE tokenizers such as cl100k.
+///
+/// Words cost a token per ~5 characters, digit runs one per 3 digits, long
+/// unbroken alphanumeric runs (base64, hashes, ids) one per 2.5 characters,
+/// punctuation one per 2 characters, and every CJK, emoji or other wide
+/// character one token. A single space merges into the next word; other
+/// whitespace runs (newlines, indentation) cost one token.
+pub struct HeuristicEstimator;
+
+/// Fixed 4 bytes per token, the usual rule of thumb for English text.
+pub struct BytesEstimator;
+
+/// Runs of word characters longer than this tokenize like random data.
+const LONG_RUN: usize = 24;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Class {
+    Word,
+    Space,
+    Punct,
+    Wide,
+}
+
+fn class(c: char) -> Class {
+    match c {
+        c if c.is_ascii_alphanumeric() || c == '_' => Class::Word,
+        c if c.is_whitespace() => Class::Space,
+        c if c.is_ascii() => Class::Punct,
+        // Accented Latin, Greek, Cyrillic, ... tokenize like words, if worse.
+        c if c.len_utf8() == 2 && c.is_alphanumeric() => Class::Word,
+        _ => Class::Wide,
+    }
+}
+
+/// Tokens of one run of same-class characters. `weight` counts two-byte
+/// characters twice.
+fn run_tokens(class: Class, run: &str, weight: usize) -> usize {
+    match class {
+        Class::Word if weight > LONG_RUN => (weight * 2).div_ceil(5),
+        Class::Word if run.bytes().all(|b| b.is_ascii_digit()) => weight.div_ceil(3),
+        Class::Word => weight.div_ceil(5),
+        Class::Space if run == " " => 0,
+        Class::Space => 1,
+        Class::Punct => weight.div_ceil(2),
+        Class::Wide => run.chars().count(),
+    }
+}
+
+impl TokenEstimator for HeuristicEstimator {
+    fn estimate(&self, text: &str) -> usize {
+        let mut tokens = 0;
+        let mut run_start = 0;
+        let mut run_class = None;
+        let mut weight = 0;
+        for (i, c) in text.char_indices() {
+            let c_class = class(c);
+            if run_class != Some(c_class) {
+                if let Some(prev) = run_class {
+                    tokens += run_tokens(prev, &text[run_start..i], weight);
+                }
+                run_start = i;
+                run_class = Some(c_class);
+                weight = 0;
+            }
+            weight += c.len_utf8().min(2);
+        }
+        if let Some(prev) = run_class {
+            tokens += run_tokens(prev, &text[run_start..], weight);
+        }
+        tokens
+    }
+}
+
+impl TokenEstimator for BytesEstimator {
+    fn estimate(&self, text: &str) -> usize {
+        text.len().div_ceil(4)
+    }
+}
+
+/// Built-in estimators, selectable in config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimatorKind {
+    #[default]
+    Heuristic,
+    Bytes,
+}
+
+impl EstimatorKind {
+    pub fn estimator(self) -> &'static dyn TokenEstimator {
+        match self {
+            EstimatorKind::Heuristic => &HeuristicEstimator,
+            EstimatorKind::Bytes => &BytesEstimator,
+        }
+    }
+}
+
+/// Unit output budgets (`max_output_size`, `chunk_threshold`) are set in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetUnit {
+    #[default]
+    Bytes,
+    Tokens,
+}
+
+/// Measures text in the unit of the output budgets.
+#[derive(Clone, Copy)]
+pub enum Meter {
+    Bytes,
+    Tokens(&'static dyn TokenEstimator),
+}
+
+impl Meter {
+    pub fn measure(&self, text: &str) -> usize {
+        match self {
+            Meter::Bytes => text.len(),
+            Meter::Tokens(estimator) => estimator.estimate(text),
+        }
+    }
+
+    /// A built-in byte limit expressed in this unit.
+    pub fn from_bytes(&self, bytes: usize) -> usize {
+        match self {
+            Meter::Bytes => bytes,
+            Meter::Tokens(_) => bytes / 4,
+        }
+    }
+
+    /// Bytes of `text` that fit in `budget`, at the text's own density.
+    pub fn byte_budget(&self, text: &str, budget: usize) -> usize {
+        match self {
+            Meter::Bytes => budget,
+            Meter::Tokens(_) => {
+                let size = self.measure(text).max(1);
+                (text.len() as u128 * budget as u128 / size as u128) as usize
+            }
+        }
+    }
+
+    /// Human-readable size, e.g. `12.3KB` or `3.1K tokens`.
+    pub fn format(&self, size: usize) -> String {
+        match self {
+            Meter::Bytes => format!("{:.1}KB", size as f64 / 1024.0),
+            Meter::Tokens(_) => format!("{:.1}K tokens", size as f64 / 1000.0),
+        }
+    }
+}
+
+/// Size of an output in bytes and estimated tokens, for the stats.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutputSize {
+    pub bytes: u64,
+    pub tokens: u64,
+}
+
+impl OutputSize {
+    pub fn of(text: &str, estimator: &dyn TokenEstimator) -> Self {
+        Self {
+            bytes: text.len() as u64,
+            tokens: estimator.estimate(text) as u64,
+        }
+    }
+}
+
+impl std::ops::AddAssign for OutputSize {
+    fn add_assign(&mut self, other: Self) {
+        self.bytes += other.bytes;
+        self.tokens += other.tokens;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(text: &str) -> usize {
+        HeuristicEstimator.estimate(text)
+    }
+
+    #[test]
+    fn test_heuristic_prose_and_json() {
+        assert_eq!(estimate(""), 0);
+        // "The", "quick", "brown", "fox", "jumps", "."
+        assert_eq!(estimate("The quick brown fox jumps."), 6);
+        // `{"`, `id`, `":`, `12345` (2), `}`
+        assert_eq!(estimate(r#"{"id": 12345}"#), 6);
+        // Indentation costs a token per line, not per space.
+        let narrow = "{\n  \"a\": 1\n}";
+        let wide = "{\n          \"a\": 1\n}";
+        assert_eq!(estimate(narrow), estimate(wide));
+    }
+
+    #[test]
+    fn test_heuristic_dense_text_costs_more_per_byte() {
+        let english = "the quick brown fox jumps over the lazy dog ".repeat(20);
+        let cjk = "快速的棕色狐狸跳过了懒狗".repeat(20);
+        let base64 = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk".repeat(15);
+        let per_byte = |text: &str| estimate(text) as f64 / text.len() as f64;
+        assert!(per_byte(&cjk) > per_byte(&english) * 1.5);
+        assert!(per_byte(&base64) > per_byte(&english) * 1.5);
+        // Close to the 4-bytes-per-token rule for English.
+        let ratio = english.len() as f64 / estimate(&english) as f64;
+        assert!((3.5..=5.5).contains(&ratio), "{ratio}");
+    }
+
+    #[test]
+    fn test_meter_units() {
+        let tokens = Meter::Tokens(&BytesEstimator);
+        assert_eq!(Meter::Bytes.measure("abcdefgh"), 8);
+        assert_eq!(tokens.measure("abcdefgh"), 2);
+        assert_eq!(tokens.from_bytes(200_000), 50_000);
+        assert_eq!(tokens.byte_budget("abcdefgh", 1), 4);
+        assert_eq!(Meter::Bytes.format(2048), "2.0KB");
+        assert_eq!(tokens.format(1500), "1.5K tokens");
+    }
+}
@@ -0,0 +1,239 @@
+//! `call_tools_batch`: several direct tool calls in parallel, without V8.
+//!
+//! Each item is dispatched like the `call_tool_chain` fast path (fallback
+//! chain, on-demand restart), at most `max_concurrency` at a time, and its
+//! result goes through the output pipeline on its own. Items fail
+//! independently: the batch returns one result or error per item, in order.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::StreamExt;
+use rmcp::schemars;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::sandbox::{
+    ChainOutput, DEFAULT_MAX_OUTPUT_SIZE, call_tool_by_dotted_name, process_output,
+};
+use crate::backend::BackendManager;
+use crate::registry::ToolRegistry;
+use crate::results::ResultStore;
+use crate::tokens::OutputSize;
+
+/// Calls running at once when the batch sets no limit.
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 8;
+
+/// Most calls accepted in one batch.
+pub const MAX_BATCH_ITEMS: usize = 100;
+
+/// One call of a batch.
+#[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
+pub struct BatchCall {
+    /// Tool name, `backend.tool` or a bare name that resolves uniquely.
+    pub tool: String,
+    /// Tool arguments.
+    #[serde(default)]
+    pub arguments: Option<Value>,
+}
+
+/// Run `calls` concurrently and collect one entry per call.
+///
+/// The text output is a JSON array of `{"tool", "ok": true, "result"}` or
+/// `{"tool", "ok": false, "error"}` objects. `result` is the item's pipeline
+/// output, kept as JSON when it still parses. Non-text content blocks of all
+/// items are returned after the text, numbered across the whole batch.
+#[allow(clippy::too_many_arguments)]
+pub async fn handle_call_tools_batch(
+    registry: &Arc<ToolRegistry>,
+    manager: &Arc<BackendManager>,
+    calls: Vec<BatchCall>,
+    max_concurrency: Option<usize>,
+    max_output_size: Option<usize>,
+    session_id: Option<u64>,
+    intent: Option<&str>,
+    output_config: &crate::config::OutputConfig,
+    results: Option<&ResultStore>,
+) -> Result<ChainOutput> {
+    if calls.is_empty() {
+        anyhow::bail!("call_tools_batch needs at least one call");
+    }
+    if calls.len() > MAX_BATCH_ITEMS {
+        anyhow::bail!(
+            "call_tools_batch accepts at most {} calls, got {}",
+            MAX_BATCH_ITEMS,
+            calls.len()
+        );
+    }
+    let concurrency = max_concurrency
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY)
+        .clamp(1, MAX_BATCH_ITEMS);
+    // The budget of the whole batch is shared evenly by its items.
+    let max_output = max_output_size
+        .unwrap_or_else(|| output_config.meter().from_bytes(DEFAULT_MAX_OUTPUT_SIZE))
+        / calls.len();
+
+    // `buffered` keeps the results in call order.
+    let outcomes: Vec<(String, Result<Value>)> = futures::stream::iter(calls)
+        .map(|call| async move {
+            let result =
+                call_tool_by_dotted_name(registry, manager, &call.tool, call.arguments, session_id)
+                    .await;
+            (call.tool, result)
+        })
+        .buffered(concurrency)
+        .collect()
+        .await;
+
+    // Lift content blocks out of all results at once, so their indices are
+    // unique across the batch.
+    let mut values: Value = outcomes
+        .iter()
+        .map(|(_, result)| result.as_ref().ok().cloned().unwrap_or(Value::Null))
+        .collect();
+    let content_blocks = crate::tools::content::extract_content_blocks(&mut values);
+
+    let mut processed = OutputSize::default();
+    let mut items: Vec<Value> = Vec::with_capacity(outcomes.len());
+    for ((tool, result), value) in outcomes.into_iter().zip(
+        values
+            .as_array_mut()
+            .map(std::mem::take)
+            .unwrap_or_default(),
+    ) {
+        let text = match result {
+            Ok(_) => {
+                let raw =
+                    serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+                processed += OutputSize::of(&raw, output_config.estimator());
+                process_output(
+                    raw,
+                    intent,
+                    None,
+                    output_config,
+                    max_output,
+                    results,
+                    registry.similarity(),
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        items.push(match text {
+            Ok(text) => {
+                let result = serde_json::from_str(&text).unwrap_or(Value::String(text));
+                serde_json::json!({ "tool": tool, "ok": true, "result": result })
+            }
+            Err(e) => {
+                serde_json::json!({ "tool": tool, "ok": false, "error": format!("{:#}", e) })
+            }
+        });
+    }
+
+    let text = serde_json::to_string_pretty(&items)?;
+    Ok(ChainOutput {
+        returned: OutputSize::of(&text, output_config.estimator()),
+        processed,
+        text,
+        content_blocks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testutil::{MockBackend, insert_mock};
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn call(tool: &str, arguments: Value) -> BatchCall {
+        BatchCall {
+            tool: tool.to_string(),
+            arguments: Some(arguments),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_returns_per_item_results_in_order() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("mock", Duration::from_millis(20));
+        insert_mock(&manager, &registry, &mock).await;
+
+        let calls = vec![
+            call("mock.echo_tool", json!({"n": 1})),
+            call("mock.error_tool", json!({})),
+            call("missing.tool", json!({})),
+            call("mock.echo_tool", json!({"n": 2})),
+        ];
+        let output = handle_call_tools_batch(
+            &registry,
+            &manager,
+            calls,
+            None,
+            None,
+            None,
+            None,
+            &Default::default(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let items: Value = serde_json::from_str(&output.text).unwrap();
+        assert_eq!(
+            items[0],
+            json!({"tool": "mock.echo_tool", "ok": true, "result": {"n": 1}})
+        );
+        assert_eq!(items[1]["ok"], false);
+        assert!(
+            items[1]["error"]
+                .as_str()
+                .unwrap()
+                .contains("injected error")
+        );
+        assert_eq!(items[2]["ok"], false);
+        assert!(items[2]["error"].as_str().unwrap().contains("not found"));
+        assert_eq!(items[3]["result"], json!({"n": 2}));
+        assert!(output.content_blocks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_respects_concurrency_cap() {
+        let manager = BackendManager::new();
+        let registry = ToolRegistry::new();
+        let mock = MockBackend::new("mock", Duration::from_millis(30));
+        insert_mock(&manager, &registry, &mock).await;
+
+        let calls = (0..6).map(|_| call("mock.slow_tool", json!({}))).collect();
+        handle_call_tools_batch(
+            &registry,
+            &manager,
+            calls,
+            Some(2),
+            None,
+            None,
+            None,
+            &Default::default(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(mock.max_seen_concurrent(), 2);
+        assert_eq!(mock.call_log().await.len(), 6);
+
+        let empty = handle_call_tools_batch(
+            &registry,
+            &manager,
+            Vec::new(),
+            None,
+            None,
+            None,
+            None,
+            &Default::default(),
+            None,
+        )
+        .await;
+        assert!(empty.is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Non-text MCP content blocks (image, audio, embedded resource, resource link).
+//!
+//! Backends return these as typed JSON objects in their MCP wire shape
+//! (`{"type": "image", "data": "...", "mimeType": "image/png"}`), so sandbox code
+//! can inspect them like any other value. Before the text output pipeline runs,
+//! the blocks are lifted out of the result and replaced by small placeholders,
+//! then returned to the client as real content blocks instead of being
+//! truncated or chunked as base64 text.
+
+use serde_json::{Map, Value};
+
+/// Payload fields stripped from the placeholder left in the text output.
+const PAYLOAD_FIELDS: &[&str] = &["data", "blob", "text", "resource"];
+
+/// Check whether a JSON value is a non-text MCP content block.
+///
+/// Requires the fields the MCP schema mandates for each type, so arbitrary
+/// tool JSON that merely has `"type": "image"` is not mistaken for a block.
+pub fn is_content_block(value: &Value) -> bool {
+    let Some(obj) = value.as_object() else {
+        return false;
+    };
+    let has_str = |key: &str| obj.get(key).is_some_and(Value::is_string);
+    match obj.get("type").and_then(Value::as_str) {
+        Some("image") | Some("audio") => has_str("data") && has_str("mimeType"),
+        Some("resource") => obj
+            .get("resource")
+            .and_then(Value::as_object)
+            .is_some_and(|r| r.get("uri").is_some_and(Value::is_string)),
+        Some("resource_link") => has_str("uri") && has_str("name"),
+        _ => false,
+    }
+}
+
+/// Move every non-text content block out of `value`, in document order.
+///
+/// Each block is replaced in place by a placeholder carrying its type, MIME
+/// type / URI, and `content_block` — the index of the block in the final
+/// `CallToolResult.content` (index 0 is the text output).
+pub fn extract_content_blocks(value: &mut Value) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    extract_into(value, &mut blocks);
+    blocks
+}
+
+fn extract_into(value: &mut Value, blocks: &mut Vec<Value>) {
+    if is_content_block(value) {
+        let placeholder = placeholder_for(value, blocks.len() + 1);
+        blocks.push(std::mem::replace(value, placeholder));
+        return;
+    }
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                extract_into(item, blocks);
+            }
+        }
+        Value::Object(obj) => {
+            for item in obj.values_mut() {
+                extract_into(item, blocks);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn placeholder_for(block: &Value, index: usize) -> Value {
+    let mut placeholder = Map::new();
+    if let Some(obj) = block.as_object() {
+        for (key, val) in obj {
+            if !PAYLOAD_FIELDS.contains(&key.as_str()) {
+                placeholder.insert(key.clone(), val.clone());
+            }
+        }
+        // Embedded resources: keep the identifying fields, drop text/blob.
+        if let Some(resource) = obj.get("resource").and_then(Value::as_object) {
+            for key in ["uri", "mimeType"] {
+                if let Some(val) = resource.get(key) {
+                    placeholder.insert(key.to_string(), val.clone());
+                }
+            }
+        }
+    }
+    placeholder.insert("content_block".to_string(), Value::from(index));
+    Value::Object(placeholder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn image() -> Value {
+        json!({"type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png"})
+    }
+
+    #[test]
+    fn test_detects_wire_shaped_blocks() {
+        assert!(is_content_block(&image()));
+        assert!(is_content_block(
+            &json!({"type": "audio", "data": "UklGRg==", "mimeType": "audio/wav"})
+        ));
+        assert!(is_content_block(&json!({
+            "type": "resource",
+            "resource": {"uri": "file:///a.pdf", "mimeType": "application/pdf", "blob": "JVBERi0="}
+        })));
+        assert!(is_content_block(
+            &json!({"type": "resource_link", "uri": "file:///a.txt", "name": "a.txt"})
+        ));
+    }
+
+    #[test]
+    fn test_ignores_lookalike_json() {
+        // Tool data that happens to use "type": "image" is not a content block.
+        assert!(!is_content_block(
+            &json!({"type": "image", "url": "https://x/y.png"})
+        ));
+        assert!(!is_content_block(&json!({"type": "text", "text": "hi"})));
+        assert!(!is_content_block(&json!("image")));
+    }
+
+    #[test]
+    fn test_extract_top_level_block() {
+        let mut value = image();
+        let blocks = extract_content_blocks(&mut value);
+        assert_eq!(blocks, vec![image()]);
+        assert_eq!(value["type"], "image");
+        assert_eq!(value["mimeType"], "image/png");
+        assert_eq!(value["content_block"], 1);
+        assert!(value.get("data").is_none(), "payload must not stay in text");
+    }
+
+    #[test]
+    fn test_extract_nested_blocks_in_order() {
+        let mut value = json!({
+            "page": "https://example.com",
+            "shots": [image(), {"type": "resource", "resource": {"uri": "mem://r", "text": "body"}}],
+        });
+        let blocks = extract_content_blocks(&mut value);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(value["shots"][0]["content_block"], 1);
+        assert_eq!(value["shots"][1]["content_block"], 2);
+        assert_eq!(value["shots"][1]["uri"], "mem://r");
+        assert!(value["shots"][1].get("resource").is_none());
+        assert_eq!(value["page"], "https://example.com");
+    }
+
+    #[test]
+    fn test_extract_leaves_plain_json_untouched() {
+        let original = json!({"a": [1, 2, {"b": "c"}]});
+        let mut value = original.clone();
+        assert!(extract_content_blocks(&mut value).is_empty());
+        assert_eq!(value, original);
+    }
+}
@@ -2,7 +2,7 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::backend::BackendManager;
-use crate::registry::{ToolEntry, ToolRegistry};
+use crate::registry::{ToolAnnotations, ToolEntry, ToolRegistry};
 
 /// Search result returned by search_tools (full mode).
 #[derive(Debug, Serialize)]
@@ -10,6 +10,9 @@ pub struct SearchResult {
     pub name: String,
     pub description: String,
     pub backend: String,
+    /// Annotation hints declared by the backend (read-only, destructive, ...).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<&'static str>,
 }
 
 /// Brief search result — name, backend, first sentence of description, call example.
@@ -20,6 +23,9 @@ pub struct BriefSearchResult {
     pub description: String,
     /// How to call this tool (backend tools are NOT direct MCP tools).
     pub call: String,
+    /// Annotation hints declared by the backend (read-only, destructive, ...).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<&'static str>,
     /// Distinctive terms from this backend for follow-up searches.
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub try_also: Vec<String>,
@@ -31,16 +37,33 @@ pub struct ToolInfoResult {
     pub name: String,
     pub description: String,
     pub backend: String,
+    /// Compact TypeScript-like signature of the parameters.
+    pub signature: String,
+    /// Input schema with refs inlined and noise keywords dropped.
     pub input_schema: Value,
+    /// Schema of the tool's structured result, when the backend declares one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
+    /// MCP annotations (title and behavior hints) declared by the backend.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
 }
 
-/// Brief tool info — name, backend, first sentence of description, parameter names, call example.
+/// Brief tool info — name, backend, first sentence of description, parameter names and signature, call example.
 #[derive(Debug, Serialize)]
 pub struct BriefToolInfoResult {
     pub name: String,
+    /// Human-readable title from the tool's annotations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
     pub backend: String,
     pub description: String,
     pub parameters: Vec<String>,
+    /// Compact TypeScript-like signature, e.g. `{query: string, limit?: integer}`.
+    pub signature: String,
+    /// Annotation hints declared by the backend (read-only, destructive, ...).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<&'static str>,
     /// How to call this tool (backend tools are NOT direct MCP tools).
     pub call: String,
 }
@@ -106,6 +129,7 @@ pub fn handle_search(
     search_tools(registry, query, limit, filter_tags, tracker)
         .into_iter()
         .map(|e| SearchResult {
+            hints: e.hints(),
             name: e.name,
             description: e.description,
             backend: e.backend_name,
@@ -136,6 +160,7 @@ pub fn handle_search_brief(
             );
             let try_also = registry.get_distinctive_terms(&e.backend_name, 3);
             BriefSearchResult {
+                hints: e.hints(),
                 name: e.name,
                 backend: e.backend_name,
                 description: first_sentence(&e.description),
@@ -172,13 +197,19 @@ pub fn handle_list_paginated(
     (page, next_cursor)
 }
 
-/// Handle tool_info: return full schema for a specific tool.
+/// Handle tool_info: return full (normalized) schema for a specific tool.
 pub fn handle_tool_info(registry: &ToolRegistry, tool_name: &str) -> Option<ToolInfoResult> {
-    registry.get_by_name(tool_name).map(|e| ToolInfoResult {
-        name: e.name,
-        description: e.description,
-        backend: e.backend_name,
-        input_schema: e.input_schema,
+    registry.get_by_name(tool_name).map(|e| {
+        let input_schema = e.normalized_input_schema();
+        ToolInfoResult {
+            signature: crate::schema::signature(&input_schema),
+            input_schema,
+            output_schema: e.normalized_output_schema(),
+            name: e.name,
+            description: e.description,
+            backend: e.backend_name,
+            annotations: e.annotations,
+        }
     })
 }
 
@@ -200,6 +231,9 @@ pub fn handle_tool_info_brief(
             sanitize_js_name(orig)
         );
         BriefToolInfoResult {
+            title: e.annotations.as_ref().and_then(|a| a.title.clone()),
+            hints: e.hints(),
+            signature: e.signature(),
             name: e.name,
             backend: e.backend_name,
             description: first_sentence(&e.description),
@@ -209,6 +243,21 @@ pub fn handle_tool_info_brief(
     })
 }
 
+/// Handle tool_types: TypeScript declarations for one tool, or for every tool
+/// of a backend when no tool is given. `None` if nothing matches.
+pub fn handle_tool_types(
+    registry: &ToolRegistry,
+    backend: Option<&str>,
+    tool_name: Option<&str>,
+) -> Option<String> {
+    let tools = match (tool_name, backend) {
+        (Some(name), _) => vec![registry.get_by_name(name)?],
+        (None, Some(backend)) => registry.get_by_backend(backend),
+        (None, None) => Vec::new(),
+    };
+    (!tools.is_empty()).then(|| crate::schema::typescript::declarations(&tools))
+}
+
 /// Handle get_required_keys_for_tool: return env var keys the backend needs.
 pub async fn handle_required_keys_async(
     registry: &ToolRegistry,
@@ -267,6 +316,73 @@ mod tests {
         assert_eq!(extract_param_names(&schema), Vec::<String>::new());
     }
 
+    #[test]
+    fn test_tool_info_normalizes_schema() {
+        let registry = ToolRegistry::new();
+        let entry: ToolEntry = serde_json::from_value(serde_json::json!({
+            "name": "search", "original_name": "search", "description": "Search. More.",
+            "backend_name": "exa",
+            "input_schema": {
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string", "title": "Query"},
+                    "mode": {"$ref": "#/$defs/Mode"}
+                },
+                "required": ["query"],
+                "$defs": {"Mode": {"enum": ["fast", "deep"]}}
+            }
+        }))
+        .unwrap();
+        registry.register_backend_tools("exa", vec![entry]);
+
+        let brief = handle_tool_info_brief(&registry, "exa.search").unwrap();
+        assert_eq!(
+            brief.signature,
+            r#"{query: string, mode?: "fast" | "deep"}"#
+        );
+
+        let full = handle_tool_info(&registry, "exa.search").unwrap();
+        assert_eq!(full.signature, brief.signature);
+        assert_eq!(
+            full.input_schema,
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "mode": {"enum": ["fast", "deep"]}
+                },
+                "required": ["query"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_tool_types() {
+        let registry = ToolRegistry::new();
+        let entry: ToolEntry = serde_json::from_value(serde_json::json!({
+            "name": "search", "original_name": "search", "description": "Search. More.",
+            "backend_name": "exa",
+            "input_schema": {
+                "type": "object",
+                "properties": {"query": {"type": "string"}},
+                "required": ["query"]
+            }
+        }))
+        .unwrap();
+        registry.register_backend_tools("exa", vec![entry]);
+
+        let by_tool = handle_tool_types(&registry, None, Some("exa.search")).unwrap();
+        assert!(by_tool.contains("declare namespace exa {"), "{by_tool}");
+        assert!(by_tool.contains("function search(args: SearchArgs): Promise<any>;"));
+        assert_eq!(
+            handle_tool_types(&registry, Some("exa"), None),
+            Some(by_tool)
+        );
+        assert_eq!(handle_tool_types(&registry, Some("missing"), None), None);
+        assert_eq!(handle_tool_types(&registry, None, None), None);
+    }
+
     #[test]
     fn test_sanitize_js_name_basic() {
         assert_eq!(sanitize_js_name("exa"), "exa");
@@ -0,0 +1,523 @@
+//! Intent stage of the output pipeline.
+//!
+//! When the caller passes `intent` and the output is large, the output is cut
+//! into units, the units are ranked against the intent, and the best ones
+//! that fit the output budget are kept in their original order. JSON keeps
+//! its structure: the members of its main array or object are ranked and the
+//! rest of the document is left as is. Other text is cut into paragraphs or
+//! 10-line groups.
+//!
+//! When no unit relates to the intent, the first units that fit are kept
+//! instead, and the note says nothing matched.
+//!
+//! Units are ranked by BM25 over the intent terms, the scorer behind
+//! `search_tools`. When an embedding index is loaded, that ranking is fused
+//! with embedding similarity by reciprocal rank, so units that only use
+//! synonyms of the intent can still be kept. At most [`MAX_EMBEDDED_UNITS`]
+//! units are embedded, on the blocking thread pool.
+
+use std::sync::Arc;
+
+use serde_json::{Map, Value};
+
+use crate::registry::{bm25_scores, tokenize};
+use crate::tokens::Meter;
+
+/// Threshold in bytes below which intent filtering is skipped (output is small enough to return raw).
+pub const INTENT_SEARCH_THRESHOLD: usize = 5_000;
+
+/// Similarity a unit without any intent term must exceed to be kept.
+const MIN_SIMILARITY: f32 = 0.3;
+
+/// Reciprocal rank fusion constant, as in `ToolRegistry::search_hybrid`.
+const RRF_K: f64 = 60.0;
+
+/// Most units embedded for one output: BM25 matches first, then the rest in
+/// order. Larger outputs are ranked by BM25 alone past this point.
+pub const MAX_EMBEDDED_UNITS: usize = 256;
+
+/// Similarity of texts to a query, e.g. cosine similarity of embeddings.
+pub trait Similarity: Send + Sync {
+    fn similarities(&self, query: &str, texts: &[&str]) -> Vec<f32>;
+}
+
+/// Settings of the intent stage.
+pub struct IntentFilter {
+    /// Size the kept units must fit in, in `meter`'s unit.
+    pub budget: usize,
+    /// Most units kept.
+    pub top_k: usize,
+    pub meter: Meter,
+    pub similarity: Option<Arc<dyn Similarity>>,
+}
+
+/// Output reduced to the units relevant to an intent.
+#[derive(Debug)]
+pub struct Filtered {
+    pub text: String,
+    /// One line saying what was kept, for the end of the output.
+    pub note: String,
+}
+
+impl IntentFilter {
+    /// Reduce `output` to the units most relevant to `intent`. `None` leaves
+    /// the output as it is: it is below the threshold, cannot be split, or
+    /// everything is kept.
+    pub async fn apply(&self, output: &str, intent: &str) -> Option<Filtered> {
+        if self.meter.measure(output) < self.meter.from_bytes(INTENT_SEARCH_THRESHOLD) {
+            return None;
+        }
+        let terms = tokenize(intent);
+        if terms.is_empty() {
+            return None;
+        }
+
+        if output.trim_start().starts_with(['{', '['])
+            && let Ok(value) = serde_json::from_str::<Value>(output)
+        {
+            return self.filter_json(value, intent, &terms).await;
+        }
+        self.filter_text(output, intent, &terms).await
+    }
+
+    async fn filter_text(&self, output: &str, intent: &str, terms: &[String]) -> Option<Filtered> {
+        let chunks = split_into_chunks(output);
+        if chunks.len() <= 1 {
+            return None;
+        }
+        let selection = self.select(intent, terms, &chunks).await?;
+        let text = selection
+            .kept
+            .iter()
+            .map(|&i| chunks[i])
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Some(Filtered {
+            text,
+            note: format!(
+                "[{} — {}/{} sections]",
+                selection.describe(intent),
+                selection.kept.len(),
+                chunks.len()
+            ),
+        })
+    }
+
+    async fn filter_json(
+        &self,
+        mut value: Value,
+        intent: &str,
+        terms: &[String],
+    ) -> Option<Filtered> {
+        let path = main_collection(&value)?;
+        let mut container = &mut value;
+        for key in &path {
+            container = container.get_mut(key)?;
+        }
+
+        // Object members are ranked with their key.
+        let (members, is_array): (Vec<(String, Value)>, bool) = match container.take() {
+            Value::Array(items) => (
+                items
+                    .into_iter()
+                    .map(|item| (String::new(), item))
+                    .collect(),
+                true,
+            ),
+            Value::Object(map) => (map.into_iter().collect(), false),
+            _ => return None,
+        };
+        let texts: Vec<String> = members
+            .iter()
+            .map(|(key, member)| {
+                let json = serde_json::to_string_pretty(member).unwrap_or_default();
+                if is_array {
+                    json
+                } else {
+                    format!("{key}: {json}")
+                }
+            })
+            .collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let selection = self.select(intent, terms, &texts).await?;
+        let described = selection.describe(intent);
+
+        let total = members.len();
+        let count = selection.kept.len();
+        let mut kept = selection.kept.into_iter().peekable();
+        let members = members
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, member)| kept.next_if_eq(&i).map(|_| member));
+        *container = if is_array {
+            Value::Array(members.map(|(_, member)| member).collect())
+        } else {
+            Value::Object(members.collect::<Map<String, Value>>())
+        };
+
+        let location: String = path.iter().map(|key| format!(".{key}")).collect();
+        Some(Filtered {
+            text: serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string()),
+            note: format!(
+                "[{} — {}/{} {} of ${}]",
+                described,
+                count,
+                total,
+                if is_array { "items" } else { "keys" },
+                location
+            ),
+        })
+    }
+
+    /// The units to keep, or `None` if every unit is kept. When no unit
+    /// relates to the intent, the first units that fit are kept.
+    async fn select(&self, intent: &str, terms: &[String], texts: &[&str]) -> Option<Selection> {
+        let docs: Vec<Vec<String>> = texts.iter().map(|text| tokenize(text)).collect();
+        let mut ranked = ranking(bm25_scores(terms, &docs).into_iter(), 0.0);
+
+        // Reciprocal rank fusion with embedding similarity, as in `search_hybrid`.
+        if let Some(similarity) = &self.similarity {
+            let mut matched = vec![false; texts.len()];
+            for &i in &ranked {
+                matched[i] = true;
+            }
+            let candidates: Vec<usize> = ranked
+                .iter()
+                .copied()
+                .chain((0..texts.len()).filter(|&i| !matched[i]))
+                .take(MAX_EMBEDDED_UNITS)
+                .collect();
+            let similarity = Arc::clone(similarity);
+            let intent = intent.to_string();
+            let candidate_texts: Vec<String> =
+                candidates.iter().map(|&i| texts[i].to_string()).collect();
+            // Encoding is CPU-bound, so it stays off the async workers.
+            let scores = tokio::task::spawn_blocking(move || {
+                let texts: Vec<&str> = candidate_texts.iter().map(String::as_str).collect();
+                similarity.similarities(&intent, &texts)
+            })
+            .await
+            .unwrap_or_default();
+            let semantic: Vec<usize> =
+                ranking(scores.into_iter().map(f64::from), f64::from(MIN_SIMILARITY))
+                    .into_iter()
+                    .map(|j| candidates[j])
+                    .collect();
+            let mut fused = vec![0.0f64; texts.len()];
+            for list in [&ranked, &semantic] {
+                for (rank, &i) in list.iter().enumerate() {
+                    fused[i] += 1.0 / (RRF_K + rank as f64 + 1.0);
+                }
+            }
+            ranked = ranking(fused.into_iter(), 0.0);
+        }
+        let matched = !ranked.is_empty();
+        if !matched {
+            ranked = (0..texts.len()).collect();
+        }
+
+        // Best units first while they fit; the best one is always kept.
+        let mut kept = Vec::new();
+        let mut used = 0;
+        for i in ranked {
+            if kept.len() == self.top_k {
+                break;
+            }
+            let size = self.meter.measure(texts[i]);
+            if !kept.is_empty() && used + size > self.budget {
+                continue;
+            }
+            used += size;
+            kept.push(i);
+        }
+        if kept.len() == texts.len() {
+            return None;
+        }
+        kept.sort_unstable();
+        Some(Selection { kept, matched })
+    }
+}
+
+/// Units kept by [`IntentFilter::select`].
+struct Selection {
+    /// Indices of the kept units, in their original order.
+    kept: Vec<usize>,
+    /// Whether any unit related to the intent.
+    matched: bool,
+}
+
+impl Selection {
+    /// Start of the note saying how the units were chosen.
+    fn describe(&self, intent: &str) -> String {
+        if self.matched {
+            format!("Filtered by intent: '{intent}'")
+        } else {
+            format!("Nothing matched intent: '{intent}', kept the first")
+        }
+    }
+}
+
+/// Indices with a score above `min`, best first (ties in original order).
+fn ranking(scores: impl Iterator<Item = f64>, min: f64) -> Vec<usize> {
+    let mut scored: Vec<(usize, f64)> = scores
+        .enumerate()
+        .filter(|(_, score)| *score > min)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+/// Object keys leading to the array or object whose members are ranked.
+///
+/// Starting at the root, descends into the member of an object that holds
+/// most of its size while that member is itself a collection, so a search
+/// response like `{"query": ..., "results": [...]}` ranks its results.
+fn main_collection(value: &Value) -> Option<Vec<String>> {
+    let members = |value: &Value| match value {
+        Value::Array(items) => items.len(),
+        Value::Object(map) => map.len(),
+        _ => 0,
+    };
+    let size = |value: &Value| serde_json::to_string(value).map_or(0, |s| s.len());
+
+    let mut path = Vec::new();
+    let mut current = value;
+    while let Value::Object(map) = current {
+        let total = size(current);
+        let Some((key, largest)) = map.iter().max_by_key(|(_, member)| size(member)) else {
+            break;
+        };
+        if members(largest) < 2 || size(largest) * 2 < total {
+            break;
+        }
+        path.push(key.clone());
+        current = largest;
+    }
+    (members(current) >= 2).then_some(path)
+}
+
+/// Split text into chunks: paragraphs (blank-line separated) if there are enough,
+/// otherwise 10-line groups.
+fn split_into_chunks(text: &str) -> Vec<&str> {
+    // Try paragraph splitting first
+    let paragraphs: Vec<&str> = text
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .collect();
+    if paragraphs.len() >= 3 {
+        return paragraphs;
+    }
+
+    // Fall back to 10-line groups
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() <= 10 {
+        return vec![text];
+    }
+    lines
+        .chunks(10)
+        .map(|chunk| {
+            let start = chunk[0].as_ptr() as usize - text.as_ptr() as usize;
+            let end = chunk
+                .last()
+                .map(|l| l.as_ptr() as usize + l.len() - text.as_ptr() as usize)
+                .unwrap_or(start);
+            &text[start..end]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn filter(top_k: usize, similarity: Option<Arc<dyn Similarity>>) -> IntentFilter {
+        IntentFilter {
+            budget: 200_000,
+            top_k,
+            meter: Meter::Bytes,
+            similarity,
+        }
+    }
+
+    fn sections(relevant: &[usize], text: &str) -> String {
+        let mut output = String::new();
+        for i in 0..20 {
+            if relevant.contains(&i) {
+                output.push_str(&format!("Section {i}: {text}\n\n"));
+            } else {
+                output.push_str(&format!(
+                    "Section {i}: {} unrelated content padding here.\n\n",
+                    "lorem ipsum ".repeat(30)
+                ));
+            }
+        }
+        output
+    }
+
+    #[tokio::test]
+    async fn test_intent_small_output_passthrough() {
+        let small = "This is a small output about errors.";
+        assert!(small.len() < INTENT_SEARCH_THRESHOLD);
+        assert!(filter(10, None).apply(small, "errors").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_intent_filters_relevant_sections() {
+        // Build output > 5KB with distinct paragraphs
+        let output = sections(
+            &[5, 12],
+            "This section discusses error handling and retry logic.",
+        );
+        assert!(output.len() > INTENT_SEARCH_THRESHOLD);
+
+        let result = filter(10, None)
+            .apply(&output, "error handling retry")
+            .await
+            .unwrap();
+        assert_eq!(
+            result.note,
+            "[Filtered by intent: 'error handling retry' — 2/20 sections]"
+        );
+        assert!(result.text.starts_with("Section 5: "));
+        assert!(result.text.contains("Section 12: "));
+        assert!(result.text.len() < output.len());
+    }
+
+    #[tokio::test]
+    async fn test_intent_no_matches_keeps_first_units() {
+        let output = sections(&[], "");
+        assert!(output.len() > INTENT_SEARCH_THRESHOLD);
+
+        // Intent with no matching terms — still reduced, to the first sections.
+        let result = filter(3, None)
+            .apply(&output, "xyzzy quantum entanglement")
+            .await
+            .unwrap();
+        assert_eq!(
+            result.note,
+            "[Nothing matched intent: 'xyzzy quantum entanglement', kept the first — 3/20 sections]"
+        );
+        assert!(result.text.starts_with("Section 0: "));
+        assert!(result.text.contains("Section 2: "));
+        assert!(!result.text.contains("Section 3: "));
+
+        let items: Vec<Value> = (0..200)
+            .map(|i| json!({"id": i, "name": format!("entry {i}")}))
+            .collect();
+        let output = serde_json::to_string_pretty(&json!({ "results": items })).unwrap();
+        assert!(output.len() > INTENT_SEARCH_THRESHOLD);
+        let mut tight = filter(10, None);
+        tight.budget = 1;
+        let result = tight.apply(&output, "timeout").await.unwrap();
+        assert_eq!(
+            result.note,
+            "[Nothing matched intent: 'timeout', kept the first — 1/200 items of $.results]"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_intent_keeps_top_k_within_budget() {
+        let output = sections(&[1, 2, 3, 4], "retry logic");
+        let result = filter(2, None).apply(&output, "retry").await.unwrap();
+        assert!(result.note.ends_with("2/20 sections]"));
+
+        let mut tight = filter(10, None);
+        tight.budget = 1;
+        // Nothing fits: only the best section is kept.
+        let result = tight.apply(&output, "retry").await.unwrap();
+        assert!(result.note.ends_with("1/20 sections]"));
+    }
+
+    #[tokio::test]
+    async fn test_intent_keeps_json_structure() {
+        let results: Vec<Value> = (0..60)
+            .map(|i| {
+                let title = if i % 20 == 7 {
+                    "Connection timeout while fetching".to_string()
+                } else {
+                    format!("Ordinary result number {i} {}", "filler ".repeat(10))
+                };
+                json!({"id": i, "title": title})
+            })
+            .collect();
+        let output =
+            serde_json::to_string_pretty(&json!({"query": "q", "results": results})).unwrap();
+
+        let result = filter(10, None).apply(&output, "timeout").await.unwrap();
+        assert_eq!(
+            result.note,
+            "[Filtered by intent: 'timeout' — 3/60 items of $.results]"
+        );
+        let value: Value = serde_json::from_str(&result.text).unwrap();
+        assert_eq!(value["query"], "q");
+        let ids: Vec<&Value> = value["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| &r["id"])
+            .collect();
+        assert_eq!(ids, [&json!(7), &json!(27), &json!(47)]);
+    }
+
+    /// Scores texts mentioning "failure" as close to any query.
+    struct Synonyms;
+
+    impl Similarity for Synonyms {
+        fn similarities(&self, _query: &str, texts: &[&str]) -> Vec<f32> {
+            texts
+                .iter()
+                .map(|t| if t.contains("failure") { 0.8 } else { 0.1 })
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_intent_similarity_finds_synonyms() {
+        let mut output = sections(&[3], "Connection error on startup.");
+        output.push_str("Section 20: Disk failure on node 4.\n\n");
+
+        let lexical = filter(10, None).apply(&output, "error").await.unwrap();
+        assert!(!lexical.text.contains("Disk failure"));
+
+        let semantic = filter(10, Some(Arc::new(Synonyms)))
+            .apply(&output, "error")
+            .await
+            .unwrap();
+        assert!(semantic.text.contains("Connection error"));
+        assert!(semantic.text.contains("Disk failure"));
+        assert!(semantic.note.ends_with("2/21 sections]"));
+    }
+
+    /// Records how many texts it was asked to embed.
+    #[derive(Default)]
+    struct Counting(std::sync::Mutex<usize>);
+
+    impl Similarity for Counting {
+        fn similarities(&self, _query: &str, texts: &[&str]) -> Vec<f32> {
+            *self.0.lock().unwrap() = texts.len();
+            vec![0.0; texts.len()]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_intent_embeds_at_most_max_units() {
+        let items: Vec<Value> = (0..1000)
+            .map(|i| json!({"id": i, "title": if i == 999 { "timeout" } else { "ok" }}))
+            .collect();
+        let output = serde_json::to_string_pretty(&items).unwrap();
+
+        let counting = Arc::new(Counting::default());
+        let result = filter(10, Some(Arc::clone(&counting) as _))
+            .apply(&output, "timeout")
+            .await
+            .unwrap();
+        assert_eq!(*counting.0.lock().unwrap(), MAX_EMBEDDED_UNITS);
+        // The BM25 match is embedded (and kept) even though it comes last.
+        assert!(result.text.contains("\"id\": 999"));
+    }
+}
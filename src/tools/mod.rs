@@ -1,5 +1,11 @@
+pub mod batch;
+pub mod content;
 pub mod discovery;
+pub mod intent;
 #[allow(dead_code)] // infrastructure for chunk_output param in call_tool_chain
 pub mod json_chunker;
+pub mod native;
 pub mod register;
 pub mod sandbox;
+pub mod select;
+pub mod typecheck;
@@ -0,0 +1,122 @@
+//! Backend tools exposed directly as MCP tools ("native" tools).
+//!
+//! Tools pinned in config (`pinned_tools`, globally or per backend), and the
+//! tools of the toolsets a session enabled with `enable_toolset`, are listed
+//! in `tools/list` next to the meta-tools with the backend's real input schema
+//! and annotations. Calls to them skip the `call_tool_chain` round trip but
+//! are dispatched the same way as its direct fast path (fallback chain,
+//! on-demand restart) and go through the same output pipeline.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use rmcp::model::{JsonObject, Tool};
+use serde_json::Value;
+
+use super::sandbox::{
+    ChainOutput, DEFAULT_MAX_OUTPUT_SIZE, call_tool_by_dotted_name, finish_output,
+};
+use crate::backend::BackendManager;
+use crate::registry::{ToolEntry, ToolRegistry};
+use crate::results::ResultStore;
+
+/// MCP tool definition of a registry entry, under the entry's name.
+pub fn to_mcp_tool(entry: &ToolEntry) -> Tool {
+    let schema = match &entry.input_schema {
+        Value::Object(schema) => schema.clone(),
+        // Backends must send an object schema; don't pass anything else on.
+        _ => JsonObject::from_iter([("type".to_string(), Value::from("object"))]),
+    };
+    let tool = Tool::new(
+        entry.name.clone(),
+        entry.description.clone(),
+        Arc::new(schema),
+    );
+    match &entry.annotations {
+        Some(a) => {
+            let mut annotations = rmcp::model::ToolAnnotations::new();
+            annotations.title = a.title.clone();
+            annotations.read_only_hint = a.read_only_hint;
+            annotations.destructive_hint = a.destructive_hint;
+            annotations.idempotent_hint = a.idempotent_hint;
+            annotations.open_world_hint = a.open_world_hint;
+            tool.annotate(annotations)
+        }
+        None => tool,
+    }
+}
+
+/// Call a native tool by the name it is listed under.
+pub async fn call_native_tool(
+    registry: &Arc<ToolRegistry>,
+    manager: &Arc<BackendManager>,
+    name: &str,
+    arguments: Option<JsonObject>,
+    session_id: Option<u64>,
+    output_config: &crate::config::OutputConfig,
+    results: Option<&ResultStore>,
+) -> Result<ChainOutput> {
+    let value = call_tool_by_dotted_name(
+        registry,
+        manager,
+        name,
+        arguments.map(Value::Object),
+        session_id,
+    )
+    .await?;
+    // Native calls take no `max_output_size`; use the `call_tool_chain` default.
+    let max_output = output_config.meter().from_bytes(DEFAULT_MAX_OUTPUT_SIZE);
+    finish_output(
+        value,
+        None,
+        None,
+        output_config,
+        max_output,
+        results,
+        registry.similarity(),
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_native_tool_keeps_schema_and_hints() {
+        let entry = ToolEntry {
+            name: "exa.web_search".into(),
+            original_name: "web_search".into(),
+            description: "Search the web".into(),
+            backend_name: "exa".into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {"query": {"type": "string"}},
+                "required": ["query"]
+            }),
+            tags: Vec::new(),
+            output_schema: None,
+            annotations: Some(crate::registry::ToolAnnotations {
+                read_only_hint: Some(true),
+                ..Default::default()
+            }),
+        };
+        let tool = to_mcp_tool(&entry);
+        assert_eq!(tool.name, "exa.web_search");
+        assert_eq!(
+            Value::Object((*tool.input_schema).clone()),
+            entry.input_schema
+        );
+        assert_eq!(tool.annotations.and_then(|a| a.read_only_hint), Some(true));
+
+        let bad = ToolEntry {
+            input_schema: Value::Null,
+            ..entry
+        };
+        assert_eq!(
+            Value::Object((*to_mcp_tool(&bad).input_schema).clone()),
+            json!({"type": "object"})
+        );
+    }
+}
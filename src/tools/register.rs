@@ -164,6 +164,9 @@ pub async fn handle_register(
         retry: Default::default(),
         prerequisite: None,
         rate_limit: None,
+        cache: None,
+        validate_arguments: true,
+        coerce_arguments: false,
         tags: Vec::new(),
         fallback_chain: Vec::new(),
         tools: cli_tools,
@@ -177,6 +180,9 @@ pub async fn handle_register(
         shutdown_grace_period: std::time::Duration::from_secs(5),
         max_memory_mb: None,
         oauth: None,
+        elicitation: Default::default(),
+        sampling: Default::default(),
+        pinned_tools: Vec::new(),
     };
 
     let tool_count = manager.add_backend(&name, config, registry).await?;
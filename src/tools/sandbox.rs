@@ -1,14 +1,41 @@
 use anyhow::{Context, Result};
 use serde_json::Value;
+use std::borrow::Cow;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
 use tracing::debug;
 
 use crate::backend::BackendManager;
 use crate::registry::ToolRegistry;
+use crate::results::ResultStore;
+use crate::tokens::{Meter, OutputSize};
+use crate::tools::intent::{IntentFilter, Similarity};
+use crate::tools::select::Selector;
+use crate::tools::typecheck;
+
+/// Output of a `call_tool_chain` run.
+///
+/// `text` is the result after the output pipeline (intent filter, chunking,
+/// truncation). `content_blocks` holds any image/audio/resource blocks lifted
+/// out of the raw result, in their MCP wire shape, to be returned to the
+/// client as real content blocks after the text. `returned` and `processed`
+/// are the sizes of the text and of the raw result, for the session stats.
+#[derive(Debug, Default)]
+pub struct ChainOutput {
+    pub text: String,
+    pub content_blocks: Vec<Value>,
+    pub returned: OutputSize,
+    pub processed: OutputSize,
+}
+
+/// Output budget of a call that sets no `max_output_size`, in bytes.
+pub const DEFAULT_MAX_OUTPUT_SIZE: usize = 200_000;
 
 /// Handle call_tool_chain: execute TypeScript code that can call backend tools.
 ///
+/// With `type_check`, argument object literals in the code are first checked
+/// against the tools' input schemas (see [`typecheck`]).
+///
 /// Strategy:
 /// 1. Try direct tool call parsing (fast path — bypasses sandbox semaphore)
 /// 2. If that fails and the sandbox feature is enabled, acquire sandbox semaphore
@@ -24,14 +51,42 @@ pub async fn handle_call_tool_chain(
     sandbox_semaphore: &Semaphore,
     session_id: Option<u64>,
     intent: Option<&str>,
+    select: Option<&str>,
+    type_check: bool,
     output_config: &crate::config::OutputConfig,
-) -> Result<String> {
-    let max_output = max_output_size.unwrap_or(200_000);
+    results: Option<&ResultStore>,
+) -> Result<ChainOutput> {
+    let max_output = max_output_size
+        .unwrap_or_else(|| output_config.meter().from_bytes(DEFAULT_MAX_OUTPUT_SIZE));
+    // Reject a malformed `select` before running anything.
+    let selector = select.map(Selector::parse).transpose()?;
+    let selector = selector.as_ref();
+    if type_check {
+        typecheck::check(&registry.get_all(), code).map_err(|mismatch| {
+            crate::error::GatewayError::InvalidArguments {
+                backend: mismatch.backend,
+                tool: mismatch.tool,
+                message: format!(
+                    "type check failed at line {}: arguments do not match the input schema:\n{}",
+                    mismatch.line, mismatch.errors
+                ),
+            }
+        })?;
+    }
 
     // Try to parse as a direct tool call (fast path — no V8, no semaphore needed).
     // Pattern: `await manual_name.tool_name({...})` or JSON with tool_name + arguments
     if let Some(result) = try_direct_tool_call(registry, manager, code, session_id).await {
-        return result.map(|v| process_output(v, intent, output_config, max_output));
+        return finish_output(
+            result?,
+            intent,
+            selector,
+            output_config,
+            max_output,
+            results,
+            registry.similarity(),
+        )
+        .await;
     }
 
     // Fall back to full TypeScript sandbox — acquire semaphore first
@@ -45,10 +100,7 @@ pub async fn handle_call_tool_chain(
         let _permit = match tokio::time::timeout(timeout_dur, sandbox_semaphore.acquire()).await {
             Ok(Ok(permit)) => permit,
             Ok(Err(_)) => anyhow::bail!("sandbox semaphore closed"),
-            Err(_) => anyhow::bail!(
-                "sandbox concurrency limit reached. All V8 isolates are busy. \
-                 Try again shortly or increase max_concurrent_sandboxes in config."
-            ),
+            Err(_) => return Err(crate::error::GatewayError::SandboxBusy.into()),
         };
         let result = crate::sandbox::execute(
             registry,
@@ -59,7 +111,16 @@ pub async fn handle_call_tool_chain(
             session_id,
         )
         .await?;
-        return Ok(process_output(result, intent, output_config, max_output));
+        return finish_output(
+            result,
+            intent,
+            selector,
+            output_config,
+            max_output,
+            results,
+            registry.similarity(),
+        )
+        .await;
     }
 
     #[cfg(not(feature = "sandbox"))]
@@ -80,7 +141,7 @@ async fn try_direct_tool_call(
     manager: &Arc<BackendManager>,
     code: &str,
     session_id: Option<u64>,
-) -> Option<Result<String>> {
+) -> Option<Result<Value>> {
     let code = code.trim();
 
     // Try JSON format first
@@ -209,7 +270,7 @@ fn strip_trailing_return(code: &str) -> &str {
 }
 
 /// Handle `__getToolInterface("backend.tool")` without V8.
-fn try_introspection_call(registry: &Arc<ToolRegistry>, code: &str) -> Option<String> {
+fn try_introspection_call(registry: &Arc<ToolRegistry>, code: &str) -> Option<Value> {
     let code = code.trim().trim_end_matches(';');
     let code = code.strip_prefix("return ").unwrap_or(code).trim();
     let code = strip_var_assignment(code);
@@ -234,12 +295,17 @@ fn try_introspection_call(registry: &Arc<ToolRegistry>, code: &str) -> Option<St
     } else {
         &entry.original_name
     };
-    let schema = serde_json::json!({
+    let input_schema = entry.normalized_input_schema();
+    let mut interface = serde_json::json!({
         "name": orig,
         "description": entry.description,
-        "input_schema": entry.input_schema,
+        "signature": crate::schema::signature(&input_schema),
+        "input_schema": input_schema,
     });
-    serde_json::to_string_pretty(&schema).ok()
+    if let Some(output_schema) = entry.normalized_output_schema() {
+        interface["output_schema"] = output_schema;
+    }
+    Some(interface)
 }
 
 /// Call a tool using "backend.tool_name" dotted notation, or just "tool_name".
@@ -247,13 +313,13 @@ fn try_introspection_call(registry: &Arc<ToolRegistry>, code: &str) -> Option<St
 /// After namespacing, tools may exist under both `backend.tool_name` and bare `tool_name`
 /// (if no collision). This function resolves both forms and always passes the
 /// `original_name` to the backend MCP server (which doesn't know about namespacing).
-async fn call_tool_by_dotted_name(
+pub(crate) async fn call_tool_by_dotted_name(
     registry: &Arc<ToolRegistry>,
     manager: &Arc<BackendManager>,
     dotted_name: &str,
     arguments: Option<Value>,
     session_id: Option<u64>,
-) -> Result<String> {
+) -> Result<Value> {
     // Resolve: try looking up the full dotted name first (handles both namespaced and bare)
     let entry = if let Some(e) = registry.get_by_name(dotted_name) {
         e
@@ -292,9 +358,12 @@ async fn call_tool_by_dotted_name(
         )
         .await;
 
-    let value = match result {
-        Ok(v) => v,
-        Err(e) if e.to_string().contains("not available") && e.to_string().contains("Stopped") => {
+    match result {
+        Ok(v) => Ok(v),
+        Err(e)
+            if crate::error::GatewayError::find(&e)
+                .is_some_and(crate::error::GatewayError::is_stopped) =>
+        {
             // Attempt on-demand restart and retry once
             debug!(backend = %entry.backend_name, tool = %call_name, "attempting on-demand restart for stopped backend");
             manager
@@ -309,51 +378,104 @@ async fn call_tool_by_dotted_name(
                         "retry after restart: tool '{}' on '{}'",
                         call_name, entry.backend_name
                     )
-                })?
+                })
         }
-        Err(e) => {
-            return Err(e).with_context(|| {
-                format!("tool '{}' on backend '{}'", call_name, entry.backend_name)
-            });
-        }
-    };
+        Err(e) => Err(e)
+            .with_context(|| format!("tool '{}' on backend '{}'", call_name, entry.backend_name)),
+    }
+}
 
-    serde_json::to_string_pretty(&value)
-        .map_err(|e| anyhow::anyhow!("failed to serialize tool result: {e}"))
+/// Lift non-text content blocks out of a raw result, then run the remaining
+/// JSON through the text output pipeline.
+pub(crate) async fn finish_output(
+    mut value: Value,
+    intent: Option<&str>,
+    select: Option<&Selector>,
+    config: &crate::config::OutputConfig,
+    max_output: usize,
+    results: Option<&ResultStore>,
+    similarity: Option<Arc<dyn Similarity>>,
+) -> Result<ChainOutput> {
+    let content_blocks = crate::tools::content::extract_content_blocks(&mut value);
+    let raw = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
+    let processed = OutputSize::of(&raw, config.estimator());
+    let text = process_output(raw, intent, select, config, max_output, results, similarity).await?;
+    Ok(ChainOutput {
+        returned: OutputSize::of(&text, config.estimator()),
+        processed,
+        text,
+        content_blocks,
+    })
 }
 
-/// Apply the full output processing pipeline: intent filter → auto-chunk JSON → truncate.
+/// Apply the full output processing pipeline: select → intent filter → auto-chunk JSON → truncate.
 ///
 /// Each stage is configurable via `OutputConfig`. The pipeline preserves the most
-/// relevant content while minimizing token usage.
-fn process_output(
+/// relevant content while minimizing token usage. When intent filtering,
+/// chunking or truncation drops part of the output and a result store is
+/// given, the raw output is kept there and the returned text ends with its
+/// handle. Sizes and `max_output` are in `config.budget_unit`. `similarity`,
+/// if given, helps rank output for the intent. Fails only when `select` does
+/// not match the output.
+pub(crate) async fn process_output(
     raw: String,
     intent: Option<&str>,
+    select: Option<&Selector>,
     config: &crate::config::OutputConfig,
     max_output: usize,
-) -> String {
-    let raw_bytes = raw.len();
+    results: Option<&ResultStore>,
+    similarity: Option<Arc<dyn Similarity>>,
+) -> Result<String> {
+    let meter = config.meter();
+    let raw_size = meter.measure(&raw);
+
+    // Stage 0: Projection (if select provided)
+    let selected = match select {
+        Some(selector) => {
+            let value = selector.apply_to_text(&raw)?;
+            Some(serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string()))
+        }
+        None => None,
+    };
+    let input = selected.as_deref().unwrap_or(&raw);
 
     // Stage 1: Intent filtering (if intent provided)
-    let after_intent = if let Some(intent) = intent {
-        filter_by_intent(&raw, intent)
-    } else {
-        raw
+    let intent_filter = IntentFilter {
+        budget: max_output,
+        top_k: config.intent_top_k,
+        meter,
+        similarity: similarity.filter(|_| config.intent_semantic),
+    };
+    let mut intent_note = None;
+    let filtered = match intent {
+        Some(intent) => intent_filter.apply(input, intent).await,
+        None => None,
     };
+    let after_intent: Cow<'_, str> = match filtered {
+        Some(filtered) => {
+            intent_note = Some(filtered.note);
+            Cow::Owned(filtered.text)
+        }
+        None => Cow::Borrowed(input),
+    };
+    let filtered = intent_note.is_some();
 
     // Stage 2: Auto-chunk large JSON (if enabled and output is parseable JSON above threshold)
+    let mut chunked = false;
     let after_chunk = if config.auto_chunk_json
-        && after_intent.len() > config.chunk_threshold
+        && meter.measure(&after_intent) > config.chunk_threshold()
         && after_intent.trim_start().starts_with(['{', '['])
     {
         if let Ok(value) = serde_json::from_str::<serde_json::Value>(&after_intent) {
             // Try uniform array collapse first (common in loop patterns)
             if let Some(collapsed) = crate::tools::json_chunker::detect_uniform_array(&value, 3) {
-                collapsed
+                chunked = true;
+                Cow::Owned(collapsed)
             } else {
                 let chunks = crate::tools::json_chunker::chunk_json(&value, "");
                 if chunks.len() > 1 {
-                    crate::tools::json_chunker::chunk_summary(&chunks)
+                    chunked = true;
+                    Cow::Owned(crate::tools::json_chunker::chunk_summary(&chunks))
                 } else {
                     after_intent
                 }
@@ -366,130 +488,63 @@ fn process_output(
     };
 
     // Stage 3: Truncation (smart head/tail or simple cutoff based on config)
+    let truncated = meter.measure(&after_chunk) > max_output;
     let final_output = if config.smart_truncation {
-        truncate_output(&after_chunk, max_output)
+        truncate_output(&after_chunk, max_output, meter)
     } else {
-        simple_truncate(&after_chunk, max_output)
+        simple_truncate(&after_chunk, max_output, meter)
+    };
+    // Say what the intent filter kept after the output, where truncation can't cut it.
+    let final_output = match intent_note {
+        Some(note) => format!("{final_output}\n\n{note}"),
+        None => final_output,
     };
 
     // Stage 4: Append size metadata when pipeline reduced output significantly
-    let returned_bytes = final_output.len();
-    if raw_bytes > returned_bytes + 200 {
-        let saved_pct = ((raw_bytes - returned_bytes) as f64 / raw_bytes as f64 * 100.0) as u32;
+    let returned_size = meter.measure(&final_output);
+    let output = if raw_size > returned_size + meter.from_bytes(200) {
+        let saved_pct = ((raw_size - returned_size) as f64 / raw_size as f64 * 100.0) as u32;
         format!(
-            "{}\n\n[Output: {:.1}KB returned, {:.1}KB processed, {}% reduced]",
+            "{}\n\n[Output: {} returned, {} processed, {}% reduced]",
             final_output,
-            returned_bytes as f64 / 1024.0,
-            raw_bytes as f64 / 1024.0,
+            meter.format(returned_size),
+            meter.format(raw_size),
             saved_pct
         )
     } else {
         final_output
-    }
+    };
+
+    // Stage 5: Keep the full output when a stage dropped part of it
+    Ok(match results {
+        Some(results) if filtered || chunked || truncated => match results.put(raw) {
+            Some(id) => format!(
+                "{output}\n[Full result kept as '{id}': read it with read_result(handle=\"{id}\") \
+                 or gatemini://result/{id}]"
+            ),
+            None => output,
+        },
+        _ => output,
+    })
 }
 
 /// Simple head-only truncation (legacy behavior, used when smart_truncation=false).
-fn simple_truncate(s: &str, max_size: usize) -> String {
-    if s.len() <= max_size {
+fn simple_truncate(s: &str, max_size: usize, meter: Meter) -> String {
+    if meter.measure(s) <= max_size {
         s.to_string()
     } else {
-        let boundary = s.floor_char_boundary(max_size);
+        let boundary = s.floor_char_boundary(meter.byte_budget(s, max_size));
         format!("{}\n... [output truncated]", &s[..boundary])
     }
 }
 
-/// Threshold below which intent filtering is skipped (output is small enough to return raw).
-const INTENT_SEARCH_THRESHOLD: usize = 5_000;
-
-/// Filter output by intent relevance. When output exceeds 5KB and an intent is provided,
-/// splits the output into chunks and returns only those matching the intent terms.
-fn filter_by_intent(output: &str, intent: &str) -> String {
-    if output.len() < INTENT_SEARCH_THRESHOLD {
-        return output.to_string();
-    }
-
-    let intent_terms = crate::registry::tokenize(intent);
-    if intent_terms.is_empty() {
-        return output.to_string();
-    }
-
-    // Split into chunks: paragraphs (blank-line separated) or 10-line groups
-    let chunks = split_into_chunks(output);
-    if chunks.len() <= 1 {
-        return output.to_string();
-    }
-
-    // Score each chunk by fraction of intent terms present
-    let scored: Vec<(&str, f64)> = chunks
-        .iter()
-        .map(|chunk| {
-            let chunk_terms: Vec<String> = crate::registry::tokenize(chunk);
-            let hits = intent_terms
-                .iter()
-                .filter(|t| chunk_terms.contains(t))
-                .count();
-            (*chunk, hits as f64 / intent_terms.len().max(1) as f64)
-        })
-        .collect();
-
-    // Keep chunks with score > 0.3 (at least 30% of intent terms present)
-    let filtered: Vec<&str> = scored
-        .iter()
-        .filter(|(_, s)| *s > 0.3)
-        .map(|(c, _)| *c)
-        .collect();
-
-    if filtered.is_empty() {
-        // No matches — return original unchanged
-        return output.to_string();
-    }
-
-    format!(
-        "[Filtered by intent: '{}' — {}/{} sections]\n\n{}",
-        intent,
-        filtered.len(),
-        chunks.len(),
-        filtered.join("\n\n")
-    )
-}
-
-/// Split text into chunks: paragraphs (blank-line separated) if there are enough,
-/// otherwise 10-line groups.
-fn split_into_chunks(text: &str) -> Vec<&str> {
-    // Try paragraph splitting first
-    let paragraphs: Vec<&str> = text
-        .split("\n\n")
-        .filter(|p| !p.trim().is_empty())
-        .collect();
-    if paragraphs.len() >= 3 {
-        return paragraphs;
-    }
-
-    // Fall back to 10-line groups
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.len() <= 10 {
-        return vec![text];
-    }
-    lines
-        .chunks(10)
-        .map(|chunk| {
-            let start = chunk[0].as_ptr() as usize - text.as_ptr() as usize;
-            let end = chunk
-                .last()
-                .map(|l| l.as_ptr() as usize + l.len() - text.as_ptr() as usize)
-                .unwrap_or(start);
-            &text[start..end]
-        })
-        .collect()
-}
-
 /// Smart truncation: keeps head 60% + tail 40% of output, snapped to line boundaries.
 ///
 /// This preserves both the beginning (setup, context) and end (results, errors,
 /// summaries) of tool output — the tail is often the most important part that
-/// a simple head-only cutoff would lose.
-fn truncate_output(s: &str, max_size: usize) -> String {
-    if s.len() <= max_size {
+/// a simple head-only cutoff would lose. `max_size` is in `meter`'s unit.
+fn truncate_output(s: &str, max_size: usize, meter: Meter) -> String {
+    if meter.measure(s) <= max_size {
         return s.to_string();
     }
 
@@ -497,6 +552,7 @@ fn truncate_output(s: &str, max_size: usize) -> String {
 
     // Single giant line (no newlines) — byte-level fallback
     if lines.len() <= 1 {
+        let max_size = meter.byte_budget(s, max_size);
         let head_end = s.floor_char_boundary((max_size as f64 * 0.6) as usize);
         let tail_start =
             s.ceil_char_boundary(s.len().saturating_sub((max_size as f64 * 0.4) as usize));
@@ -506,9 +562,9 @@ fn truncate_output(s: &str, max_size: usize) -> String {
             return format!("{}\n... [output truncated]", &s[..boundary]);
         }
         return format!(
-            "{}\n... [truncated middle — {:.1}KB omitted] ...\n{}",
+            "{}\n... [truncated middle — {} omitted] ...\n{}",
             &s[..head_end],
-            (tail_start - head_end) as f64 / 1024.0,
+            meter.format(meter.measure(&s[head_end..tail_start])),
             &s[tail_start..]
         );
     }
@@ -517,12 +573,15 @@ fn truncate_output(s: &str, max_size: usize) -> String {
     let tail_budget = max_size.saturating_sub(head_budget);
 
     // Walk forward for head lines
+    let mut head_size = 0;
     let mut head_bytes = 0;
     let mut head_count = 0;
     for line in &lines {
-        if head_bytes + line.len() > head_budget {
+        let size = meter.measure(line);
+        if head_size + size > head_budget {
             break;
         }
+        head_size += size;
         head_bytes += line.len();
         head_count += 1;
     }
@@ -533,12 +592,15 @@ fn truncate_output(s: &str, max_size: usize) -> String {
     }
 
     // Walk backward for tail lines
+    let mut tail_size = 0;
     let mut tail_bytes = 0;
     let mut tail_count = 0;
     for line in lines.iter().rev() {
-        if tail_bytes + line.len() > tail_budget {
+        let size = meter.measure(line);
+        if tail_size + size > tail_budget {
             break;
         }
+        tail_size += size;
         tail_bytes += line.len();
         tail_count += 1;
     }
@@ -549,7 +611,6 @@ fn truncate_output(s: &str, max_size: usize) -> String {
     }
 
     let omitted_lines = lines.len() - head_count - tail_count;
-    let omitted_bytes = s.len() - head_bytes - tail_bytes;
 
     if omitted_lines == 0 {
         // Nothing to omit — everything fits
@@ -561,10 +622,10 @@ fn truncate_output(s: &str, max_size: usize) -> String {
     let tail_text = &s[tail_start..];
 
     format!(
-        "{}\n... [{} lines / {:.1}KB truncated — showing first {} + last {} lines] ...\n{}",
+        "{}\n... [{} lines / {} truncated — showing first {} + last {} lines] ...\n{}",
         head_text,
         omitted_lines,
-        omitted_bytes as f64 / 1024.0,
+        meter.format(meter.measure(&s[head_bytes..tail_start])),
         head_count,
         tail_count,
         tail_text
@@ -669,7 +730,7 @@ mod truncation_tests {
     #[test]
     fn test_truncate_preserves_small() {
         let small = "hello\nworld\n";
-        assert_eq!(truncate_output(small, 100), small);
+        assert_eq!(truncate_output(small, 100, Meter::Bytes), small);
     }
 
     #[test]
@@ -679,7 +740,7 @@ mod truncation_tests {
         assert_eq!(lines.len(), 200);
 
         // Allow 100 bytes: head 60 bytes (~6 lines), tail 40 bytes (~4 lines)
-        let result = truncate_output(&lines, 100);
+        let result = truncate_output(&lines, 100, Meter::Bytes);
         assert!(result.contains("line 0001"));
         assert!(result.contains("line 0020")); // tail preserved
         assert!(result.contains("truncated"));
@@ -691,7 +752,7 @@ mod truncation_tests {
     #[test]
     fn test_truncate_single_long_line() {
         let long = "x".repeat(1000);
-        let result = truncate_output(&long, 200);
+        let result = truncate_output(&long, 200, Meter::Bytes);
         assert!(result.contains("truncated middle"));
         // Head should be ~120 chars (60% of 200), tail ~80 chars (40% of 200)
         assert!(result.len() < 300); // head + tail + message
@@ -700,10 +761,9 @@ mod truncation_tests {
     #[test]
     fn test_truncate_message_format() {
         let lines: String = (1..=100).map(|i| format!("line {:04}\n", i)).collect();
-        let result = truncate_output(&lines, 200);
+        let result = truncate_output(&lines, 200, Meter::Bytes);
         // Should contain the descriptive truncation message
-        assert!(result.contains("lines /"));
-        assert!(result.contains("KB truncated"));
+        assert!(result.contains("80 lines / 0.8KB truncated"));
         assert!(result.contains("showing first"));
         assert!(result.contains("last"));
     }
@@ -712,55 +772,18 @@ mod truncation_tests {
     fn test_truncate_preserves_exact_fit() {
         let lines = "aaa\nbbb\nccc\n";
         // Exactly at limit — should return unchanged
-        assert_eq!(truncate_output(lines, lines.len()), lines);
-    }
-
-    #[test]
-    fn test_intent_small_output_passthrough() {
-        let small = "This is a small output about errors.";
-        assert!(small.len() < INTENT_SEARCH_THRESHOLD);
-        assert_eq!(filter_by_intent(small, "errors"), small);
-    }
-
-    #[test]
-    fn test_intent_filters_relevant_sections() {
-        // Build output > 5KB with distinct paragraphs
-        let mut output = String::new();
-        for i in 0..20 {
-            if i == 5 || i == 12 {
-                output.push_str(&format!(
-                    "Section {i}: This section discusses error handling and retry logic.\n\n"
-                ));
-            } else {
-                output.push_str(&format!(
-                    "Section {i}: {} unrelated content padding here.\n\n",
-                    "lorem ipsum ".repeat(30)
-                ));
-            }
-        }
-        assert!(output.len() > INTENT_SEARCH_THRESHOLD);
-
-        let result = filter_by_intent(&output, "error handling retry");
-        assert!(result.contains("Filtered by intent"));
-        assert!(result.contains("error handling"));
-        // Should not contain all 20 sections
-        assert!(result.len() < output.len());
+        assert_eq!(truncate_output(lines, lines.len(), Meter::Bytes), lines);
     }
 
     #[test]
-    fn test_intent_no_matches_returns_all() {
-        let mut output = String::new();
-        for i in 0..20 {
-            output.push_str(&format!(
-                "Section {i}: {} unrelated content here.\n\n",
-                "lorem ipsum ".repeat(30)
-            ));
-        }
-        assert!(output.len() > INTENT_SEARCH_THRESHOLD);
-
-        // Intent with no matching terms — should return original
-        let result = filter_by_intent(&output, "xyzzy quantum entanglement");
-        assert!(!result.contains("Filtered by intent"));
-        assert_eq!(result, output);
+    fn test_truncate_token_budget() {
+        // 13 estimated tokens (12 characters + newline) but 37 bytes per line.
+        let lines = "快速的棕色狐狸跳过了懒狗\n".repeat(100);
+        let meter = Meter::Tokens(&crate::tokens::HeuristicEstimator);
+        let result = truncate_output(&lines, 130, meter);
+        // 78 tokens of head, 52 of tail; a 130-byte budget would keep 3 lines.
+        assert!(result.contains("90 lines / 1.2K tokens truncated"));
+        assert!(result.contains("showing first 6 + last 4 lines"));
+        assert_eq!(truncate_output(&lines, 1300, meter), lines);
     }
 }
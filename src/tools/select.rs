@@ -0,0 +1,522 @@
+//! `select` projection of JSON tool results.
+//!
+//! A small path language accepting both JSONPath and jq spellings, applied to
+//! the raw result before the rest of the output pipeline:
+//!
+//! - `$.a.b` / `.a.b`: object keys (`["a b"]` or `['a b']` for other names)
+//! - `[0]`, `[-1]`: array index, negative from the end
+//! - `[*]`, `.*`, `[]`: every element of an array or value of an object
+//! - `[1:3]`, `[:5]`: array slice
+//! - `..name`: `name` at any depth
+//! - `{a, b}` or `['a','b']` as the last step: keep only those keys
+//! - `|` between steps is accepted and ignored (`.items[] | {id, name}`)
+//!
+//! A path without `*`, `[]`, slices or `..` selects one value and fails when
+//! any step is missing. Other paths select an array of every match, and fail
+//! only when nothing matches.
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SelectError {
+    #[error("invalid select '{expr}' at position {pos}: {reason}")]
+    Parse {
+        expr: String,
+        pos: usize,
+        reason: String,
+    },
+
+    #[error("select '{0}' needs a JSON result")]
+    NotJson(String),
+
+    #[error("select '{expr}' matched nothing: {reason}")]
+    NoMatch { expr: String, reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    Slice(Option<i64>, Option<i64>),
+    Descend(String),
+    Fields(Vec<String>),
+}
+
+impl Step {
+    fn is_definite(&self) -> bool {
+        matches!(self, Step::Key(_) | Step::Index(_) | Step::Fields(_))
+    }
+
+    fn display(&self) -> String {
+        match self {
+            Step::Key(key) => format!(".{key}"),
+            Step::Index(i) => format!("[{i}]"),
+            Step::Wildcard => "[*]".to_string(),
+            Step::Slice(start, end) => format!(
+                "[{}:{}]",
+                start.map(|s| s.to_string()).unwrap_or_default(),
+                end.map(|e| e.to_string()).unwrap_or_default()
+            ),
+            Step::Descend(key) => format!("..{key}"),
+            Step::Fields(fields) => format!("{{{}}}", fields.join(", ")),
+        }
+    }
+}
+
+/// A parsed `select` expression.
+#[derive(Debug, Clone)]
+pub struct Selector {
+    expr: String,
+    steps: Vec<Step>,
+}
+
+impl Selector {
+    pub fn parse(expr: &str) -> Result<Self, SelectError> {
+        Parser::new(expr).parse()
+    }
+
+    /// Apply the selector to the JSON text of a tool result.
+    pub fn apply_to_text(&self, text: &str) -> Result<Value, SelectError> {
+        let value: Value =
+            serde_json::from_str(text).map_err(|_| SelectError::NotJson(self.expr.clone()))?;
+        self.apply(&value)
+    }
+
+    pub fn apply(&self, root: &Value) -> Result<Value, SelectError> {
+        let mut nodes = vec![root];
+        let mut definite = true;
+        let mut path = String::from("$");
+        for step in &self.steps {
+            if let Step::Fields(fields) = step {
+                let projected: Vec<Value> = nodes
+                    .iter()
+                    .filter_map(|node| node.as_object())
+                    .map(|obj| {
+                        let picked: Map<String, Value> = fields
+                            .iter()
+                            .filter_map(|f| obj.get(f).map(|v| (f.clone(), v.clone())))
+                            .collect();
+                        Value::Object(picked)
+                    })
+                    .collect();
+                if projected.is_empty() {
+                    return Err(self.no_match(format!("{path} has no objects to pick keys from")));
+                }
+                return Ok(self.result(projected, definite));
+            }
+
+            let mut next = Vec::new();
+            for node in &nodes {
+                select_step(node, step, &mut next);
+            }
+            if next.is_empty() && definite && step.is_definite() {
+                return Err(self.no_match(explain_miss(nodes[0], step, &path)));
+            }
+            definite &= step.is_definite();
+            path.push_str(&step.display());
+            nodes = next;
+        }
+        if nodes.is_empty() {
+            return Err(self.no_match(format!("no values at {path}")));
+        }
+        Ok(self.result(nodes.into_iter().cloned().collect(), definite))
+    }
+
+    fn result(&self, mut values: Vec<Value>, definite: bool) -> Value {
+        if definite && values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        }
+    }
+
+    fn no_match(&self, reason: String) -> SelectError {
+        SelectError::NoMatch {
+            expr: self.expr.clone(),
+            reason,
+        }
+    }
+}
+
+fn select_step<'a>(node: &'a Value, step: &Step, out: &mut Vec<&'a Value>) {
+    match (step, node) {
+        (Step::Key(key), Value::Object(obj)) => out.extend(obj.get(key)),
+        (Step::Index(i), Value::Array(items)) => {
+            out.extend(resolve_index(*i, items.len()).map(|i| &items[i]))
+        }
+        (Step::Wildcard, Value::Array(items)) => out.extend(items),
+        (Step::Wildcard, Value::Object(obj)) => out.extend(obj.values()),
+        (Step::Slice(start, end), Value::Array(items)) => {
+            let len = items.len() as i64;
+            let clamp = |i: i64| {
+                let i = if i < 0 { (len + i).max(0) } else { i.min(len) };
+                i as usize
+            };
+            let start = clamp(start.unwrap_or(0));
+            let end = clamp(end.unwrap_or(len));
+            if start < end {
+                out.extend(&items[start..end]);
+            }
+        }
+        (Step::Descend(key), _) => descend(node, key, out),
+        _ => {}
+    }
+}
+
+fn resolve_index(i: i64, len: usize) -> Option<usize> {
+    let i = if i < 0 { len as i64 + i } else { i };
+    (0..len as i64).contains(&i).then_some(i as usize)
+}
+
+fn descend<'a>(node: &'a Value, key: &str, out: &mut Vec<&'a Value>) {
+    match node {
+        Value::Object(obj) => {
+            if let Some(value) = obj.get(key) {
+                out.push(value);
+            }
+            for value in obj.values() {
+                descend(value, key, out);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                descend(item, key, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Why a definite step found nothing, naming what is there instead.
+fn explain_miss(node: &Value, step: &Step, path: &str) -> String {
+    match (step, node) {
+        (Step::Key(key), Value::Object(obj)) => {
+            let mut keys: Vec<&str> = obj.keys().map(String::as_str).collect();
+            keys.truncate(20);
+            format!("no key '{key}' at {path} (keys: {})", keys.join(", "))
+        }
+        (Step::Index(i), Value::Array(items)) => {
+            format!("index {i} out of range at {path} (length {})", items.len())
+        }
+        (_, other) => format!(
+            "{} expects {} at {path}, found {}",
+            step.display(),
+            match step {
+                Step::Index(_) | Step::Slice(..) => "an array",
+                Step::Key(_) => "an object",
+                _ => "an array or object",
+            },
+            type_name(other)
+        ),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+struct Parser<'a> {
+    expr: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(expr: &'a str) -> Self {
+        Self {
+            expr,
+            chars: expr.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn error(&self, reason: impl Into<String>) -> SelectError {
+        SelectError::Parse {
+            expr: self.expr.to_string(),
+            pos: self.pos,
+            reason: reason.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace() || c == '|') {
+            self.pos += 1;
+        }
+    }
+
+    fn parse(mut self) -> Result<Selector, SelectError> {
+        self.skip_separators();
+        self.eat('$');
+        let mut steps = Vec::new();
+        loop {
+            self.skip_separators();
+            let Some(c) = self.peek() else { break };
+            if matches!(steps.last(), Some(Step::Fields(_))) {
+                return Err(self.error("key selection {..} must be the last step"));
+            }
+            match c {
+                '.' => {
+                    self.pos += 1;
+                    if self.eat('.') {
+                        steps.push(Step::Descend(self.ident()?));
+                    } else if self.eat('*') {
+                        steps.push(Step::Wildcard);
+                    } else if self.peek().is_some_and(is_ident_char) {
+                        steps.push(Step::Key(self.ident()?));
+                    }
+                    // A bare `.` (jq identity, or `.[` / `.{`) adds nothing.
+                }
+                '[' => {
+                    self.pos += 1;
+                    steps.push(self.bracket()?);
+                }
+                '{' => {
+                    self.pos += 1;
+                    steps.push(self.fields()?);
+                }
+                _ => return Err(self.error(format!("unexpected '{c}'"))),
+            }
+        }
+        Ok(Selector {
+            expr: self.expr.to_string(),
+            steps,
+        })
+    }
+
+    fn ident(&mut self) -> Result<String, SelectError> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_ident_char) {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a key name"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn quoted(&mut self) -> Result<String, SelectError> {
+        let quote = self.peek().filter(|c| *c == '"' || *c == '\'');
+        let Some(quote) = quote else {
+            return Err(self.error("expected a quoted key"));
+        };
+        self.pos += 1;
+        let mut key = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("unterminated string")),
+                Some('\\') => {
+                    self.pos += 1;
+                    key.extend(self.peek());
+                    self.pos += 1;
+                }
+                Some(c) if c == quote => {
+                    self.pos += 1;
+                    return Ok(key);
+                }
+                Some(c) => {
+                    key.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Option<i64>, SelectError> {
+        let start = self.pos;
+        if self.peek() == Some('-') {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        text.parse()
+            .map(Some)
+            .map_err(|_| self.error(format!("invalid number '{text}'")))
+    }
+
+    /// `[...]` after the opening bracket.
+    fn bracket(&mut self) -> Result<Step, SelectError> {
+        let step =
+            match self.peek() {
+                Some(']') => Step::Wildcard,
+                Some('*') => {
+                    self.pos += 1;
+                    Step::Wildcard
+                }
+                Some('"') | Some('\'') => {
+                    let mut keys = vec![self.quoted()?];
+                    while self.eat(',') {
+                        self.skip_whitespace();
+                        keys.push(self.quoted()?);
+                    }
+                    if keys.len() == 1 {
+                        Step::Key(keys.remove(0))
+                    } else {
+                        Step::Fields(keys)
+                    }
+                }
+                _ => {
+                    let start = self.number()?;
+                    if self.eat(':') {
+                        Step::Slice(start, self.number()?)
+                    } else {
+                        Step::Index(start.ok_or_else(|| {
+                            self.error("expected an index, slice, '*' or quoted key")
+                        })?)
+                    }
+                }
+            };
+        if !self.eat(']') {
+            return Err(self.error("expected ']'"));
+        }
+        Ok(step)
+    }
+
+    /// `{a, b}` after the opening brace.
+    fn fields(&mut self) -> Result<Step, SelectError> {
+        let mut fields = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                break;
+            }
+            let field = match self.peek() {
+                Some('"') | Some('\'') => self.quoted()?,
+                _ => self.ident()?,
+            };
+            fields.push(field);
+            self.skip_whitespace();
+            if !self.eat(',') && self.peek() != Some('}') {
+                return Err(self.error("expected ',' or '}'"));
+            }
+        }
+        if fields.is_empty() {
+            return Err(self.error("expected at least one key"));
+        }
+        Ok(Step::Fields(fields))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '$' || c == '@'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(expr: &str, value: &Value) -> Result<Value, SelectError> {
+        Selector::parse(expr)?.apply(value)
+    }
+
+    fn sample() -> Value {
+        json!({
+            "total": 3,
+            "items": [
+                {"id": 1, "name": "a", "meta": {"tags": ["x"]}},
+                {"id": 2, "name": "b", "meta": {"tags": []}},
+                {"id": 3, "name": "c"}
+            ]
+        })
+    }
+
+    #[test]
+    fn test_definite_paths_in_both_spellings() {
+        let value = sample();
+        assert_eq!(select("$.total", &value).unwrap(), json!(3));
+        assert_eq!(select(".items[0].name", &value).unwrap(), json!("a"));
+        assert_eq!(select("$['items'][-1].id", &value).unwrap(), json!(3));
+        assert_eq!(select(".", &value).unwrap(), value);
+        assert_eq!(
+            select("$.items[1]{id, name}", &value).unwrap(),
+            json!({"id": 2, "name": "b"})
+        );
+    }
+
+    #[test]
+    fn test_multi_value_paths() {
+        let value = sample();
+        assert_eq!(select("$.items[*].id", &value).unwrap(), json!([1, 2, 3]));
+        assert_eq!(
+            select(".items[] | {id, name}", &value).unwrap(),
+            json!([{"id": 1, "name": "a"}, {"id": 2, "name": "b"}, {"id": 3, "name": "c"}])
+        );
+        assert_eq!(
+            select("$.items[1:].name", &value).unwrap(),
+            json!(["b", "c"])
+        );
+        assert_eq!(select("$..tags", &value).unwrap(), json!([["x"], []]));
+        assert_eq!(
+            select("$.items[0]['id','name']", &value).unwrap(),
+            json!({"id": 1, "name": "a"})
+        );
+        // Missing keys are skipped as long as something matches.
+        assert_eq!(
+            select("$.items[*].meta.tags", &value).unwrap(),
+            json!([["x"], []])
+        );
+    }
+
+    #[test]
+    fn test_errors_name_what_is_there() {
+        let value = sample();
+        let err = select("$.itemz", &value).unwrap_err().to_string();
+        assert!(err.contains("no key 'itemz' at $ (keys: "), "{err}");
+        let err = select("$.items[5]", &value).unwrap_err().to_string();
+        assert!(
+            err.contains("index 5 out of range at $.items (length 3)"),
+            "{err}"
+        );
+        let err = select("$.total.x", &value).unwrap_err().to_string();
+        assert!(err.contains("found a number"), "{err}");
+        assert!(matches!(
+            select("$..nope", &value),
+            Err(SelectError::NoMatch { .. })
+        ));
+
+        assert!(matches!(
+            Selector::parse("$.items["),
+            Err(SelectError::Parse { .. })
+        ));
+        assert!(matches!(
+            Selector::parse("{id}.name"),
+            Err(SelectError::Parse { .. })
+        ));
+        assert!(matches!(
+            Selector::parse("$.a").unwrap().apply_to_text("not json"),
+            Err(SelectError::NotJson(_))
+        ));
+    }
+}
@@ -0,0 +1,691 @@
+//! Pre-execution check of tool call arguments in `call_tool_chain` code.
+//!
+//! With `type_check: true`, calls written as `backend.tool({...})` are found
+//! in the code before it runs, and each object literal is checked against the
+//! tool's normalized input schema, as `tsc` would check it against the
+//! declarations from [`crate::schema::typescript`]: missing required
+//! properties, unknown properties and literal values of the wrong type.
+//!
+//! Only what the literal says for certain is checked. Values that are not
+//! literals (variables, calls, template strings with `${}`) are taken to be
+//! of the right type, a spread (`...opts`) or computed key may supply any
+//! property, and calls whose argument is not an object literal are skipped.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Number, Value};
+
+use crate::registry::ToolEntry;
+use crate::sandbox::bridge::sanitize_identifier;
+use crate::schema::validate::{ArgumentErrors, Issue};
+use crate::schema::{normalize, validate};
+
+/// A call whose arguments do not fit the tool's input schema.
+#[derive(Debug)]
+pub struct Mismatch {
+    pub backend: String,
+    pub tool: String,
+    /// 1-based line of the call in the code.
+    pub line: usize,
+    pub errors: ArgumentErrors,
+}
+
+/// Check the argument literals of every call to one of `tools` in `code`.
+/// Returns the first call that does not fit.
+pub fn check(tools: &[ToolEntry], code: &str) -> Result<(), Mismatch> {
+    let by_name: HashMap<(String, String), &ToolEntry> = tools
+        .iter()
+        .map(|tool| {
+            let name = if tool.original_name.is_empty() {
+                &tool.name
+            } else {
+                &tool.original_name
+            };
+            let key = (
+                sanitize_identifier(&tool.backend_name),
+                sanitize_identifier(name),
+            );
+            (key, tool)
+        })
+        .collect();
+
+    for call in calls(code) {
+        let Some(tool) = by_name.get(&(call.backend, call.tool)) else {
+            continue;
+        };
+        let (arguments, known) = match call.arguments {
+            Arguments::None => (None, Known::default()),
+            Arguments::Literal(value, known) => (Some(value), known),
+            Arguments::Other => continue,
+        };
+        let schema = normalize(&tool.input_schema);
+        if let Err(mut errors) = validate(&schema, arguments.as_ref()) {
+            errors.issues.retain(|issue| known.is_certain(issue));
+            if !errors.issues.is_empty() {
+                return Err(Mismatch {
+                    backend: tool.backend_name.clone(),
+                    tool: if tool.original_name.is_empty() {
+                        tool.name.clone()
+                    } else {
+                        tool.original_name.clone()
+                    },
+                    line: call.line,
+                    errors,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A `backend.tool(...)` call found in the code.
+struct Call {
+    backend: String,
+    tool: String,
+    line: usize,
+    arguments: Arguments,
+}
+
+enum Arguments {
+    /// `backend.tool()`
+    None,
+    /// `backend.tool({...})`, with what the literal leaves open.
+    Literal(Value, Known),
+    /// Anything else, e.g. `backend.tool(args)`.
+    Other,
+}
+
+/// Parts of a parsed literal whose value is not known before running.
+#[derive(Default)]
+struct Known {
+    /// Paths of values that are not literals; `null` stands in for them.
+    opaque: Vec<String>,
+    /// Paths of objects with a spread or computed key.
+    open: Vec<String>,
+}
+
+impl Known {
+    /// Whether `issue` holds whatever the non-literal parts turn out to be.
+    fn is_certain(&self, issue: &Issue) -> bool {
+        let path = match issue.path() {
+            "arguments" => "",
+            path => path,
+        };
+        match issue {
+            Issue::Missing { .. } => {
+                let parent = parent(path);
+                !self.open.iter().any(|open| open == parent)
+                    && !self.opaque.iter().any(|o| within(parent, o))
+            }
+            Issue::Unknown { .. } => !self.opaque.iter().any(|o| o != path && within(path, o)),
+            Issue::Type { .. } | Issue::NotAllowed { .. } => !self
+                .opaque
+                .iter()
+                .any(|o| within(path, o) || within(o, path)),
+        }
+    }
+}
+
+/// Whether `path` is `ancestor` or below it. The root path is empty.
+fn within(path: &str, ancestor: &str) -> bool {
+    ancestor.is_empty()
+        || path
+            .strip_prefix(ancestor)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+}
+
+fn parent(path: &str) -> &str {
+    path.rfind(['.', '[']).map_or("", |i| {
+        if path[i..].starts_with('.') {
+            &path[..i]
+        } else {
+            path
+        }
+    })
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{path}.{key}")
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Find `ident.ident(` calls outside strings and comments.
+fn calls(code: &str) -> Vec<Call> {
+    let chars: Vec<char> = code.chars().collect();
+    let mut scanner = Scanner {
+        chars: &chars,
+        pos: 0,
+    };
+    let mut calls = Vec::new();
+    while let Some(c) = scanner.peek() {
+        if scanner.skip_string_or_comment() {
+            continue;
+        }
+        let start = scanner.pos;
+        let follows_member =
+            start > 0 && (is_ident_char(chars[start - 1]) || chars[start - 1] == '.');
+        if !is_ident_start(c) || follows_member {
+            scanner.pos += 1;
+            continue;
+        }
+        let backend = scanner.ident();
+        let after_backend = scanner.pos;
+        scanner.skip_ws();
+        if scanner.peek() == Some('.') {
+            scanner.pos += 1;
+            scanner.skip_ws();
+            if scanner.peek().is_some_and(is_ident_start) {
+                let tool = scanner.ident();
+                scanner.skip_ws();
+                if scanner.peek() == Some('(') {
+                    scanner.pos += 1;
+                    let line = 1 + chars[..start].iter().filter(|&&c| c == '\n').count();
+                    let arguments = scanner.arguments();
+                    calls.push(Call {
+                        backend,
+                        tool,
+                        line,
+                        arguments,
+                    });
+                }
+            }
+        }
+        scanner.pos = after_backend;
+    }
+    calls
+}
+
+struct Scanner<'a> {
+    chars: &'a [char],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        text.chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_ident_char) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_ws(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.pos += 1,
+                Some('/') if self.starts_with("//") || self.starts_with("/*") => {
+                    self.skip_string_or_comment();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Skip a string, template or comment starting here. Returns whether
+    /// there was one.
+    fn skip_string_or_comment(&mut self) -> bool {
+        match self.peek() {
+            Some('"' | '\'') => {
+                self.string();
+            }
+            Some('`') => {
+                self.template();
+            }
+            Some('/') if self.starts_with("//") => {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.pos += 1;
+                }
+            }
+            Some('/') if self.starts_with("/*") => {
+                self.pos += 2;
+                while self.peek().is_some() && !self.starts_with("*/") {
+                    self.pos += 1;
+                }
+                self.pos = (self.pos + 2).min(self.chars.len());
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Skip to the end of the expression starting here: the next `,` or
+    /// closing bracket outside nested brackets. `None` at the end of the code.
+    fn skip_expression(&mut self) -> Option<()> {
+        let mut depth = 0usize;
+        loop {
+            if self.skip_string_or_comment() {
+                continue;
+            }
+            match self.peek()? {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => return Some(()),
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 => return Some(()),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Skip a bracketed expression starting here, including the brackets.
+    fn skip_expression_in(&mut self, open: char, close: char) -> Option<()> {
+        debug_assert_eq!(self.peek(), Some(open));
+        self.pos += 1;
+        self.skip_expression()?;
+        (self.peek()? == close).then(|| self.pos += 1)
+    }
+
+    /// Parse the arguments of a call, just past its `(`.
+    fn arguments(&mut self) -> Arguments {
+        self.skip_ws();
+        match self.peek() {
+            Some(')') => Arguments::None,
+            Some('{') => {
+                let mut parser = Parser {
+                    scanner: Scanner {
+                        chars: self.chars,
+                        pos: self.pos,
+                    },
+                    known: Known::default(),
+                };
+                let value = parser.value("");
+                parser.scanner.skip_ws();
+                match (value, parser.scanner.peek()) {
+                    // A single argument that is the literal itself.
+                    (Some(value), Some(')'))
+                        if !parser.known.opaque.iter().any(String::is_empty) =>
+                    {
+                        Arguments::Literal(value, parser.known)
+                    }
+                    _ => Arguments::Other,
+                }
+            }
+            _ => Arguments::Other,
+        }
+    }
+
+    /// Read a quoted string starting here, decoding escapes.
+    fn string(&mut self) -> Option<String> {
+        let quote = self.peek()?;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            match self.peek()? {
+                c if c == quote => {
+                    self.pos += 1;
+                    return Some(out);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    out.extend(self.escape()?);
+                }
+                '\n' => return None,
+                c => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Read a template literal starting here. `Some(None)` if it has
+    /// substitutions, so its value is not known.
+    fn template(&mut self) -> Option<Option<String>> {
+        self.pos += 1;
+        let mut out = Some(String::new());
+        loop {
+            match self.peek()? {
+                '`' => {
+                    self.pos += 1;
+                    return Some(out);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    let escaped = self.escape()?;
+                    if let Some(ref mut out) = out {
+                        out.extend(escaped);
+                    }
+                }
+                '$' if self.starts_with("${") => {
+                    self.pos += 2;
+                    self.skip_expression()?;
+                    if self.peek() != Some('}') {
+                        return None;
+                    }
+                    self.pos += 1;
+                    out = None;
+                }
+                c => {
+                    if let Some(ref mut out) = out {
+                        out.push(c);
+                    }
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Decode the escape sequence after a `\`.
+    fn escape(&mut self) -> Option<Option<char>> {
+        let c = self.peek()?;
+        self.pos += 1;
+        let hex = |scanner: &mut Self, len: usize| -> Option<char> {
+            let digits: String = scanner
+                .chars
+                .get(scanner.pos..scanner.pos + len)?
+                .iter()
+                .collect();
+            scanner.pos += len;
+            char::from_u32(u32::from_str_radix(&digits, 16).ok()?)
+        };
+        Some(match c {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            'b' => Some('\u{8}'),
+            'f' => Some('\u{c}'),
+            'v' => Some('\u{b}'),
+            '0' => Some('\0'),
+            'x' => Some(hex(self, 2)?),
+            'u' if self.peek() == Some('{') => {
+                let end = self.chars[self.pos..].iter().position(|&c| c == '}')?;
+                let digits: String = self.chars[self.pos + 1..self.pos + end].iter().collect();
+                self.pos += end + 1;
+                Some(char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?)
+            }
+            'u' => Some(hex(self, 4)?),
+            // Line continuation.
+            '\n' => None,
+            c => Some(c),
+        })
+    }
+}
+
+/// Parser for the JSON-like subset of JavaScript object literals.
+struct Parser<'a> {
+    scanner: Scanner<'a>,
+    known: Known,
+}
+
+impl Parser<'_> {
+    /// Parse the value starting here. Values that are not literals become
+    /// `null` and are recorded as opaque. `None` if the code is not a value
+    /// this parser understands.
+    fn value(&mut self, path: &str) -> Option<Value> {
+        self.scanner.skip_ws();
+        let start = self.scanner.pos;
+        let literal = match self.scanner.peek()? {
+            '{' => self.object(path)?,
+            '[' => self.array(path)?,
+            '"' | '\'' => Some(Value::String(self.scanner.string()?)),
+            '`' => self.scanner.template()?.map(Value::String),
+            c if c == '-' || c == '.' || c.is_ascii_digit() => self.number(),
+            c if is_ident_start(c) => match self.scanner.ident().as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                "null" => Some(Value::Null),
+                _ => None,
+            },
+            _ => None,
+        };
+        self.scanner.skip_ws();
+        // A literal followed by an operator is part of a larger expression.
+        let complete = matches!(self.scanner.peek(), Some(',' | '}' | ']' | ')'));
+        match literal {
+            Some(value) if complete => Some(value),
+            _ => {
+                self.scanner.pos = start;
+                self.scanner.skip_expression()?;
+                self.known.opaque.push(path.to_string());
+                Some(Value::Null)
+            }
+        }
+    }
+
+    /// Parse an object literal. `Some(None)` if it is not a plain literal
+    /// (e.g. it has methods), so the whole value is opaque.
+    fn object(&mut self, path: &str) -> Option<Option<Value>> {
+        self.scanner.pos += 1;
+        let mut object = Map::new();
+        loop {
+            self.scanner.skip_ws();
+            let key = match self.scanner.peek()? {
+                '}' => {
+                    self.scanner.pos += 1;
+                    return Some(Some(Value::Object(object)));
+                }
+                '.' if self.scanner.starts_with("...") => {
+                    self.scanner.pos += 3;
+                    self.scanner.skip_expression()?;
+                    self.known.open.push(path.to_string());
+                    None
+                }
+                '[' => {
+                    self.scanner.skip_expression_in('[', ']')?;
+                    self.expect(':')?;
+                    self.scanner.skip_expression()?;
+                    self.known.open.push(path.to_string());
+                    None
+                }
+                '"' | '\'' => Some(self.scanner.string()?),
+                c if is_ident_char(c) => Some(self.scanner.ident()),
+                _ => return Some(None),
+            };
+            if let Some(key) = key {
+                self.scanner.skip_ws();
+                let member = join(path, &key);
+                match self.scanner.peek()? {
+                    ':' => {
+                        self.scanner.pos += 1;
+                        let value = self.value(&member)?;
+                        object.insert(key, value);
+                    }
+                    // Shorthand `{query}`: present, value unknown.
+                    ',' | '}' => {
+                        self.known.opaque.push(member);
+                        object.insert(key, Value::Null);
+                    }
+                    _ => return Some(None),
+                }
+            }
+            self.scanner.skip_ws();
+            match self.scanner.peek()? {
+                ',' => self.scanner.pos += 1,
+                '}' => {}
+                _ => return Some(None),
+            }
+        }
+    }
+
+    /// Parse an array literal. A spread makes the whole array opaque.
+    fn array(&mut self, path: &str) -> Option<Option<Value>> {
+        self.scanner.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.scanner.skip_ws();
+            match self.scanner.peek()? {
+                ']' => {
+                    self.scanner.pos += 1;
+                    return Some(Some(Value::Array(items)));
+                }
+                '.' if self.scanner.starts_with("...") => return Some(None),
+                _ => {}
+            }
+            let item = self.value(&format!("{path}[{}]", items.len()))?;
+            items.push(item);
+            self.scanner.skip_ws();
+            match self.scanner.peek()? {
+                ',' => self.scanner.pos += 1,
+                ']' => {}
+                _ => return Some(None),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let start = self.scanner.pos;
+        while let Some(c) = self.scanner.peek() {
+            let exponent_sign = (c == '+' || c == '-')
+                && self.scanner.pos > start
+                && matches!(self.scanner.chars[self.scanner.pos - 1], 'e' | 'E');
+            let leading_sign = c == '-' && self.scanner.pos == start;
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' || exponent_sign || leading_sign {
+                self.scanner.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let text: String = self.scanner.chars[start..self.scanner.pos]
+            .iter()
+            .filter(|&&c| c != '_')
+            .collect();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.as_str()),
+        };
+        let n = if let Some(hex) = digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+            i64::from_str_radix(hex, 16).ok().map(Number::from)
+        } else {
+            digits
+                .parse::<i64>()
+                .ok()
+                .map(Number::from)
+                .or_else(|| digits.parse::<f64>().ok().and_then(Number::from_f64))
+        }?;
+        if !negative {
+            return Some(Value::Number(n));
+        }
+        let negated = n
+            .as_i64()
+            .map(|i| Number::from(-i))
+            .or_else(|| n.as_f64().and_then(|f| Number::from_f64(-f)))?;
+        Some(Value::Number(negated))
+    }
+
+    fn expect(&mut self, c: char) -> Option<()> {
+        self.scanner.skip_ws();
+        (self.scanner.peek()? == c).then(|| self.scanner.pos += 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tools() -> Vec<ToolEntry> {
+        vec![ToolEntry {
+            name: "my-search.web_search".to_string(),
+            original_name: "web_search".to_string(),
+            description: String::new(),
+            backend_name: "my-search".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "query": {"type": "string"},
+                    "limit": {"type": "integer"},
+                    "filter": {"$ref": "#/$defs/Filter"}
+                },
+                "required": ["query"],
+                "additionalProperties": false,
+                "$defs": {
+                    "Filter": {
+                        "type": "object",
+                        "properties": {"since": {"type": "string"}},
+                        "required": ["since"]
+                    }
+                }
+            }),
+            tags: Vec::new(),
+            output_schema: None,
+            annotations: None,
+        }]
+    }
+
+    fn messages(code: &str) -> Option<(usize, Vec<String>)> {
+        check(&tools(), code).err().map(|mismatch| {
+            let mut messages: Vec<String> = mismatch
+                .errors
+                .issues
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            messages.sort();
+            (mismatch.line, messages)
+        })
+    }
+
+    #[test]
+    fn test_reports_literal_mistakes() {
+        let code = r#"
+            // my_search.web_search({}) in a comment is not a call
+            const note = "my_search.web_search({})";
+            const a = await my_search.web_search({query: 'rust', limit: 5});
+            const b = await my_search.web_search({
+                querry: "rust",
+                limit: "5",
+                filter: {since: 2024},
+            });
+            return [a, b];
+        "#;
+        assert_eq!(
+            messages(code),
+            Some((
+                5,
+                vec![
+                    "'filter.since' should be string, got integer".to_string(),
+                    "'limit' should be integer, got string".to_string(),
+                    "missing required property 'query'".to_string(),
+                    "unknown property 'querry'".to_string(),
+                ]
+            ))
+        );
+        assert_eq!(
+            messages("return await my_search.web_search();"),
+            Some((1, vec!["missing required property 'query'".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_unknown_values_are_not_reported() {
+        let code = r#"
+            const query = "rust";
+            const opts = {limit: 3};
+            await my_search.web_search({query, limit: opts.limit * 2});
+            await my_search.web_search({...opts});
+            await my_search.web_search({query: `topic ${query}`, filter: {since: new Date().toISOString()}});
+            await my_search.web_search(buildArgs());
+            await my_search.web_search({query: "a" + query, limit: -1, filter: {since: 'x', ...extra}});
+            await other.web_search({nonsense: true});
+        "#;
+        assert_eq!(messages(code), None);
+
+        // Shorthand keys still count as written.
+        assert_eq!(
+            messages("my_search.web_search({query, lmit})"),
+            Some((1, vec!["unknown property 'lmit'".to_string()]))
+        );
+    }
+}
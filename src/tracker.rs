@@ -1,6 +1,6 @@
 //! In-memory tracking for recent tool calls, usage counts, and backend latency.
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
@@ -9,6 +9,8 @@ use dashmap::DashMap;
 use hdrhistogram::Histogram;
 use serde::Serialize;
 
+use crate::tokens::OutputSize;
+
 /// Default maximum number of recent call events to keep.
 const DEFAULT_MAX_RECENT: usize = 500;
 
@@ -41,6 +43,8 @@ pub struct LatencyStats {
     pub p99_ms: f64,
     pub avg_ms: f64,
     pub sample_count: u64,
+    /// Failed calls, including tool-reported (`isError`) results.
+    pub error_count: u64,
 }
 
 /// Thread-safe call tracker that records tool invocations.
@@ -56,16 +60,49 @@ pub struct CallTracker {
     usage_counts: DashMap<String, u64>,
     /// Per-backend latency histograms. Inner Mutex because Histogram::record is &mut self.
     latency: DashMap<String, Mutex<Histogram<u64>>>,
+    /// Per-backend failed call counts.
+    errors: DashMap<String, u64>,
+    /// Failed call counts keyed by `GatewayError` kind (`"timeout"`, `"tool_error"`, ...).
+    error_kinds: DashMap<&'static str, u64>,
     /// Maximum entries in the recent ring buffer.
     max_recent: usize,
     /// Per-tool bytes returned to context (after truncation/filtering).
     bytes_returned: DashMap<String, u64>,
     /// Total raw bytes processed before truncation/filtering.
     bytes_processed: AtomicU64,
+    /// Per-tool estimated tokens returned to context.
+    tokens_returned: DashMap<String, u64>,
+    /// Total estimated raw tokens processed before truncation/filtering.
+    tokens_processed: AtomicU64,
+    /// Per-backend result cache hits, misses and coalesced calls.
+    cache: DashMap<String, CacheStats>,
+    /// Argument repairs keyed by kind (`"string_to_number"`, `"rename_property"`, ...).
+    coercions: DashMap<&'static str, u64>,
     /// Session start time for uptime calculation.
     session_start: Instant,
 }
 
+/// How a call to a cached tool was answered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheOutcome {
+    /// From the result cache.
+    Hit,
+    /// By the backend.
+    Miss,
+    /// By an identical call that was already in flight.
+    Coalesced,
+}
+
+/// Result cache counters of a backend.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub coalesced: u64,
+    /// Share of calls answered without a backend request of their own.
+    pub hit_rate: f64,
+}
+
 impl CallTracker {
     /// Create a new tracker with default capacity (500 recent events).
     pub fn new() -> Self {
@@ -78,9 +115,15 @@ impl CallTracker {
             recent: Mutex::new(VecDeque::with_capacity(max_recent)),
             usage_counts: DashMap::new(),
             latency: DashMap::new(),
+            errors: DashMap::new(),
+            error_kinds: DashMap::new(),
             max_recent,
             bytes_returned: DashMap::new(),
             bytes_processed: AtomicU64::new(0),
+            tokens_returned: DashMap::new(),
+            tokens_processed: AtomicU64::new(0),
+            cache: DashMap::new(),
+            coercions: DashMap::new(),
             session_start: Instant::now(),
         }
     }
@@ -110,6 +153,13 @@ impl CallTracker {
             .and_modify(|c| *c += 1)
             .or_insert(1);
 
+        if !success {
+            self.errors
+                .entry(backend_name.to_string())
+                .and_modify(|c| *c += 1)
+                .or_insert(1);
+        }
+
         // Update latency histogram
         let duration_us = duration.as_micros() as u64;
         self.latency
@@ -167,9 +217,42 @@ impl CallTracker {
             p99_ms: hist.value_at_quantile(0.99) as f64 / 1000.0,
             avg_ms: hist.mean() / 1000.0,
             sample_count: hist.len(),
+            error_count: self.error_count(backend_name),
         })
     }
 
+    /// Get the number of calls recorded for a backend, failed or not.
+    pub fn call_count(&self, backend_name: &str) -> u64 {
+        self.latency
+            .get(backend_name)
+            .map(|r| r.value().lock().unwrap_or_else(|e| e.into_inner()).len())
+            .unwrap_or(0)
+    }
+
+    /// Get the number of failed calls recorded for a backend.
+    pub fn error_count(&self, backend_name: &str) -> u64 {
+        self.errors
+            .get(backend_name)
+            .map(|r| *r.value())
+            .unwrap_or(0)
+    }
+
+    /// Count a failed call by error kind (see [`crate::error::error_kind`]).
+    pub fn record_error_kind(&self, kind: &'static str) {
+        self.error_kinds
+            .entry(kind)
+            .and_modify(|c| *c += 1)
+            .or_insert(1);
+    }
+
+    /// Get failed call counts keyed by error kind.
+    pub fn error_kind_counts(&self) -> BTreeMap<String, u64> {
+        self.error_kinds
+            .iter()
+            .map(|r| (r.key().to_string(), *r.value()))
+            .collect()
+    }
+
     /// Get recent call events as serializable summaries.
     pub fn recent_calls(&self, limit: usize) -> Vec<CallEventSummary> {
         let now = Instant::now();
@@ -208,11 +291,60 @@ impl CallTracker {
         self.bytes_processed.fetch_add(processed, Ordering::Relaxed);
     }
 
+    /// Record bytes and estimated tokens of a tool call's output.
+    pub fn record_output(&self, tool_name: &str, returned: OutputSize, processed: OutputSize) {
+        self.record_bytes(tool_name, returned.bytes, processed.bytes);
+        self.tokens_returned
+            .entry(tool_name.to_string())
+            .and_modify(|t| *t += returned.tokens)
+            .or_insert(returned.tokens);
+        self.tokens_processed
+            .fetch_add(processed.tokens, Ordering::Relaxed);
+    }
+
+    /// Count a call to a cached tool of `backend_name`.
+    pub fn record_cache(&self, backend_name: &str, outcome: CacheOutcome) {
+        let mut stats = self.cache.entry(backend_name.to_string()).or_default();
+        match outcome {
+            CacheOutcome::Hit => stats.hits += 1,
+            CacheOutcome::Miss => stats.misses += 1,
+            CacheOutcome::Coalesced => stats.coalesced += 1,
+        }
+        let total = stats.hits + stats.misses + stats.coalesced;
+        stats.hit_rate = (stats.hits + stats.coalesced) as f64 / total as f64;
+    }
+
+    /// Get result cache counters keyed by backend.
+    pub fn cache_stats(&self) -> BTreeMap<String, CacheStats> {
+        self.cache
+            .iter()
+            .map(|r| (r.key().clone(), r.value().clone()))
+            .collect()
+    }
+
+    /// Count an argument repair by kind (see [`crate::schema::coerce`]).
+    pub fn record_coercion(&self, kind: &'static str) {
+        self.coercions
+            .entry(kind)
+            .and_modify(|c| *c += 1)
+            .or_insert(1);
+    }
+
+    /// Get argument repair counts keyed by kind.
+    pub fn coercion_counts(&self) -> BTreeMap<String, u64> {
+        self.coercions
+            .iter()
+            .map(|r| (r.key().to_string(), *r.value()))
+            .collect()
+    }
+
     /// Get session-level statistics for context savings tracking.
     pub fn session_stats(&self) -> SessionStats {
         let total_calls: u64 = self.usage_counts.iter().map(|r| *r.value()).sum();
         let total_bytes_returned: u64 = self.bytes_returned.iter().map(|r| *r.value()).sum();
         let total_bytes_processed = self.bytes_processed.load(Ordering::Relaxed);
+        let total_tokens_returned: u64 = self.tokens_returned.iter().map(|r| *r.value()).sum();
+        let total_tokens_processed = self.tokens_processed.load(Ordering::Relaxed);
         let uptime = self.session_start.elapsed().as_secs_f64();
 
         let savings_ratio = if total_bytes_returned > 0 {
@@ -233,6 +365,7 @@ impl CallTracker {
                 name: r.key().clone(),
                 calls: self.usage_count(r.key()),
                 bytes_returned: *r.value(),
+                tokens_returned: self.tokens_returned.get(r.key()).map_or(0, |t| *t),
             })
             .collect();
         per_tool.sort_by_key(|stat| std::cmp::Reverse(stat.bytes_returned));
@@ -244,9 +377,18 @@ impl CallTracker {
             total_bytes_processed,
             savings_ratio,
             reduction_pct,
-            estimated_tokens_saved: (total_bytes_processed.saturating_sub(total_bytes_returned))
-                / 4,
+            total_tokens_returned,
+            total_tokens_processed,
+            // Outputs recorded with bytes only fall back to 4 bytes per token.
+            estimated_tokens_saved: if total_tokens_processed > 0 {
+                total_tokens_processed.saturating_sub(total_tokens_returned)
+            } else {
+                total_bytes_processed.saturating_sub(total_bytes_returned) / 4
+            },
             per_tool,
+            errors_by_kind: self.error_kind_counts(),
+            cache_by_backend: self.cache_stats(),
+            coercions_by_kind: self.coercion_counts(),
         }
     }
 }
@@ -260,8 +402,21 @@ pub struct SessionStats {
     pub total_bytes_processed: u64,
     pub savings_ratio: f64,
     pub reduction_pct: f64,
+    /// Estimated tokens returned to context.
+    pub total_tokens_returned: u64,
+    /// Estimated raw tokens processed.
+    pub total_tokens_processed: u64,
     pub estimated_tokens_saved: u64,
     pub per_tool: Vec<ToolByteStat>,
+    /// Failed calls by error kind (rate_limited, timeout, tool_error, ...).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub errors_by_kind: BTreeMap<String, u64>,
+    /// Result cache counters of backends with a `cache` section.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub cache_by_backend: BTreeMap<String, CacheStats>,
+    /// Argument repairs made for backends with `coerce_arguments`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub coercions_by_kind: BTreeMap<String, u64>,
 }
 
 /// Per-tool byte tracking statistics.
@@ -270,6 +425,7 @@ pub struct ToolByteStat {
     pub name: String,
     pub calls: u64,
     pub bytes_returned: u64,
+    pub tokens_returned: u64,
 }
 
 #[cfg(test)]
@@ -361,6 +517,35 @@ mod tests {
         assert!(tracker.latency_stats("unknown").is_none());
     }
 
+    #[test]
+    fn test_error_counts_per_backend() {
+        let tracker = CallTracker::new();
+        tracker.record("tool_a", "b1", Duration::from_millis(1), true);
+        tracker.record("tool_a", "b1", Duration::from_millis(1), false);
+        tracker.record("tool_b", "b1", Duration::from_millis(1), false);
+        tracker.record("tool_c", "b2", Duration::from_millis(1), true);
+
+        assert_eq!(tracker.error_count("b1"), 2);
+        assert_eq!(tracker.error_count("b2"), 0);
+        assert_eq!(tracker.call_count("b1"), 3);
+        assert_eq!(tracker.call_count("b3"), 0);
+        assert_eq!(tracker.latency_stats("b1").unwrap().error_count, 2);
+    }
+
+    #[test]
+    fn test_error_kind_counts() {
+        let tracker = CallTracker::new();
+        assert!(tracker.session_stats().errors_by_kind.is_empty());
+
+        tracker.record_error_kind("timeout");
+        tracker.record_error_kind("timeout");
+        tracker.record_error_kind("tool_error");
+
+        let stats = tracker.session_stats();
+        assert_eq!(stats.errors_by_kind.get("timeout"), Some(&2));
+        assert_eq!(stats.errors_by_kind.get("tool_error"), Some(&1));
+    }
+
     #[tokio::test]
     async fn test_concurrent_recording() {
         use std::sync::Arc;
@@ -525,6 +710,50 @@ mod tests {
         assert_eq!(stats.estimated_tokens_saved, 2475);
     }
 
+    #[test]
+    fn test_record_output_counts_tokens() {
+        let tracker = CallTracker::new();
+        let size = |bytes, tokens| OutputSize { bytes, tokens };
+        tracker.record_output("call_tool_chain", size(400, 50), size(8_000, 3_000));
+        tracker.record_output("call_tool_chain", size(100, 10), size(100, 10));
+
+        let stats = tracker.session_stats();
+        assert_eq!(stats.total_bytes_returned, 500);
+        assert_eq!(stats.total_tokens_returned, 60);
+        assert_eq!(stats.total_tokens_processed, 3_010);
+        // Token estimates win over the bytes / 4 rule.
+        assert_eq!(stats.estimated_tokens_saved, 2_950);
+        assert_eq!(stats.per_tool[0].tokens_returned, 60);
+    }
+
+    #[test]
+    fn test_record_cache_hit_rate() {
+        let tracker = CallTracker::new();
+        tracker.record_cache("exa", CacheOutcome::Miss);
+        tracker.record_cache("exa", CacheOutcome::Hit);
+        tracker.record_cache("exa", CacheOutcome::Hit);
+        tracker.record_cache("exa", CacheOutcome::Coalesced);
+
+        let stats = tracker.session_stats();
+        let exa = &stats.cache_by_backend["exa"];
+        assert_eq!((exa.hits, exa.misses, exa.coalesced), (2, 1, 1));
+        assert_eq!(exa.hit_rate, 0.75);
+    }
+
+    #[test]
+    fn test_coercion_counts() {
+        let tracker = CallTracker::new();
+        assert!(tracker.session_stats().coercions_by_kind.is_empty());
+
+        tracker.record_coercion("string_to_number");
+        tracker.record_coercion("rename_property");
+        tracker.record_coercion("string_to_number");
+
+        let stats = tracker.session_stats();
+        assert_eq!(stats.coercions_by_kind.get("string_to_number"), Some(&2));
+        assert_eq!(stats.coercions_by_kind.get("rename_property"), Some(&1));
+    }
+
     #[test]
     fn test_session_stats_no_data() {
         let tracker = CallTracker::new();